@@ -14,6 +14,7 @@ fn main() -> Result<()> {
         color: "#f5f5f5".to_string(),
         image: None,
         radius: Some(Radius::Single(20.0)),
+        ..Default::default()
     };
     generator.add_background(background);
 
@@ -0,0 +1,361 @@
+//! Animated posters, encoded as GIF or APNG.
+//!
+//! An [`Animation`] keyframes the opacity, offset and rotation of elements,
+//! picked by their `id`, over a duration:
+//!
+//! ```json
+//! "animation": {
+//!     "duration_ms": 2000,
+//!     "fps": 20,
+//!     "tracks": [
+//!         { "element": "title", "property": "opacity", "keyframes": [{ "time_ms": 0, "value": 0 }, { "time_ms": 800, "value": 1 }] },
+//!         { "element": "title", "property": "translate_y", "easing": "ease_out",
+//!           "keyframes": [{ "time_ms": 0, "value": 40 }, { "time_ms": 800, "value": 0 }] }
+//!     ]
+//! }
+//! ```
+//!
+//! [`PosterGenerator::generate_animation`](crate::PosterGenerator::generate_animation)
+//! renders the frames into one file, and
+//! [`PosterGenerator::generate_frames`](crate::PosterGenerator::generate_frames)
+//! hands them out one by one for video pipelines. The other methods render the
+//! poster without the animation.
+
+use crate::{encode_png, PosterError, PosterGenerator, Result};
+use serde::{Deserialize, Serialize};
+use skia_safe::{AlphaType, Canvas, ColorType, ImageInfo, Rect, Surface};
+use std::io::Write;
+
+/// Keyframed element properties and the timing of the frames.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Animation {
+    /// Length of the animation in milliseconds.
+    pub duration_ms: u32,
+    /// Frames per second, from 1 to 50. GIF frame delays are whole hundredths
+    /// of a second, so 10, 20, 25 and 50 play back most accurately.
+    #[serde(default = "default_fps")]
+    pub fps: u32,
+    /// How many times the animation plays; 0 (default) loops forever.
+    #[serde(default)]
+    pub loop_count: u16,
+    /// Property animations.
+    pub tracks: Vec<Track>,
+}
+
+/// The keyframes of one property of one element.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Track {
+    /// `id` of the animated element.
+    pub element: String,
+    /// Animated property.
+    pub property: AnimatedProperty,
+    /// Values at points in time; the value is held before the first and after the last.
+    pub keyframes: Vec<Keyframe>,
+    /// How values change between keyframes.
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+/// A value of a property at a point in time.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    /// Time from the start of the animation in milliseconds.
+    pub time_ms: u32,
+    /// Value of the property.
+    pub value: f32,
+}
+
+/// Element property changed by a [`Track`].
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimatedProperty {
+    /// Opacity from 0 (hidden) to 1 (as designed).
+    Opacity,
+    /// Horizontal offset from the element's position, in pixels.
+    TranslateX,
+    /// Vertical offset from the element's position, in pixels.
+    TranslateY,
+    /// Rotation around the center of the element's bounds, in degrees clockwise.
+    Rotation,
+}
+
+/// How a value changes between two keyframes.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    /// At a constant rate (default).
+    #[default]
+    Linear,
+    /// Starting slowly.
+    EaseIn,
+    /// Ending slowly.
+    EaseOut,
+    /// Starting and ending slowly.
+    EaseInOut,
+}
+
+/// File format of an animated poster.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimationFormat {
+    /// Animated GIF, playable everywhere but limited to 256 colors per frame.
+    Gif,
+    /// Animated PNG, with full color and transparency (default).
+    #[default]
+    Apng,
+}
+
+/// Encoding of the frames returned by [`PosterGenerator::generate_frames`].
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FrameFormat {
+    /// Unpremultiplied RGBA pixels, row by row (default), e.g. for ffmpeg's `rawvideo` input.
+    #[default]
+    Rgba,
+    /// A PNG file per frame.
+    Png,
+}
+
+fn default_fps() -> u32 {
+    15
+}
+
+// Number of frames covering the duration, at least one
+fn frame_count(duration_ms: u32, fps: u32) -> u32 {
+    (duration_ms as u64 * fps as u64).div_ceil(1000).max(1) as u32
+}
+
+impl Animation {
+    /// Number of frames rendered, at least one.
+    pub fn frame_count(&self) -> u32 {
+        frame_count(self.duration_ms, self.fps())
+    }
+
+    // Frame rate within the range GIF delays can express
+    pub(crate) fn fps(&self) -> u32 {
+        self.fps.clamp(1, 50)
+    }
+
+    // Transform of an element at a point in time, if it is animated
+    pub(crate) fn state_at(&self, id: &str, time_ms: f32) -> Option<AnimatedState> {
+        let mut state: Option<AnimatedState> = None;
+        for track in self.tracks.iter().filter(|track| track.element == id) {
+            let Some(value) = track.value_at(time_ms) else {
+                continue;
+            };
+            let state = state.get_or_insert_with(AnimatedState::default);
+            match track.property {
+                AnimatedProperty::Opacity => state.opacity = value.clamp(0.0, 1.0),
+                AnimatedProperty::TranslateX => state.translate_x = value,
+                AnimatedProperty::TranslateY => state.translate_y = value,
+                AnimatedProperty::Rotation => state.rotation = value,
+            }
+        }
+        state
+    }
+}
+
+impl Track {
+    // Value between the keyframes around the time, or None without keyframes
+    fn value_at(&self, time_ms: f32) -> Option<f32> {
+        let mut keyframes = self.keyframes.clone();
+        keyframes.sort_by_key(|keyframe| keyframe.time_ms);
+        let first = keyframes.first()?;
+        if time_ms <= first.time_ms as f32 {
+            return Some(first.value);
+        }
+        for pair in keyframes.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            if time_ms < to.time_ms as f32 {
+                let progress = (time_ms - from.time_ms as f32) / (to.time_ms - from.time_ms) as f32;
+                return Some(from.value + (to.value - from.value) * self.easing.apply(progress));
+            }
+        }
+        keyframes.last().map(|keyframe| keyframe.value)
+    }
+}
+
+impl Easing {
+    // Eased progress for a linear progress from 0 to 1
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+// Animated properties of an element in one frame
+pub(crate) struct AnimatedState {
+    opacity: f32,
+    translate_x: f32,
+    translate_y: f32,
+    rotation: f32,
+}
+
+impl Default for AnimatedState {
+    fn default() -> Self {
+        Self { opacity: 1.0, translate_x: 0.0, translate_y: 0.0, rotation: 0.0 }
+    }
+}
+
+impl AnimatedState {
+    // Run `render` with the element moved, rotated and faded
+    pub(crate) fn render(&self, canvas: &Canvas, bounds: Option<Rect>, render: impl FnOnce() -> Result<()>) -> Result<()> {
+        if self.opacity <= 0.0 {
+            return Ok(());
+        }
+        let count = canvas.save();
+        canvas.translate((self.translate_x, self.translate_y));
+        if self.rotation != 0.0 {
+            let center = bounds.map(|bounds| bounds.center());
+            canvas.rotate(self.rotation, center);
+        }
+        if self.opacity < 1.0 {
+            canvas.save_layer_alpha_f(None, self.opacity);
+        }
+        let result = render();
+        canvas.restore_to_count(count);
+        result
+    }
+}
+
+// Frames of unpremultiplied RGBA pixels, all of the same size
+pub(crate) struct Frames {
+    width: u32,
+    height: u32,
+    fps: u32,
+    loop_count: u16,
+    pixels: Vec<Vec<u8>>,
+}
+
+impl Frames {
+    pub(crate) fn new(animation: &Animation, width: u32, height: u32, pixels: Vec<Vec<u8>>) -> Self {
+        Self { width, height, fps: animation.fps(), loop_count: animation.loop_count, pixels }
+    }
+
+    pub(crate) fn encode(self, format: AnimationFormat) -> Result<Vec<u8>> {
+        let _span = tracing::debug_span!("encode", ?format, frames = self.pixels.len()).entered();
+        let mut out = Vec::new();
+        match format {
+            AnimationFormat::Gif => self.write_gif(&mut out)?,
+            AnimationFormat::Apng => self.write_apng(&mut out)?,
+        }
+        Ok(out)
+    }
+
+    fn write_gif<W: Write>(mut self, writer: W) -> Result<()> {
+        let (Ok(width), Ok(height)) = (u16::try_from(self.width), u16::try_from(self.height)) else {
+            return Err(PosterError::Encode(format!("{}x{} is too large for a GIF", self.width, self.height)));
+        };
+        let gif_error = |err: gif::EncodingError| PosterError::Encode(format!("Failed to encode GIF: {}", err));
+        let mut encoder = gif::Encoder::new(writer, width, height, &[]).map_err(gif_error)?;
+        // The loop extension counts repeats after the first play, and a count of 0
+        // means forever, so a single play has no extension at all
+        match self.loop_count {
+            0 => encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_error)?,
+            1 => {}
+            count => encoder.set_repeat(gif::Repeat::Finite(count - 1)).map_err(gif_error)?,
+        }
+        // Delays are in hundredths of a second
+        let delay = (100 / self.fps).max(1) as u16;
+        for pixels in &mut self.pixels {
+            // Speed 10 is the quantizer's recommended balance of speed and quality
+            let mut frame = gif::Frame::from_rgba_speed(width, height, pixels, 10);
+            frame.delay = delay;
+            encoder.write_frame(&frame).map_err(gif_error)?;
+        }
+        Ok(())
+    }
+
+    fn write_apng<W: Write>(self, writer: W) -> Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(self.pixels.len() as u32, self.loop_count as u32).map_err(crate::png_error)?;
+        encoder.set_frame_delay(1, self.fps as u16).map_err(crate::png_error)?;
+        let mut writer = encoder.write_header().map_err(crate::png_error)?;
+        for pixels in &self.pixels {
+            writer.write_image_data(pixels).map_err(crate::png_error)?;
+        }
+        writer.finish().map_err(crate::png_error)
+    }
+}
+
+/// Frames of a poster's animation, rendered one at a time as the iterator advances.
+///
+/// Returned by [`PosterGenerator::generate_frames`]. Rendering stops after the first error.
+pub struct FrameSequence<'a> {
+    generator: &'a mut PosterGenerator,
+    surface: Option<Surface>,
+    format: FrameFormat,
+    fps: u32,
+    frame: u32,
+    count: u32,
+}
+
+impl<'a> FrameSequence<'a> {
+    pub(crate) fn new(generator: &'a mut PosterGenerator, fps: u32, duration_ms: u32, format: FrameFormat) -> Self {
+        let fps = fps.max(1);
+        Self { generator, surface: None, format, fps, frame: 0, count: frame_count(duration_ms, fps) }
+    }
+
+    fn render(&mut self, time_ms: f32) -> Result<Vec<u8>> {
+        if self.surface.is_none() {
+            self.surface = Some(self.generator.create_surface()?);
+        }
+        let surface = self.surface.as_mut().expect("the surface was just created");
+        self.generator.time_ms = Some(time_ms);
+        let generator = &*self.generator;
+        generator.draw_supersampled(surface, |canvas| generator.draw(canvas, None, |_| false, false))?;
+
+        match self.format {
+            FrameFormat::Png => encode_png(surface),
+            FrameFormat::Rgba => {
+                let size = surface.image_info().dimensions();
+                let info = ImageInfo::new(size, ColorType::RGBA8888, AlphaType::Unpremul, None);
+                let row_bytes = surface.width() as usize * 4;
+                let mut pixels = vec![0u8; row_bytes * surface.height() as usize];
+                if !surface.read_pixels(&info, &mut pixels, row_bytes, (0, 0)) {
+                    return Err(PosterError::RenderError("Failed to read frame pixels".to_string()));
+                }
+                Ok(pixels)
+            }
+        }
+    }
+}
+
+impl Iterator for FrameSequence<'_> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.frame >= self.count {
+            return None;
+        }
+        let time_ms = self.frame as f32 * 1000.0 / self.fps as f32;
+        self.frame += 1;
+        let result = self.render(time_ms);
+        if result.is_err() {
+            self.frame = self.count;
+        }
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.count - self.frame) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for FrameSequence<'_> {}
+
+// The generator goes back to rendering still posters
+impl Drop for FrameSequence<'_> {
+    fn drop(&mut self) {
+        self.generator.time_ms = None;
+        self.generator.images = None;
+    }
+}
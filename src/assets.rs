@@ -0,0 +1,201 @@
+//! Loading of image and font sources.
+//!
+//! Every `src`, `image`, `icon` and `font_file` value is turned into bytes by an
+//! [`AssetResolver`]. The [`DefaultResolver`] reads files, base64 data URLs and
+//! (with the default `http` feature) `http(s)://` URLs; applications can plug in
+//! their own resolver to load assets from object storage, a database, or a test
+//! fixture:
+//!
+//! ```
+//! use poster_generator::assets::AssetResolver;
+//! use poster_generator::PosterGenerator;
+//! use std::collections::HashMap;
+//! use std::io;
+//!
+//! struct Fixtures(HashMap<String, Vec<u8>>);
+//!
+//! impl AssetResolver for Fixtures {
+//!     fn resolve(&self, src: &str) -> io::Result<Vec<u8>> {
+//!         self.0.get(src).cloned().ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+//!     }
+//! }
+//!
+//! let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+//! generator.set_asset_resolver(Fixtures(HashMap::new()));
+//! ```
+//!
+//! Sources written as `asset://<name>` refer to data registered with
+//! [`PosterGenerator::register_asset`](crate::PosterGenerator::register_asset) and
+//! are looked up before the resolver is asked.
+//!
+//! Services rendering configs from untrusted clients should not let them read
+//! arbitrary files. Give the default resolver a [`SandboxedFileResolver`] to
+//! limit local reads to a set of asset directories, or to none at all:
+//!
+//! ```no_run
+//! use poster_generator::assets::{DefaultResolver, SandboxedFileResolver};
+//! use poster_generator::PosterGenerator;
+//!
+//! let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+//! generator.set_asset_resolver(DefaultResolver {
+//!     sandbox: Some(SandboxedFileResolver::new(["/srv/poster-assets"]).unwrap()),
+//!     ..Default::default()
+//! });
+//! ```
+
+use base64::{engine::general_purpose, Engine};
+use std::io;
+#[cfg(feature = "http")]
+use std::io::Read;
+use std::path::PathBuf;
+
+/// Turns an asset source (path, URL or id) into its bytes.
+pub trait AssetResolver: Send + Sync {
+    /// Returns the bytes of the asset at `src`.
+    fn resolve(&self, src: &str) -> io::Result<Vec<u8>>;
+}
+
+/// Reads sources as local file paths.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileResolver;
+
+impl AssetResolver for FileResolver {
+    fn resolve(&self, src: &str) -> io::Result<Vec<u8>> {
+        std::fs::read(src)
+    }
+}
+
+/// Reads local files, but only from inside a set of allowed directories.
+///
+/// Paths are resolved (including `..` components and symlinks) before they are
+/// checked, so traversal out of an allowed directory is rejected. With no
+/// directories, all local file reads are rejected.
+#[derive(Debug, Clone, Default)]
+pub struct SandboxedFileResolver {
+    roots: Vec<PathBuf>,
+}
+
+impl SandboxedFileResolver {
+    /// Creates a resolver allowing reads from the given directories, which must exist.
+    pub fn new<P: Into<PathBuf>>(roots: impl IntoIterator<Item = P>) -> io::Result<Self> {
+        let roots = roots
+            .into_iter()
+            .map(|root| root.into().canonicalize())
+            .collect::<io::Result<_>>()?;
+        Ok(Self { roots })
+    }
+
+    /// Creates a resolver that rejects every local file.
+    pub fn deny_all() -> Self {
+        Self::default()
+    }
+
+    /// The allowed directories, canonicalized.
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+}
+
+impl AssetResolver for SandboxedFileResolver {
+    fn resolve(&self, src: &str) -> io::Result<Vec<u8>> {
+        if self.roots.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "Local file access is disabled"));
+        }
+        let path = std::fs::canonicalize(src)?;
+        if !self.roots.iter().any(|root| path.starts_with(root)) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Path is outside the allowed asset directories",
+            ));
+        }
+        std::fs::read(path)
+    }
+}
+
+/// Decodes base64 data URLs (`data:image/png;base64,...`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DataUrlResolver;
+
+impl AssetResolver for DataUrlResolver {
+    fn resolve(&self, src: &str) -> io::Result<Vec<u8>> {
+        let (_, data) = src
+            .strip_prefix("data:")
+            .and_then(|rest| rest.split_once(','))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid base64 data URL"))?;
+        general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid base64 data: {}", e)))
+    }
+}
+
+/// Downloads `http://` and `https://` URLs.
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, Copy)]
+pub struct HttpResolver {
+    /// Largest response body accepted, in bytes.
+    pub max_size: u64,
+}
+
+#[cfg(feature = "http")]
+impl Default for HttpResolver {
+    fn default() -> Self {
+        Self { max_size: 50 * 1024 * 1024 }
+    }
+}
+
+#[cfg(feature = "http")]
+impl AssetResolver for HttpResolver {
+    fn resolve(&self, src: &str) -> io::Result<Vec<u8>> {
+        let response = ureq::get(src).call().map_err(io::Error::other)?;
+        let mut bytes = Vec::new();
+        response.into_reader().take(self.max_size + 1).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 > self.max_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Response is larger than {} bytes", self.max_size),
+            ));
+        }
+        Ok(bytes)
+    }
+}
+
+/// Resolver used unless another one is set: data URLs, then HTTP(S) URLs, then files.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultResolver {
+    /// Resolver for `http://` and `https://` URLs.
+    #[cfg(feature = "http")]
+    pub http: HttpResolver,
+    /// Restricts local file reads to some directories. Any file can be read if `None`.
+    pub sandbox: Option<SandboxedFileResolver>,
+}
+
+impl AssetResolver for DefaultResolver {
+    fn resolve(&self, src: &str) -> io::Result<Vec<u8>> {
+        if src.starts_with("data:") {
+            DataUrlResolver.resolve(src)
+        } else if src.starts_with("http://") || src.starts_with("https://") {
+            self.resolve_http(src)
+        } else if let Some(sandbox) = &self.sandbox {
+            sandbox.resolve(src)
+        } else {
+            FileResolver.resolve(src)
+        }
+    }
+}
+
+impl DefaultResolver {
+    #[cfg(feature = "http")]
+    fn resolve_http(&self, src: &str) -> io::Result<Vec<u8>> {
+        self.http.resolve(src)
+    }
+
+    #[cfg(not(feature = "http"))]
+    fn resolve_http(&self, _src: &str) -> io::Result<Vec<u8>> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "HTTP sources need the `http` feature"))
+    }
+}
+
+// Short description of a source for error messages, leaving out inline data
+pub(crate) fn describe_source(src: &str) -> &str {
+    if src.starts_with("data:") { "base64 data URL" } else { src }
+}
@@ -0,0 +1,296 @@
+//! Badge (pill) element: a rounded box that sizes itself around its text.
+
+use crate::{
+    create_rounded_rect_path, draw_box_shadow, draw_text_line_improved, get_font_for_text_with_family,
+    is_rtl_text, load_image, measure_text_with_font, parse_color, BoxShadow, Element, MissingAssetPolicy, PosterElement,
+    Radius, RenderContext, Result, TextAlignType, TextContent, TextDirectionType, TextKind,
+};
+use serde::{Deserialize, Serialize};
+use skia_safe::{paint::Style as PaintStyle, Canvas, Font, Image, Paint, Rect};
+use tracing::warn;
+
+/// Badge configuration.
+///
+/// The pill is sized from its text, icon and padding, so labels of any length fit
+/// without measuring them by hand.
+///
+/// # Example
+///
+/// ```
+/// use poster_generator::{PosterGenerator, BadgeElement};
+///
+/// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+/// generator.add_badge(BadgeElement {
+///     text: "¥ 99".to_string(),
+///     x: 40.0,
+///     y: 40.0,
+///     font_size: 28.0,
+///     color: "#ffffff".to_string(),
+///     background_color: Some("#e53935".to_string()),
+///     bold: true,
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BadgeElement {
+    /// Text content of the badge.
+    pub text: String,
+    /// X-coordinate of the badge anchor: its left edge, center or right edge depending on `align`.
+    pub x: f32,
+    /// Y-coordinate of the top of the badge.
+    pub y: f32,
+    /// Font size in points.
+    #[serde(default = "default_badge_font_size")]
+    pub font_size: f32,
+    /// Text color in hex format.
+    #[serde(default = "default_badge_color")]
+    pub color: String,
+    /// Which edge of the badge `x` refers to.
+    #[serde(default = "default_badge_align")]
+    pub align: TextAlignType,
+    /// Optional fill color in hex format.
+    pub background_color: Option<String>,
+    /// Optional border color in hex format.
+    pub border_color: Option<String>,
+    /// Border width in pixels.
+    #[serde(default)]
+    pub border_width: f32,
+    /// Horizontal padding between the border and the content.
+    #[serde(default = "default_badge_padding_x")]
+    pub padding_x: f32,
+    /// Vertical padding between the border and the content.
+    #[serde(default = "default_badge_padding_y")]
+    pub padding_y: f32,
+    /// Corner radius. Defaults to half the badge height, giving a pill shape.
+    pub radius: Option<f32>,
+    /// Optional shadow under the badge.
+    #[serde(default)]
+    pub box_shadow: Option<BoxShadow>,
+    /// Optional icon drawn before the text: image file path or base64 data URL.
+    pub icon: Option<String>,
+    /// Size of the icon. Defaults to the font size.
+    pub icon_size: Option<f32>,
+    /// Space between the icon and the text.
+    #[serde(default = "default_badge_icon_gap")]
+    pub icon_gap: f32,
+    /// Whether to use bold font weight.
+    #[serde(default)]
+    pub bold: bool,
+    /// Optional font family name from system fonts.
+    pub font_family: Option<String>,
+    /// Optional font file path. Takes priority over font_family if both are specified.
+    pub font_file: Option<String>,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+    /// Missing asset policy for the icon and `font_file`, overriding the poster-wide policy.
+    /// The badge is drawn without its icon, or with the default font, unless the policy is `error`.
+    #[serde(default)]
+    pub on_missing_asset: Option<MissingAssetPolicy>,
+}
+
+impl Default for BadgeElement {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            x: 0.0,
+            y: 0.0,
+            font_size: default_badge_font_size(),
+            color: default_badge_color(),
+            align: default_badge_align(),
+            background_color: None,
+            border_color: None,
+            border_width: 0.0,
+            padding_x: default_badge_padding_x(),
+            padding_y: default_badge_padding_y(),
+            radius: None,
+            box_shadow: None,
+            icon: None,
+            icon_size: None,
+            icon_gap: default_badge_icon_gap(),
+            bold: false,
+            font_family: None,
+            font_file: None,
+            id: None,
+            z_index: None,
+            on_missing_asset: None,
+        }
+    }
+}
+
+fn default_badge_font_size() -> f32 {
+    16.0
+}
+
+fn default_badge_color() -> String {
+    "#000000".to_string()
+}
+
+fn default_badge_align() -> TextAlignType {
+    TextAlignType::Left
+}
+
+fn default_badge_padding_x() -> f32 {
+    12.0
+}
+
+fn default_badge_padding_y() -> f32 {
+    6.0
+}
+
+fn default_badge_icon_gap() -> f32 {
+    6.0
+}
+
+// Measured badge, shared by rendering and bounds
+struct BadgeLayout {
+    font: Font,
+    icon: Option<Image>,
+    rect: Rect,
+    text_width: f32,
+    icon_size: f32,
+}
+
+impl BadgeElement {
+    fn layout(&self, ctx: &RenderContext) -> Result<BadgeLayout> {
+        let policy = ctx.missing_asset_policy(self.on_missing_asset);
+
+        let font = match get_font_for_text_with_family(&self.text, self.font_size, self.bold, self.font_family.as_deref(), self.font_file.as_deref(), ctx) {
+            Ok(font) => font,
+            Err(err) if policy != MissingAssetPolicy::Error => {
+                warn!("Falling back to default font: {}", err);
+                get_font_for_text_with_family(&self.text, self.font_size, self.bold, self.font_family.as_deref(), None, ctx)?
+            }
+            Err(err) => return Err(err),
+        };
+
+        let icon = match self.icon.as_deref().map(|icon| load_image(icon, ctx)) {
+            Some(Ok(icon)) => Some(icon),
+            Some(Err(err)) if policy != MissingAssetPolicy::Error => {
+                warn!("Skipping missing badge icon: {}", err);
+                None
+            }
+            Some(Err(err)) => return Err(err),
+            None => None,
+        };
+
+        let (_, metrics) = font.metrics();
+        let text_height = metrics.descent - metrics.ascent;
+        let text_width = if self.text.is_empty() { 0.0 } else { measure_text_with_font(&self.text, &font).0 };
+        let icon_size = self.icon_size.unwrap_or(self.font_size);
+
+        let mut content_width = text_width;
+        let mut content_height = text_height;
+        if icon.is_some() {
+            content_width += icon_size + if text_width > 0.0 { self.icon_gap } else { 0.0 };
+            content_height = content_height.max(icon_size);
+        }
+
+        let width = content_width + self.padding_x * 2.0;
+        let height = content_height + self.padding_y * 2.0;
+        let left = match self.align {
+            TextAlignType::Left => self.x,
+            TextAlignType::Center => self.x - width / 2.0,
+            TextAlignType::Right => self.x - width,
+        };
+
+        Ok(BadgeLayout {
+            font,
+            icon,
+            rect: Rect::from_xywh(left, self.y, width, height),
+            text_width,
+            icon_size,
+        })
+    }
+}
+
+impl PosterElement for BadgeElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Badge(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let layout = self.layout(ctx)?;
+        let rect = layout.rect;
+        let radius = Radius::Single(self.radius.unwrap_or(rect.height() / 2.0));
+
+        if let Some(shadow) = &self.box_shadow {
+            draw_box_shadow(canvas, shadow, (rect.left, rect.top, rect.width(), rect.height()), Some(&radius), 0.0)?;
+        }
+
+        if let Some(background_color) = &self.background_color {
+            let mut paint = Paint::default();
+            paint.set_color(parse_color(background_color, "background_color")?);
+            paint.set_anti_alias(true);
+            let path = create_rounded_rect_path(rect.left, rect.top, rect.width(), rect.height(), &radius);
+            canvas.draw_path(&path, &paint);
+        }
+
+        if let (Some(border_color), true) = (&self.border_color, self.border_width > 0.0) {
+            let mut paint = Paint::default();
+            paint.set_color(parse_color(border_color, "border_color")?);
+            paint.set_anti_alias(true);
+            paint.set_style(PaintStyle::Stroke);
+            paint.set_stroke_width(self.border_width);
+            // Keep the stroke inside the badge
+            let inset = self.border_width / 2.0;
+            let path = create_rounded_rect_path(
+                rect.left + inset,
+                rect.top + inset,
+                rect.width() - self.border_width,
+                rect.height() - self.border_width,
+                &radius,
+            );
+            canvas.draw_path(&path, &paint);
+        }
+
+        // RTL badges put the icon after the text, on the right
+        let rtl = is_rtl_text(&self.text);
+        let center_y = rect.center_y();
+        let mut text_x = rect.left + self.padding_x;
+
+        if let Some(icon) = &layout.icon {
+            let icon_x = if rtl {
+                rect.right - self.padding_x - layout.icon_size
+            } else {
+                text_x += layout.icon_size + self.icon_gap;
+                rect.left + self.padding_x
+            };
+            let mut paint = Paint::default();
+            paint.set_anti_alias(true);
+            let dest = Rect::from_xywh(icon_x, center_y - layout.icon_size / 2.0, layout.icon_size, layout.icon_size);
+            canvas.draw_image_rect(icon, None, dest, &paint);
+        }
+
+        if layout.text_width > 0.0 {
+            let mut paint = Paint::default();
+            paint.set_color(parse_color(&self.color, "color")?);
+            paint.set_anti_alias(true);
+
+            let (_, metrics) = layout.font.metrics();
+            let baseline = center_y - (metrics.ascent + metrics.descent) / 2.0;
+            let direction = if rtl { TextDirectionType::Rtl } else { TextDirectionType::Ltr };
+            draw_text_line_improved(canvas, &self.text, text_x, baseline, &layout.font, &paint, &direction, &TextAlignType::Left);
+        }
+
+        Ok(())
+    }
+
+    fn bounds(&self, ctx: &RenderContext) -> Option<Rect> {
+        self.layout(ctx).ok().map(|layout| layout.rect)
+    }
+
+    fn text_content(&self) -> Option<TextContent> {
+        let direction = if is_rtl_text(&self.text) { TextDirectionType::Rtl } else { TextDirectionType::Ltr };
+        Some(TextContent { text: self.text.clone(), kind: TextKind::Text, direction, lang: None })
+    }
+}
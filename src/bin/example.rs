@@ -1,7 +1,7 @@
 use anyhow::Result;
 use poster_generator::{
-    BackgroundElement, Element, ImageElement, ObjectFit, PosterConfig, PosterGenerator, Radius,
-    TextAlignType, TextDirectionType, TextElement,
+    BackgroundElement, Element, ImageElement, MissingAssetPolicy, ObjectFit, PosterConfig,
+    PosterGenerator, Radius, TextAlignType, TextDirectionType, TextElement,
 };
 
 fn main() -> Result<()> {
@@ -17,6 +17,7 @@ fn main() -> Result<()> {
                 color: "#f5f5f5".to_string(),
                 image: None,
                 radius: Some(Radius::Single(20.0)),
+                ..Default::default()
             }),
             Element::Image(ImageElement {
                 src: "sample_image.jpg".to_string(), // 请替换为实际存在的图片路径
@@ -27,6 +28,8 @@ fn main() -> Result<()> {
                 radius: Some(Radius::Single(10.0)),
                 z_index: Some(1),
                 object_fit: ObjectFit::Cover,
+                alt: Some("Sample Image".to_string()),
+                ..Default::default()
             }),
             Element::Text(TextElement {
                 text: "使用 Skia Safe 的海报生成器".to_string(),
@@ -49,6 +52,7 @@ fn main() -> Result<()> {
                 width: None,
                 height: None,
                 direction: TextDirectionType::Ltr,
+                ..Default::default()
             }),
             Element::Text(TextElement {
                 text: "这是一个使用 Skia Safe 库实现的海报生成工具的示例，支持多行文本、图片、圆角等功能。".to_string(),
@@ -71,6 +75,7 @@ fn main() -> Result<()> {
                 width: None,
                 height: None,
                 direction: TextDirectionType::Ltr,
+                ..Default::default()
             }),
             Element::Text(TextElement {
                 text: "价格: 99.99".to_string(),
@@ -93,6 +98,7 @@ fn main() -> Result<()> {
                 width: None,
                 height: None,
                 direction: TextDirectionType::Ltr,
+                ..Default::default()
             }),
             // 添加一个RTL方向的文本元素(维吾尔语示例) - using custom font file
             Element::Text(TextElement {
@@ -116,16 +122,16 @@ fn main() -> Result<()> {
                 width: None,
                 height: None,
                 direction: TextDirectionType::Rtl, // 设置为RTL方向
+                ..Default::default()
             }),
         ],
+        // 图片缺失时绘制占位框，而不是整个渲染失败
+        on_missing_asset: MissingAssetPolicy::Placeholder,
+        ..Default::default()
     };
 
-    // 创建海报生成器
-    let mut generator =
-        PosterGenerator::new(config.width, config.height, config.background_color.clone());
-
-    // 设置元素
-    generator.set_elements(config.elements);
+    // 根据配置创建海报生成器（包括元素和缺失资源策略）
+    let generator = PosterGenerator::from_config(config);
 
     // 生成海报并保存
     println!("正在生成海报文件...");
@@ -0,0 +1,209 @@
+//! Binding element fields to a JSON data document.
+//!
+//! A config can carry the payload it is rendered for as `data`, and any field can
+//! take its value from it with a `{"$": path}` object, where `path` is a JSONPath
+//! expression. The template then doesn't depend on the payload's shape, and
+//! clients send their data as it is:
+//!
+//! ```json
+//! "data": { "user": { "name": "Ada", "avatar": "https://example.com/ada.png" }, "stats": [{ "value": 12 }, { "value": 30 }] },
+//! "elements": [
+//!     { "type": "text", "text": { "$": "$.user.name" }, "x": 40, "y": 80 },
+//!     { "type": "image", "src": { "$": "$.user.avatar", "default": "avatar.png" }, "x": 40, "y": 120, "width": 96, "height": 96 }
+//! ]
+//! ```
+//!
+//! Paths start at the document root `$` and select object members with `.name` or
+//! `['name']`, array items with `[index]` (negative indices count from the end) and
+//! all members or items with `.*` or `[*]`. A path with a wildcard binds an array
+//! of the values it selects, e.g. `$.stats[*].value` gives `[12, 30]`.
+//!
+//! Bound values keep their JSON type, except that numbers and booleans bound to a
+//! `text` field become strings. A path that selects nothing binds the `default`
+//! value, or fails without one.
+//!
+//! Bindings are resolved on the JSON config before it is deserialized.
+//! [`template::render_config`](crate::template::render_config), [`render_config`](crate::render_config)
+//! and the CLI do this through [`preprocess`](crate::preprocess) with the config's own `data`; [`bind`] takes the data separately:
+//!
+//! ```
+//! use poster_generator::binding;
+//! use serde_json::json;
+//!
+//! let mut config = json!({
+//!     "width": 800,
+//!     "height": 600,
+//!     "background_color": "#ffffff",
+//!     "elements": [{ "type": "text", "text": { "$": "$.order.items[0].name" }, "x": 40, "y": 80 }]
+//! });
+//! let data = json!({ "order": { "items": [{ "name": "Tea" }] } });
+//!
+//! binding::bind(&mut config, &data).unwrap();
+//! assert_eq!(config["elements"][0]["text"], "Tea");
+//! ```
+
+use serde_json::Value;
+
+/// Resolves the bindings of a JSON config against the config's `data`, which is
+/// removed from the config.
+///
+/// # Errors
+///
+/// Returns an error if a path is invalid, or selects nothing and the binding has
+/// no default.
+pub fn apply(config: &mut Value) -> serde_json::Result<()> {
+    let data = config.as_object_mut().and_then(|config| config.remove("data"));
+    bind(config, &data.unwrap_or_default())
+}
+
+/// Replaces every `{"$": path}` binding within a JSON value by the value `path`
+/// selects in `data`, in place.
+///
+/// # Errors
+///
+/// Returns an error if a path is invalid, or selects nothing and the binding has
+/// no default.
+pub fn bind(value: &mut Value, data: &Value) -> serde_json::Result<()> {
+    bind_field(value, None, data)
+}
+
+fn bind_field(value: &mut Value, key: Option<&str>, data: &Value) -> serde_json::Result<()> {
+    match value {
+        Value::Object(map) => match binding(map) {
+            Some((path, default)) => {
+                let bound = match query(data, path) {
+                    Ok(Some(bound)) => bound,
+                    Ok(None) => default.cloned().ok_or_else(|| {
+                        serde::de::Error::custom(format!("no value at '{}' in the data", path))
+                    })?,
+                    Err(message) => {
+                        return Err(serde::de::Error::custom(format!("invalid data path '{}': {}", path, message)));
+                    }
+                };
+                *value = match bound {
+                    // Text fields only take strings
+                    Value::Number(_) | Value::Bool(_) if key == Some("text") => Value::String(bound.to_string()),
+                    bound => bound,
+                };
+            }
+            None => {
+                for (key, item) in map.iter_mut() {
+                    bind_field(item, Some(key), data)?;
+                }
+            }
+        },
+        Value::Array(items) => {
+            for item in items {
+                bind_field(item, None, data)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// The path and default value of an object that is a binding
+fn binding(map: &serde_json::Map<String, Value>) -> Option<(&str, Option<&Value>)> {
+    let path = map.get("$")?.as_str()?;
+    if map.keys().any(|key| key != "$" && key != "default") {
+        return None;
+    }
+    Some((path, map.get("default")))
+}
+
+/// Selects values in a JSON document with a JSONPath expression.
+///
+/// Returns the selected value, an array of the selected values if the path has a
+/// wildcard, or `None` if a path without wildcards selects nothing.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the path is invalid.
+///
+/// # Example
+///
+/// ```
+/// use poster_generator::binding::query;
+/// use serde_json::json;
+///
+/// let data = json!({ "tags": [{ "name": "new" }, { "name": "sale" }] });
+/// assert_eq!(query(&data, "$.tags[-1].name"), Ok(Some(json!("sale"))));
+/// assert_eq!(query(&data, "$.tags[*].name"), Ok(Some(json!(["new", "sale"]))));
+/// ```
+pub fn query(data: &Value, path: &str) -> Result<Option<Value>, String> {
+    let segments = parse_path(path)?;
+    let wildcard = segments.contains(&Segment::Wildcard);
+
+    let mut nodes = vec![data];
+    for segment in &segments {
+        nodes = nodes
+            .into_iter()
+            .flat_map(|node| -> Vec<&Value> {
+                match (segment, node) {
+                    (Segment::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+                    (Segment::Index(index), Value::Array(items)) => {
+                        let index = if *index < 0 { items.len() as i64 + index } else { *index };
+                        usize::try_from(index).ok().and_then(|index| items.get(index)).into_iter().collect()
+                    }
+                    (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+                    (Segment::Wildcard, Value::Array(items)) => items.iter().collect(),
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+    }
+
+    if wildcard {
+        Ok(Some(Value::Array(nodes.into_iter().cloned().collect())))
+    } else {
+        Ok(nodes.first().map(|&node| node.clone()))
+    }
+}
+
+// One step of a path
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(i64),
+    Wildcard,
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment>, String> {
+    let mut rest = path.trim().strip_prefix('$').ok_or("paths start with '$'")?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            if let Some(after) = after.strip_prefix('*') {
+                segments.push(Segment::Wildcard);
+                rest = after;
+                continue;
+            }
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err("expected a name after '.'".to_string());
+            }
+            segments.push(Segment::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or("missing ']'")?;
+            let inner = after[..end].trim();
+            let segment = if inner == "*" {
+                Segment::Wildcard
+            } else if let Some(name) = quoted(inner) {
+                Segment::Key(name.to_string())
+            } else {
+                Segment::Index(inner.parse().map_err(|_| format!("invalid index '{}'", inner))?)
+            };
+            segments.push(segment);
+            rest = &after[end + 1..];
+        } else {
+            return Err(format!("unexpected '{}'", rest.chars().next().unwrap_or_default()));
+        }
+    }
+    Ok(segments)
+}
+
+// The inside of a single or double quoted name
+fn quoted(s: &str) -> Option<&str> {
+    ['\'', '"'].into_iter().find_map(|quote| s.strip_prefix(quote)?.strip_suffix(quote))
+}
@@ -0,0 +1,196 @@
+//! [BlurHash](https://blurha.sh) placeholders for rendered posters.
+//!
+//! A BlurHash is a string of 20 to 30 characters that decodes to a blurred
+//! version of the image, so apps can show a placeholder with the poster's
+//! colors while the full image loads:
+//!
+//! ```no_run
+//! use poster_generator::PosterGenerator;
+//!
+//! # let generator: PosterGenerator = unimplemented!();
+//! let rendered = generator.generate_with_metadata().expect("Failed to render");
+//! let placeholder = rendered.blurhash().expect("Failed to decode the render");
+//! ```
+//!
+//! Hashes can also be drawn: an image source of the form `blurhash:<hash>` is
+//! decoded at the size of the image element, for placeholder compositions that
+//! carry no real photo:
+//!
+//! ```json
+//! { "type": "image", "src": "blurhash:LEHV6nWB2yk8pyo0adR*.7kCMdnj", "x": 0, "y": 0, "width": 800, "height": 450 }
+//! ```
+
+use crate::{decode_image, DecodeOptions, PosterError, Result};
+use skia_safe::{images, AlphaType, ColorType, Data, Image, ImageInfo};
+use std::f32::consts::PI;
+
+// Longest side of the thumbnail the hash is computed on; the hash only keeps a few
+// low frequencies, so more pixels would only make it slower
+const SAMPLE_SIZE: f32 = 32.0;
+
+const BASE83: &[u8; 83] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+/// Computes the BlurHash of PNG (or any other supported format) image data.
+///
+/// Uses 4 components along the longest side and 3 along the other, which suits
+/// most placeholders.
+///
+/// # Errors
+///
+/// Returns an error if the image can't be decoded.
+pub fn from_png(png: &[u8]) -> Result<String> {
+    let image = decode_image(Data::new_copy(png), &DecodeOptions::default())
+        .ok_or_else(|| PosterError::ImageLoadError("Failed to decode image for BlurHash".to_string()))?;
+    let (pixels, width, height) = crate::palette::thumbnail_rgba(&image, SAMPLE_SIZE)
+        .ok_or_else(|| PosterError::OutputError("Failed to scale image for BlurHash".to_string()))?;
+    let (components_x, components_y) = if width >= height { (4, 3) } else { (3, 4) };
+    Ok(encode(&pixels, width, height, components_x, components_y))
+}
+
+/// Computes the BlurHash of unpremultiplied RGBA pixels, with `components_x` by
+/// `components_y` components (each from 1 to 9, clamped).
+///
+/// `rgba` must hold `width * height` pixels. Alpha is ignored. The hash is slow to compute on large images, so scale them
+/// down to a few dozen pixels first.
+pub fn encode(rgba: &[u8], width: u32, height: u32, components_x: u32, components_y: u32) -> String {
+    let components_x = components_x.clamp(1, 9);
+    let components_y = components_y.clamp(1, 9);
+    let (width, height) = (width as usize, height as usize);
+    let linear: Vec<[f32; 3]> = rgba
+        .chunks_exact(4)
+        .take(width * height)
+        .map(|pixel| [srgb_to_linear(pixel[0]), srgb_to_linear(pixel[1]), srgb_to_linear(pixel[2])])
+        .collect();
+
+    // Cosine transform of the image, keeping only the lowest frequencies
+    let mut factors: Vec<[f32; 3]> = Vec::with_capacity((components_x * components_y) as usize);
+    for j in 0..components_y {
+        for i in 0..components_x {
+            let mut factor = [0.0f32; 3];
+            for y in 0..height {
+                let basis_y = (PI * j as f32 * y as f32 / height as f32).cos();
+                for x in 0..width {
+                    let basis = basis_y * (PI * i as f32 * x as f32 / width as f32).cos();
+                    for (sum, value) in factor.iter_mut().zip(linear[y * width + x]) {
+                        *sum += basis * value;
+                    }
+                }
+            }
+            let normalisation = if i == 0 && j == 0 { 1.0 } else { 2.0 };
+            let scale = normalisation / (width * height).max(1) as f32;
+            factors.push(factor.map(|value| value * scale));
+        }
+    }
+
+    let (dc, ac) = factors.split_first().expect("there is at least one component");
+    let mut hash = String::with_capacity(4 + 2 * factors.len());
+    push_base83(&mut hash, (components_x - 1) + (components_y - 1) * 9, 1);
+
+    let maximum = if ac.is_empty() {
+        push_base83(&mut hash, 0, 1);
+        1.0
+    } else {
+        let actual = ac.iter().flatten().fold(0.0f32, |max, value| max.max(value.abs()));
+        let quantised = (actual * 166.0 - 0.5).floor().clamp(0.0, 82.0) as u32;
+        push_base83(&mut hash, quantised, 1);
+        (quantised + 1) as f32 / 166.0
+    };
+
+    let dc_value = (linear_to_srgb(dc[0]) << 16) | (linear_to_srgb(dc[1]) << 8) | linear_to_srgb(dc[2]);
+    push_base83(&mut hash, dc_value, 4);
+    for factor in ac {
+        let quantise = |value: f32| (sign_pow(value / maximum, 0.5) * 9.0 + 9.5).floor().clamp(0.0, 18.0) as u32;
+        push_base83(&mut hash, quantise(factor[0]) * 19 * 19 + quantise(factor[1]) * 19 + quantise(factor[2]), 2);
+    }
+    hash
+}
+
+/// Decodes a BlurHash into `width` by `height` unpremultiplied RGBA pixels.
+///
+/// The hash only holds a few low frequencies, so a few dozen pixels are enough;
+/// scale the result up to the size it is shown at.
+///
+/// # Errors
+///
+/// Returns an error if the hash is malformed.
+pub fn decode(hash: &str, width: u32, height: u32) -> Result<Vec<u8>> {
+    let invalid = |message: &str| PosterError::ImageLoadError(format!("Invalid BlurHash '{}': {}", hash, message));
+    let digits = hash
+        .bytes()
+        .map(|c| BASE83.iter().position(|&digit| digit == c).map(|value| value as u32))
+        .collect::<Option<Vec<u32>>>()
+        .ok_or_else(|| invalid("not a base 83 string"))?;
+    if digits.len() < 6 {
+        return Err(invalid("too short"));
+    }
+    let number = |range: std::ops::Range<usize>| digits[range].iter().fold(0, |number, digit| number * 83 + digit);
+
+    let (components_x, components_y) = (digits[0] % 9 + 1, digits[0] / 9 + 1);
+    let count = (components_x * components_y) as usize;
+    if digits.len() != 4 + 2 * count {
+        let expected = format!("expected {} characters for {}x{} components", 4 + 2 * count, components_x, components_y);
+        return Err(invalid(&expected));
+    }
+    let maximum = (digits[1] + 1) as f32 / 166.0;
+
+    let dc = number(2..6);
+    let mut colors = vec![[dc >> 16, (dc >> 8) & 255, dc & 255].map(|channel| srgb_to_linear(channel as u8))];
+    for index in 1..count {
+        let ac = number(4 + 2 * index..6 + 2 * index);
+        let unquantise = |quantised: u32| sign_pow((quantised as f32 - 9.0) / 9.0, 2.0) * maximum;
+        colors.push([unquantise(ac / (19 * 19)), unquantise((ac / 19) % 19), unquantise(ac % 19)]);
+    }
+
+    let (width, height) = (width.max(1) as usize, height.max(1) as usize);
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let mut pixel = [0.0f32; 3];
+            for j in 0..components_y as usize {
+                let basis_y = (PI * j as f32 * y as f32 / height as f32).cos();
+                for i in 0..components_x as usize {
+                    let basis = basis_y * (PI * i as f32 * x as f32 / width as f32).cos();
+                    for (sum, value) in pixel.iter_mut().zip(colors[i + j * components_x as usize]) {
+                        *sum += basis * value;
+                    }
+                }
+            }
+            rgba.extend(pixel.map(|value| linear_to_srgb(value) as u8));
+            rgba.push(255);
+        }
+    }
+    Ok(rgba)
+}
+
+// Decode a hash into an image with the aspect ratio of a `width` by `height` box
+pub(crate) fn image(hash: &str, width: f32, height: f32) -> Result<Image> {
+    let scale = SAMPLE_SIZE / width.max(height).max(1.0);
+    let width = (width * scale).round().max(1.0) as u32;
+    let height = (height * scale).round().max(1.0) as u32;
+    let rgba = decode(hash, width, height)?;
+    let info = ImageInfo::new((width as i32, height as i32), ColorType::RGBA8888, AlphaType::Unpremul, None);
+    images::raster_from_data(&info, Data::new_copy(&rgba), width as usize * 4)
+        .ok_or_else(|| PosterError::ImageLoadError("Failed to create BlurHash image".to_string()))
+}
+
+fn push_base83(hash: &mut String, value: u32, length: u32) {
+    for i in 1..=length {
+        let digit = (value / 83u32.pow(length - i)) % 83;
+        hash.push(BASE83[digit as usize] as char);
+    }
+}
+
+fn srgb_to_linear(value: u8) -> f32 {
+    let v = value as f32 / 255.0;
+    if v <= 0.04045 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(value: f32) -> u32 {
+    let v = value.clamp(0.0, 1.0);
+    let srgb = if v <= 0.003_130_8 { v * 12.92 } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 };
+    (srgb * 255.0 + 0.5) as u32
+}
+
+fn sign_pow(value: f32, exponent: f32) -> f32 {
+    value.abs().powf(exponent).copysign(value)
+}
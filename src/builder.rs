@@ -0,0 +1,554 @@
+//! Fluent builders for poster elements.
+//!
+//! Builders start from the element's defaults, so only the fields that matter
+//! need to be set:
+//!
+//! ```
+//! use poster_generator::{PosterGenerator, TextElement, TextAlignType};
+//!
+//! let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+//! generator.add_text(
+//!     TextElement::builder()
+//!         .text("Hello, World!")
+//!         .position(400.0, 300.0)
+//!         .font_size(48.0)
+//!         .align(TextAlignType::Center)
+//!         .bold(true)
+//!         .build(),
+//! );
+//! ```
+
+use crate::{
+    BackgroundElement, BackgroundRepeat, BaselineType, BoxShadow, ColorAdjustments, CropRect, Gradient, Highlight,
+    ImageElement, Insets, MissingAssetPolicy, ObjectFit, OverflowType, Pattern, PositionMode, Radius, ShaperType,
+    TextAlignType, TextDirectionType, TextElement, TextTransform, VerticalAlignType,
+};
+
+impl From<f32> for Radius {
+    fn from(radius: f32) -> Self {
+        Radius::Single(radius)
+    }
+}
+
+impl From<[f32; 4]> for Radius {
+    fn from(corners: [f32; 4]) -> Self {
+        Radius::Multiple(corners)
+    }
+}
+
+impl From<f32> for Insets {
+    fn from(inset: f32) -> Self {
+        Insets::Single(inset)
+    }
+}
+
+impl From<[f32; 4]> for Insets {
+    fn from(sides: [f32; 4]) -> Self {
+        Insets::Multiple(sides)
+    }
+}
+
+impl TextElement {
+    /// Creates a builder for a text element.
+    pub fn builder() -> TextElementBuilder {
+        TextElementBuilder::default()
+    }
+}
+
+/// Builder for [`TextElement`].
+#[derive(Debug, Clone, Default)]
+pub struct TextElementBuilder {
+    element: TextElement,
+}
+
+impl TextElementBuilder {
+    /// Sets the text content.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.element.text = text.into();
+        self
+    }
+
+    /// Sets the anchor point (`y` is the baseline of the first line).
+    pub fn position(mut self, x: f32, y: f32) -> Self {
+        self.element.x = x;
+        self.element.y = y;
+        self
+    }
+
+    /// Sets the font size in points.
+    pub fn font_size(mut self, font_size: f32) -> Self {
+        self.element.font_size = font_size;
+        self
+    }
+
+    /// Sets the text color in hex format.
+    pub fn color(mut self, color: impl Into<String>) -> Self {
+        self.element.color = color.into();
+        self
+    }
+
+    /// Fills the glyphs with an image (file path, base64 data URL or `asset://` name).
+    pub fn fill_image(mut self, src: impl Into<String>) -> Self {
+        self.element.fill_image = Some(src.into());
+        self
+    }
+
+    /// Fills the glyphs with a gradient instead of the text color.
+    pub fn fill_gradient(mut self, gradient: Gradient) -> Self {
+        self.element.fill_gradient = Some(gradient);
+        self
+    }
+
+    /// Adds film grain to the glyph fill, from 0 (none) to 1.
+    pub fn fill_noise(mut self, strength: f32) -> Self {
+        self.element.fill_noise = strength;
+        self
+    }
+
+    /// Sets the text alignment.
+    pub fn align(mut self, align: TextAlignType) -> Self {
+        self.element.align = align;
+        self
+    }
+
+    /// Sets the font family.
+    pub fn font_family(mut self, family: impl Into<String>) -> Self {
+        self.element.font_family = Some(family.into());
+        self
+    }
+
+    /// Sets the font file, which takes priority over the font family.
+    pub fn font_file(mut self, path: impl Into<String>) -> Self {
+        self.element.font_file = Some(path.into());
+        self
+    }
+
+    /// Sets the maximum width for word wrapping.
+    pub fn max_width(mut self, max_width: f32) -> Self {
+        self.element.max_width = Some(max_width);
+        self
+    }
+
+    /// Sets the line height multiplier.
+    pub fn line_height(mut self, line_height: f32) -> Self {
+        self.element.line_height = line_height;
+        self
+    }
+
+    /// Sets an absolute line height in pixels, overriding the multiplier.
+    pub fn line_height_px(mut self, line_height: f32) -> Self {
+        self.element.line_height_px = Some(line_height);
+        self
+    }
+
+    /// Sets what `y` is aligned to.
+    pub fn baseline(mut self, baseline: BaselineType) -> Self {
+        self.element.baseline = baseline;
+        self
+    }
+
+    /// Sets how `x` and `y` position the text.
+    pub fn position_mode(mut self, mode: PositionMode) -> Self {
+        self.element.position_mode = mode;
+        self
+    }
+
+    /// Sets the vertical alignment of the text inside its box, in box mode.
+    pub fn vertical_align(mut self, align: VerticalAlignType) -> Self {
+        self.element.vertical_align = align;
+        self
+    }
+
+    /// Sets what happens when the text doesn't fit in its box.
+    pub fn overflow(mut self, overflow: OverflowType) -> Self {
+        self.element.overflow = overflow;
+        self
+    }
+
+    /// Sets the maximum number of lines.
+    pub fn max_lines(mut self, max_lines: u32) -> Self {
+        self.element.max_lines = Some(max_lines);
+        self
+    }
+
+    /// Sets the text ending the last line when `max_lines` truncates, e.g. "… read more".
+    pub fn ellipsis(mut self, ellipsis: impl Into<String>) -> Self {
+        self.element.ellipsis = Some(ellipsis.into());
+        self
+    }
+
+    /// Sets the color of the ellipsis.
+    pub fn ellipsis_color(mut self, color: impl Into<String>) -> Self {
+        self.element.ellipsis_color = Some(color.into());
+        self
+    }
+
+    /// Sets the z-index.
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.element.z_index = Some(z_index);
+        self
+    }
+
+    /// Sets whether the text is bold.
+    pub fn bold(mut self, bold: bool) -> Self {
+        self.element.bold = bold;
+        self
+    }
+
+    /// Sets a prefix prepended to the text (e.g. a currency symbol).
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.element.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Sets the background color of the text box.
+    pub fn background_color(mut self, color: impl Into<String>) -> Self {
+        self.element.background_color = Some(color.into());
+        self
+    }
+
+    /// Sets a gradient fill for the text box, used instead of the background color.
+    pub fn background_gradient(mut self, gradient: Gradient) -> Self {
+        self.element.background_gradient = Some(gradient);
+        self
+    }
+
+    /// Sets the padding around the text box.
+    pub fn padding(mut self, padding: f32) -> Self {
+        self.element.padding = padding;
+        self
+    }
+
+    /// Sets the border radius of the text background.
+    pub fn border_radius(mut self, radius: impl Into<Radius>) -> Self {
+        self.element.border_radius = Some(radius.into());
+        self
+    }
+
+    /// Smooths the corners of the text background into its sides, from 0 to 1.
+    pub fn corner_smoothing(mut self, smoothing: f32) -> Self {
+        self.element.corner_smoothing = smoothing;
+        self
+    }
+
+    /// Sets a border drawn inside the text box.
+    pub fn border(mut self, width: f32, color: impl Into<String>) -> Self {
+        self.element.border_width = width;
+        self.element.border_color = Some(color.into());
+        self
+    }
+
+    /// Sets a gradient for the border, used instead of the border color.
+    pub fn border_gradient(mut self, gradient: Gradient) -> Self {
+        self.element.border_gradient = Some(gradient);
+        self
+    }
+
+    /// Sets a shadow under the text background.
+    pub fn box_shadow(mut self, shadow: BoxShadow) -> Self {
+        self.element.box_shadow = Some(shadow);
+        self
+    }
+
+    /// Sets a marker stripe behind some of the words.
+    pub fn highlight(mut self, highlight: Highlight) -> Self {
+        self.element.highlight = Some(highlight);
+        self
+    }
+
+    /// Sets a fixed size for the text box.
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.element.width = Some(width);
+        self.element.height = Some(height);
+        self
+    }
+
+    /// Sets the text direction.
+    pub fn direction(mut self, direction: TextDirectionType) -> Self {
+        self.element.direction = direction;
+        self
+    }
+
+    /// Sets whether `:shortcode:` emoji in the text are expanded.
+    pub fn expand_shortcodes(mut self, expand: bool) -> Self {
+        self.element.expand_shortcodes = expand;
+        self
+    }
+
+    /// Adds an OpenType feature setting, such as `"tnum"` or `"-liga"`.
+    pub fn font_feature(mut self, feature: impl Into<String>) -> Self {
+        self.element.font_features.push(feature.into());
+        self
+    }
+
+    /// Sets the case change applied to the text before layout.
+    pub fn text_transform(mut self, transform: TextTransform) -> Self {
+        self.element.text_transform = Some(transform);
+        self
+    }
+
+    /// Sets the language of the text as a BCP 47 tag (e.g. "ug").
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.element.lang = Some(lang.into());
+        self
+    }
+
+    /// Sets the engine used to shape the text.
+    pub fn shaper(mut self, shaper: ShaperType) -> Self {
+        self.element.shaper = shaper;
+        self
+    }
+
+    /// Sets what happens when the font file cannot be loaded.
+    pub fn on_missing_asset(mut self, policy: MissingAssetPolicy) -> Self {
+        self.element.on_missing_asset = Some(policy);
+        self
+    }
+
+    /// Builds the text element.
+    pub fn build(self) -> TextElement {
+        self.element
+    }
+}
+
+impl ImageElement {
+    /// Creates a builder for an image element.
+    pub fn builder() -> ImageElementBuilder {
+        ImageElementBuilder::default()
+    }
+}
+
+/// Builder for [`ImageElement`].
+///
+/// ```
+/// use poster_generator::{ImageElement, ObjectFit};
+///
+/// let image = ImageElement::builder()
+///     .src("photo.jpg")
+///     .position(50.0, 50.0)
+///     .size(300.0, 200.0)
+///     .radius(10.0)
+///     .object_fit(ObjectFit::Contain)
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ImageElementBuilder {
+    element: ImageElement,
+}
+
+impl ImageElementBuilder {
+    /// Sets the image source (file path, base64 data URL or `asset://` name).
+    pub fn src(mut self, src: impl Into<String>) -> Self {
+        self.element.src = src.into();
+        self
+    }
+
+    /// Sets the top-left corner of the image.
+    pub fn position(mut self, x: f32, y: f32) -> Self {
+        self.element.x = x;
+        self.element.y = y;
+        self
+    }
+
+    /// Sets the size of the image container.
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.element.width = width;
+        self.element.height = height;
+        self
+    }
+
+    /// Sets the corner radius.
+    pub fn radius(mut self, radius: impl Into<Radius>) -> Self {
+        self.element.radius = Some(radius.into());
+        self
+    }
+
+    /// Smooths the rounded corners into the sides, from 0 to 1.
+    pub fn corner_smoothing(mut self, smoothing: f32) -> Self {
+        self.element.corner_smoothing = smoothing;
+        self
+    }
+
+    /// Sets the z-index.
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.element.z_index = Some(z_index);
+        self
+    }
+
+    /// Draws only a region of the source image, in pixels of the image.
+    pub fn crop(mut self, x: f32, y: f32, width: f32, height: f32) -> Self {
+        self.element.crop = Some(CropRect { x, y, width, height });
+        self
+    }
+
+    /// Sets the scaling mode.
+    pub fn object_fit(mut self, object_fit: ObjectFit) -> Self {
+        self.element.object_fit = object_fit;
+        self
+    }
+
+    /// Sets the corner insets used by [`ObjectFit::NinePatch`].
+    pub fn insets(mut self, insets: impl Into<Insets>) -> Self {
+        self.element.insets = Some(insets.into());
+        self
+    }
+
+    /// Sets the clip shape: a preset name (e.g. "hexagon") or SVG path data.
+    pub fn clip_path(mut self, clip_path: impl Into<String>) -> Self {
+        self.element.clip_path = Some(clip_path.into());
+        self
+    }
+
+    /// Sets the color adjustments (brightness, contrast, saturation, hue rotation, inversion).
+    pub fn adjustments(mut self, adjustments: ColorAdjustments) -> Self {
+        self.element.adjustments = adjustments;
+        self
+    }
+
+    /// Pixelates the image into blocks of the given size.
+    pub fn pixelate(mut self, block_size: f32) -> Self {
+        self.element.pixelate = Some(block_size);
+        self
+    }
+
+    /// Sets whether the EXIF orientation of photos is ignored.
+    pub fn ignore_orientation(mut self, ignore: bool) -> Self {
+        self.element.ignore_orientation = ignore;
+        self
+    }
+
+    /// Mirrors the image horizontally and/or vertically within its box.
+    pub fn flip(mut self, horizontal: bool, vertical: bool) -> Self {
+        self.element.flip_horizontal = horizontal;
+        self.element.flip_vertical = vertical;
+        self
+    }
+
+    /// Selects the frame of an animated GIF, WebP or Lottie animation to draw.
+    pub fn frame(mut self, frame: usize) -> Self {
+        self.element.frame = Some(frame);
+        self
+    }
+
+    /// Selects the animation frame shown at a playback time.
+    pub fn time_ms(mut self, time_ms: u32) -> Self {
+        self.element.time_ms = Some(time_ms);
+        self
+    }
+
+    /// Selects the animation frame at a position from 0 (first frame) to 1 (last frame).
+    pub fn progress(mut self, progress: f32) -> Self {
+        self.element.progress = Some(progress);
+        self
+    }
+
+    /// Sets what happens when the image cannot be loaded.
+    pub fn on_missing_asset(mut self, policy: MissingAssetPolicy) -> Self {
+        self.element.on_missing_asset = Some(policy);
+        self
+    }
+
+    /// Sets the alternative text, whose initials are shown on placeholders.
+    pub fn alt(mut self, alt: impl Into<String>) -> Self {
+        self.element.alt = Some(alt.into());
+        self
+    }
+
+    /// Builds the image element.
+    pub fn build(self) -> ImageElement {
+        self.element
+    }
+}
+
+impl BackgroundElement {
+    /// Creates a builder for a background element.
+    pub fn builder() -> BackgroundElementBuilder {
+        BackgroundElementBuilder::default()
+    }
+}
+
+/// Builder for [`BackgroundElement`].
+#[derive(Debug, Clone, Default)]
+pub struct BackgroundElementBuilder {
+    element: BackgroundElement,
+}
+
+impl BackgroundElementBuilder {
+    /// Sets the background color.
+    pub fn color(mut self, color: impl Into<String>) -> Self {
+        self.element.color = color.into();
+        self
+    }
+
+    /// Sets the background image (file path or base64 data URL).
+    pub fn image(mut self, image: impl Into<String>) -> Self {
+        self.element.image = Some(image.into());
+        self
+    }
+
+    /// Sets the corner radius.
+    pub fn radius(mut self, radius: impl Into<Radius>) -> Self {
+        self.element.radius = Some(radius.into());
+        self
+    }
+
+    /// Sets a gradient filling the background instead of its color.
+    pub fn gradient(mut self, gradient: Gradient) -> Self {
+        self.element.gradient = Some(gradient);
+        self
+    }
+
+    /// Sets a pattern filling the background instead of its color or gradient.
+    pub fn pattern(mut self, pattern: Pattern) -> Self {
+        self.element.pattern = Some(pattern);
+        self
+    }
+
+    /// Repeats the background image instead of scaling it to cover the canvas.
+    pub fn repeat(mut self, repeat: BackgroundRepeat) -> Self {
+        self.element.repeat = Some(repeat);
+        self
+    }
+
+    /// Sets what happens when the background image cannot be loaded.
+    pub fn on_missing_asset(mut self, policy: MissingAssetPolicy) -> Self {
+        self.element.on_missing_asset = Some(policy);
+        self
+    }
+
+    /// Sets a color (with alpha) drawn over the background image.
+    pub fn overlay(mut self, color: impl Into<String>) -> Self {
+        self.element.overlay = Some(color.into());
+        self
+    }
+
+    /// Sets a gradient drawn over the background image, used instead of the overlay color.
+    pub fn overlay_gradient(mut self, gradient: Gradient) -> Self {
+        self.element.overlay_gradient = Some(gradient);
+        self
+    }
+
+    /// Sets a border drawn inside the edges of the canvas.
+    pub fn border(mut self, width: f32, color: impl Into<String>) -> Self {
+        self.element.border_width = width;
+        self.element.border_color = Some(color.into());
+        self
+    }
+
+    /// Sets the opacity of the whole layer, from 0.0 to 1.0.
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.element.opacity = opacity;
+        self
+    }
+
+    /// Sets the z-index, to place the layer among other backgrounds or elements.
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.element.z_index = Some(z_index);
+        self
+    }
+
+    /// Builds the background element.
+    pub fn build(self) -> BackgroundElement {
+        self.element
+    }
+}
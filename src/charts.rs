@@ -0,0 +1,655 @@
+//! Chart elements rendered natively.
+//!
+//! Charts are regular poster elements: they are layered by z-index and can be
+//! written in JSON configs and templates like any other element.
+
+use crate::{
+    draw_text_line_improved, get_font_for_text_with_family, is_rtl_text, parse_color, Element, PosterElement,
+    RenderContext, Result, TextAlignType, TextDirectionType,
+};
+use serde::{Deserialize, Serialize};
+use skia_safe::{
+    paint::{Cap as PaintCap, Join as PaintJoin, Style as PaintStyle},
+    Canvas, Font, Paint, Path as SkPath, PathFillType, Point, Rect,
+};
+
+/// Pie or donut chart configuration.
+///
+/// # Example
+///
+/// ```
+/// use poster_generator::{PosterGenerator, PieChartElement, PieSlice};
+///
+/// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+/// generator.add_pie_chart(PieChartElement {
+///     x: 400.0,
+///     y: 300.0,
+///     radius: 200.0,
+///     inner_radius: 100.0,
+///     slices: vec![
+///         PieSlice { value: 60.0, color: "#4caf50".to_string(), label: Some("Yes".to_string()) },
+///         PieSlice { value: 40.0, color: "#f44336".to_string(), label: Some("No".to_string()) },
+///     ],
+///     show_percentages: true,
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PieChartElement {
+    /// X-coordinate of the chart center.
+    pub x: f32,
+    /// Y-coordinate of the chart center.
+    pub y: f32,
+    /// Outer radius of the chart.
+    pub radius: f32,
+    /// Inner radius for donut charts. 0 draws a full pie.
+    #[serde(default)]
+    pub inner_radius: f32,
+    /// Chart slices, drawn clockwise in order.
+    pub slices: Vec<PieSlice>,
+    /// Angle in degrees where the first slice starts (-90 is twelve o'clock).
+    #[serde(default = "default_start_angle")]
+    pub start_angle: f32,
+    /// Whether to add each slice's share as a percentage to its label.
+    #[serde(default)]
+    pub show_percentages: bool,
+    /// Font size of the slice labels.
+    #[serde(default = "default_label_font_size")]
+    pub font_size: f32,
+    /// Color of the slice labels in hex format.
+    #[serde(default = "default_pie_label_color")]
+    pub label_color: String,
+    /// Optional font family for the slice labels.
+    pub font_family: Option<String>,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+impl Default for PieChartElement {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            radius: 0.0,
+            inner_radius: 0.0,
+            slices: Vec::new(),
+            start_angle: default_start_angle(),
+            show_percentages: false,
+            font_size: default_label_font_size(),
+            label_color: default_pie_label_color(),
+            font_family: None,
+            id: None,
+            z_index: None,
+        }
+    }
+}
+
+/// A slice of a pie or donut chart.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PieSlice {
+    /// Value of the slice. Slices are sized by their share of the total.
+    pub value: f32,
+    /// Fill color in hex format.
+    pub color: String,
+    /// Optional label drawn in the middle of the slice.
+    pub label: Option<String>,
+}
+
+/// Bar chart configuration.
+///
+/// The chart, including its category and value labels, is laid out inside the
+/// rectangle given by `x`, `y`, `width` and `height`.
+///
+/// # Example
+///
+/// ```
+/// use poster_generator::{PosterGenerator, BarChartElement};
+///
+/// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+/// generator.add_bar_chart(BarChartElement {
+///     x: 50.0,
+///     y: 50.0,
+///     width: 700.0,
+///     height: 500.0,
+///     categories: vec!["Mon".to_string(), "Tue".to_string(), "Wed".to_string()],
+///     values: vec![12.0, 30.0, 21.0],
+///     colors: vec!["#2196f3".to_string()],
+///     show_gridlines: true,
+///     show_values: true,
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BarChartElement {
+    /// X-coordinate of the chart area (top-left corner).
+    pub x: f32,
+    /// Y-coordinate of the chart area (top-left corner).
+    pub y: f32,
+    /// Width of the chart area.
+    pub width: f32,
+    /// Height of the chart area.
+    pub height: f32,
+    /// Category labels drawn under the bars.
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Bar values, one per category.
+    pub values: Vec<f32>,
+    /// Bar colors in hex format, cycled if there are fewer colors than bars.
+    #[serde(default = "default_bar_colors")]
+    pub colors: Vec<String>,
+    /// Top of the value scale. Defaults to the largest value.
+    pub max_value: Option<f32>,
+    /// Fraction of each category slot left empty between bars (0.0 - 0.9).
+    #[serde(default = "default_bar_gap")]
+    pub bar_gap: f32,
+    /// Whether to draw the baseline axis.
+    #[serde(default = "default_true")]
+    pub show_axis: bool,
+    /// Whether to draw horizontal gridlines.
+    #[serde(default)]
+    pub show_gridlines: bool,
+    /// Number of gridlines above the baseline.
+    #[serde(default = "default_gridlines")]
+    pub gridlines: u32,
+    /// Whether to draw each bar's value above it.
+    #[serde(default)]
+    pub show_values: bool,
+    /// Font size of the category and value labels.
+    #[serde(default = "default_label_font_size")]
+    pub font_size: f32,
+    /// Color of the category and value labels in hex format.
+    #[serde(default = "default_chart_text_color")]
+    pub label_color: String,
+    /// Color of the axis in hex format.
+    #[serde(default = "default_axis_color")]
+    pub axis_color: String,
+    /// Color of the gridlines in hex format.
+    #[serde(default = "default_grid_color")]
+    pub grid_color: String,
+    /// Optional font family for the labels.
+    pub font_family: Option<String>,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+impl Default for BarChartElement {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+            categories: Vec::new(),
+            values: Vec::new(),
+            colors: default_bar_colors(),
+            max_value: None,
+            bar_gap: default_bar_gap(),
+            show_axis: true,
+            show_gridlines: false,
+            gridlines: default_gridlines(),
+            show_values: false,
+            font_size: default_label_font_size(),
+            label_color: default_chart_text_color(),
+            axis_color: default_axis_color(),
+            grid_color: default_grid_color(),
+            font_family: None,
+            id: None,
+            z_index: None,
+        }
+    }
+}
+
+/// Sparkline configuration: a compact line chart of a numeric series.
+///
+/// The series is scaled to fill the bounding box, from its minimum at the bottom
+/// to its maximum at the top.
+///
+/// # Example
+///
+/// ```
+/// use poster_generator::{PosterGenerator, SparklineElement};
+///
+/// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+/// generator.add_sparkline(SparklineElement {
+///     x: 50.0,
+///     y: 400.0,
+///     width: 300.0,
+///     height: 80.0,
+///     values: vec![10.2, 10.8, 10.5, 11.9, 11.4, 12.6],
+///     fill: Some("#4caf5040".to_string()),
+///     show_min_max: true,
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SparklineElement {
+    /// X-coordinate of the bounding box (top-left corner).
+    pub x: f32,
+    /// Y-coordinate of the bounding box (top-left corner).
+    pub y: f32,
+    /// Width of the bounding box.
+    pub width: f32,
+    /// Height of the bounding box.
+    pub height: f32,
+    /// Data series, plotted left to right at equal spacing.
+    pub values: Vec<f32>,
+    /// Line color in hex format.
+    #[serde(default = "default_line_color")]
+    pub color: String,
+    /// Line width in pixels.
+    #[serde(default = "default_line_width")]
+    pub line_width: f32,
+    /// Whether to draw a smooth curve through the points instead of straight segments.
+    #[serde(default = "default_true")]
+    pub smooth: bool,
+    /// Optional color of the area between the line and the bottom of the box.
+    pub fill: Option<String>,
+    /// Whether to mark the lowest and highest points.
+    #[serde(default)]
+    pub show_min_max: bool,
+    /// Radius of the min/max markers.
+    #[serde(default = "default_marker_radius")]
+    pub marker_radius: f32,
+    /// Color of the minimum marker in hex format.
+    #[serde(default = "default_min_color")]
+    pub min_color: String,
+    /// Color of the maximum marker in hex format.
+    #[serde(default = "default_max_color")]
+    pub max_color: String,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+impl Default for SparklineElement {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+            values: Vec::new(),
+            color: default_line_color(),
+            line_width: default_line_width(),
+            smooth: true,
+            fill: None,
+            show_min_max: false,
+            marker_radius: default_marker_radius(),
+            min_color: default_min_color(),
+            max_color: default_max_color(),
+            id: None,
+            z_index: None,
+        }
+    }
+}
+
+fn default_start_angle() -> f32 {
+    -90.0
+}
+
+fn default_label_font_size() -> f32 {
+    14.0
+}
+
+fn default_pie_label_color() -> String {
+    "#ffffff".to_string()
+}
+
+fn default_bar_colors() -> Vec<String> {
+    vec!["#2196f3".to_string()]
+}
+
+fn default_bar_gap() -> f32 {
+    0.3
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_gridlines() -> u32 {
+    4
+}
+
+fn default_chart_text_color() -> String {
+    "#333333".to_string()
+}
+
+fn default_axis_color() -> String {
+    "#999999".to_string()
+}
+
+fn default_grid_color() -> String {
+    "#e0e0e0".to_string()
+}
+
+fn default_line_color() -> String {
+    "#2196f3".to_string()
+}
+
+fn default_line_width() -> f32 {
+    2.0
+}
+
+fn default_marker_radius() -> f32 {
+    3.0
+}
+
+fn default_min_color() -> String {
+    "#f44336".to_string()
+}
+
+fn default_max_color() -> String {
+    "#4caf50".to_string()
+}
+
+impl PosterElement for PieChartElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::PieChart(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let total: f32 = self.slices.iter().map(|slice| slice.value.max(0.0)).sum();
+        if total <= 0.0 || self.radius <= 0.0 {
+            return Ok(());
+        }
+
+        let (cx, cy) = (self.x, self.y);
+        let inner_radius = self.inner_radius.clamp(0.0, self.radius);
+        let outer_oval = Rect::new(cx - self.radius, cy - self.radius, cx + self.radius, cy + self.radius);
+        let inner_oval = Rect::new(cx - inner_radius, cy - inner_radius, cx + inner_radius, cy + inner_radius);
+
+        let mut labels = Vec::new();
+        let mut angle = self.start_angle;
+
+        for (i, slice) in self.slices.iter().enumerate() {
+            let value = slice.value.max(0.0);
+            if value == 0.0 {
+                continue;
+            }
+            let sweep = value / total * 360.0;
+
+            let mut path = SkPath::new();
+            if sweep >= 360.0 {
+                // A single slice covers the whole chart, which an arc can't describe
+                path.add_circle((cx, cy), self.radius, None);
+                if inner_radius > 0.0 {
+                    path.add_circle((cx, cy), inner_radius, None);
+                    path.set_fill_type(PathFillType::EvenOdd);
+                }
+            } else if inner_radius > 0.0 {
+                path.arc_to(outer_oval, angle, sweep, true);
+                path.arc_to(inner_oval, angle + sweep, -sweep, false);
+                path.close();
+            } else {
+                path.move_to((cx, cy));
+                path.arc_to(outer_oval, angle, sweep, false);
+                path.close();
+            }
+
+            let mut paint = Paint::default();
+            paint.set_color(parse_color(&slice.color, &format!("slices[{}].color", i))?);
+            paint.set_anti_alias(true);
+            canvas.draw_path(&path, &paint);
+
+            let percentage = format!("{:.0}%", value / total * 100.0);
+            let label = match (&slice.label, self.show_percentages) {
+                (Some(label), true) => Some(format!("{} {}", label, percentage)),
+                (Some(label), false) => Some(label.clone()),
+                (None, true) => Some(percentage),
+                (None, false) => None,
+            };
+            if let Some(label) = label {
+                labels.push((label, angle + sweep / 2.0));
+            }
+
+            angle += sweep;
+        }
+
+        // Labels go on top of all slices, in the middle of the ring (or at 60% of a pie)
+        if !labels.is_empty() {
+            let font = get_font_for_text_with_family("", self.font_size, false, self.font_family.as_deref(), None, ctx)?;
+            let mut paint = Paint::default();
+            paint.set_color(parse_color(&self.label_color, "label_color")?);
+            paint.set_anti_alias(true);
+
+            let label_radius = if inner_radius > 0.0 {
+                (self.radius + inner_radius) / 2.0
+            } else {
+                self.radius * 0.6
+            };
+            for (label, mid_angle) in labels {
+                let rad = mid_angle.to_radians();
+                let x = cx + label_radius * rad.cos();
+                let y = cy + label_radius * rad.sin();
+                draw_label(canvas, &label, x, y, &font, &paint, TextAlignType::Center);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        Some(Rect::new(self.x - self.radius, self.y - self.radius, self.x + self.radius, self.y + self.radius))
+    }
+}
+
+impl PosterElement for BarChartElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::BarChart(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        if self.values.is_empty() || self.width <= 0.0 || self.height <= 0.0 {
+            return Ok(());
+        }
+
+        let font = get_font_for_text_with_family("", self.font_size, false, self.font_family.as_deref(), None, ctx)?;
+        let mut label_paint = Paint::default();
+        label_paint.set_color(parse_color(&self.label_color, "label_color")?);
+        label_paint.set_anti_alias(true);
+
+        // Reserve room for category labels below and value labels above the bars
+        let label_height = self.font_size * 1.5;
+        let top = self.y + if self.show_values { label_height } else { 0.0 };
+        let bottom = self.y + self.height - if self.categories.is_empty() { 0.0 } else { label_height };
+        let plot_height = bottom - top;
+        if plot_height <= 0.0 {
+            return Ok(());
+        }
+
+        // The value scale always includes zero, so negative bars hang below the baseline
+        let max = self.max_value.unwrap_or_else(|| self.values.iter().copied().fold(0.0, f32::max)).max(0.0);
+        let min = self.values.iter().copied().fold(0.0, f32::min);
+        let range = if max - min > 0.0 { max - min } else { 1.0 };
+        let value_to_y = |value: f32| bottom - (value.clamp(min, max) - min) / range * plot_height;
+        let baseline = value_to_y(0.0);
+
+        if self.show_gridlines && self.gridlines > 0 {
+            let mut grid_paint = Paint::default();
+            grid_paint.set_color(parse_color(&self.grid_color, "grid_color")?);
+            grid_paint.set_anti_alias(true);
+            grid_paint.set_stroke_width(1.0);
+            for i in 1..=self.gridlines {
+                let y = baseline - (baseline - top) * i as f32 / self.gridlines as f32;
+                canvas.draw_line((self.x, y), (self.x + self.width, y), &grid_paint);
+            }
+        }
+
+        let slot_width = self.width / self.values.len() as f32;
+        let bar_width = slot_width * (1.0 - self.bar_gap.clamp(0.0, 0.9));
+
+        for (i, value) in self.values.iter().copied().enumerate() {
+            let slot_x = self.x + slot_width * i as f32;
+            let bar_x = slot_x + (slot_width - bar_width) / 2.0;
+            let value_y = value_to_y(value);
+
+            if !self.colors.is_empty() {
+                let color = &self.colors[i % self.colors.len()];
+                let mut paint = Paint::default();
+                paint.set_color(parse_color(color, &format!("colors[{}]", i % self.colors.len()))?);
+                paint.set_anti_alias(true);
+                canvas.draw_rect(Rect::new(bar_x, value_y.min(baseline), bar_x + bar_width, value_y.max(baseline)), &paint);
+            }
+
+            let center_x = slot_x + slot_width / 2.0;
+            if self.show_values {
+                let label_y = if value >= 0.0 { value_y - label_height / 2.0 } else { value_y + label_height / 2.0 };
+                draw_label(canvas, &format_value(value), center_x, label_y, &font, &label_paint, TextAlignType::Center);
+            }
+            if let Some(category) = self.categories.get(i) {
+                draw_label(canvas, category, center_x, bottom + label_height / 2.0, &font, &label_paint, TextAlignType::Center);
+            }
+        }
+
+        if self.show_axis {
+            let mut axis_paint = Paint::default();
+            axis_paint.set_color(parse_color(&self.axis_color, "axis_color")?);
+            axis_paint.set_anti_alias(true);
+            axis_paint.set_stroke_width(1.0);
+            canvas.draw_line((self.x, baseline), (self.x + self.width, baseline), &axis_paint);
+        }
+
+        Ok(())
+    }
+
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        Some(Rect::from_xywh(self.x, self.y, self.width, self.height))
+    }
+}
+
+impl PosterElement for SparklineElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Sparkline(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, _ctx: &RenderContext) -> Result<()> {
+        if self.values.is_empty() || self.width <= 0.0 || self.height <= 0.0 {
+            return Ok(());
+        }
+
+        // Inset the plot so markers and thick lines at the extremes stay inside the box
+        let inset = if self.show_min_max { self.marker_radius.max(self.line_width / 2.0) } else { self.line_width / 2.0 };
+        let left = self.x + inset;
+        let top = self.y + inset;
+        let plot_width = (self.width - inset * 2.0).max(0.0);
+        let plot_height = (self.height - inset * 2.0).max(0.0);
+
+        let min = self.values.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = self.values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let range = max - min;
+        let step = if self.values.len() > 1 { plot_width / (self.values.len() - 1) as f32 } else { 0.0 };
+
+        let points: Vec<Point> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                // A flat series is drawn through the middle of the box
+                let t = if range > 0.0 { (value - min) / range } else { 0.5 };
+                Point::new(left + step * i as f32, top + plot_height * (1.0 - t))
+            })
+            .collect();
+
+        let mut line = SkPath::new();
+        line.move_to(points[0]);
+        for (i, &point) in points.iter().enumerate().skip(1) {
+            if self.smooth {
+                // Catmull-Rom spline through the points, as cubic Bézier segments
+                let before = points[i.saturating_sub(2)];
+                let previous = points[i - 1];
+                let next = points[(i + 1).min(points.len() - 1)];
+                let c1 = previous + (point - before) * (1.0 / 6.0);
+                let c2 = point - (next - previous) * (1.0 / 6.0);
+                line.cubic_to(c1, c2, point);
+            } else {
+                line.line_to(point);
+            }
+        }
+
+        if let Some(fill) = &self.fill {
+            let bottom = self.y + self.height;
+            let mut area = line.clone();
+            area.line_to((points[points.len() - 1].x, bottom));
+            area.line_to((points[0].x, bottom));
+            area.close();
+
+            let mut paint = Paint::default();
+            paint.set_color(parse_color(fill, "fill")?);
+            paint.set_anti_alias(true);
+            canvas.draw_path(&area, &paint);
+        }
+
+        let mut paint = Paint::default();
+        paint.set_color(parse_color(&self.color, "color")?);
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_stroke_width(self.line_width);
+        paint.set_stroke_cap(PaintCap::Round);
+        paint.set_stroke_join(PaintJoin::Round);
+        canvas.draw_path(&line, &paint);
+
+        if self.show_min_max && range > 0.0 {
+            let min_index = self.values.iter().position(|value| *value == min).unwrap_or(0);
+            let max_index = self.values.iter().position(|value| *value == max).unwrap_or(0);
+            for (index, color, field) in [(min_index, &self.min_color, "min_color"), (max_index, &self.max_color, "max_color")] {
+                let mut marker_paint = Paint::default();
+                marker_paint.set_color(parse_color(color, field)?);
+                marker_paint.set_anti_alias(true);
+                canvas.draw_circle(points[index], self.marker_radius, &marker_paint);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        Some(Rect::from_xywh(self.x, self.y, self.width, self.height))
+    }
+}
+
+// Format a chart value without a trailing ".0" for whole numbers
+fn format_value(value: f32) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+// Draw a single line of text vertically centered on `center_y`
+fn draw_label(canvas: &Canvas, text: &str, x: f32, center_y: f32, font: &Font, paint: &Paint, align: TextAlignType) {
+    let (_, metrics) = font.metrics();
+    let baseline = center_y - (metrics.ascent + metrics.descent) / 2.0;
+    let direction = if is_rtl_text(text) { TextDirectionType::Rtl } else { TextDirectionType::Ltr };
+    draw_text_line_improved(canvas, text, x, baseline, font, paint, &direction, &align);
+}
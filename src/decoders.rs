@@ -0,0 +1,135 @@
+//! Fallback decoders for image formats Skia can't decode.
+//!
+//! AVIF and HEIC sources are decoded here when the `avif` and `heic` features
+//! are enabled. Both formats are ISO-BMFF files, told apart by the brand in
+//! their `ftyp` box. Lottie animations, which are JSON documents, are drawn
+//! with rlottie when the `lottie` feature is enabled. Images embedded in an
+//! animation as data URIs are drawn; external image files it references are not
+//! loaded, as they would bypass the poster's asset resolver.
+
+use crate::FrameSelector;
+use skia_safe::Image;
+#[cfg(any(feature = "avif", feature = "heic", feature = "lottie"))]
+use skia_safe::{images, AlphaType, ColorType, Data, ImageInfo};
+
+// Format of an image file Skia can't decode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Avif,
+    Heic,
+    Lottie,
+}
+
+// Read the major brand of the `ftyp` box at the start of the file, or recognize
+// a JSON document as a Lottie animation
+fn sniff(bytes: &[u8]) -> Option<Format> {
+    if bytes.trim_ascii_start().starts_with(b"{") {
+        return Some(Format::Lottie);
+    }
+    if bytes.len() < 12 || &bytes[4..8] != b"ftyp" {
+        return None;
+    }
+    match &bytes[8..12] {
+        b"avif" | b"avis" => Some(Format::Avif),
+        b"heic" | b"heix" | b"hevc" | b"hevx" | b"heim" | b"heis" | b"mif1" | b"msf1" => Some(Format::Heic),
+        _ => None,
+    }
+}
+
+/// Decodes an image Skia doesn't support, if its format is recognized and the
+/// matching feature is enabled. `frame` selects the frame of an animation.
+pub(crate) fn decode(bytes: &[u8], frame: Option<FrameSelector>) -> Option<Image> {
+    match sniff(bytes)? {
+        Format::Avif => decode_avif(bytes),
+        Format::Heic => decode_heic(bytes),
+        Format::Lottie => decode_lottie(bytes, frame),
+    }
+}
+
+#[cfg(feature = "avif")]
+fn decode_avif(bytes: &[u8]) -> Option<Image> {
+    let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Avif)
+        .map_err(|e| tracing::warn!("Failed to decode AVIF image: {}", e))
+        .ok()?
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+    rgba_image(width, height, image.as_raw(), width as usize * 4)
+}
+
+#[cfg(not(feature = "avif"))]
+fn decode_avif(_bytes: &[u8]) -> Option<Image> {
+    tracing::warn!("AVIF images need the `avif` feature");
+    None
+}
+
+// libheif applies the rotation and mirroring stored in the file
+#[cfg(feature = "heic")]
+fn decode_heic(bytes: &[u8]) -> Option<Image> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let decoded = HeifContext::read_from_bytes(bytes)
+        .and_then(|context| {
+            let handle = context.primary_image_handle()?;
+            LibHeif::new().decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+        })
+        .map_err(|e| tracing::warn!("Failed to decode HEIC image: {}", e))
+        .ok()?;
+    let plane = decoded.planes().interleaved?;
+    rgba_image(plane.width, plane.height, plane.data, plane.stride)
+}
+
+#[cfg(not(feature = "heic"))]
+fn decode_heic(_bytes: &[u8]) -> Option<Image> {
+    tracing::warn!("HEIC images need the `heic` feature");
+    None
+}
+
+// Resource directory of Lottie animations: a file, so no path below it exists
+#[cfg(feature = "lottie")]
+const NO_RESOURCES: &str = "/dev/null";
+
+// Draw one frame of a Lottie animation at the animation's own size. Without a
+// frame selector this is the first frame.
+#[cfg(feature = "lottie")]
+fn decode_lottie(bytes: &[u8], frame: Option<FrameSelector>) -> Option<Image> {
+    use rlottie::{Animation, Surface};
+
+    // An empty cache key keeps rlottie from caching the animation. External image
+    // assets are resolved below a path that can't have any files under it, so they
+    // fail to load instead of being read from the working directory.
+    let Some(mut animation) = Animation::from_data(bytes, "", NO_RESOURCES) else {
+        tracing::warn!("Failed to load Lottie animation");
+        return None;
+    };
+    let count = animation.totalframe().max(1);
+    let frame_index = match frame {
+        None => 0,
+        Some(FrameSelector::Index(index)) => index.min(count - 1),
+        Some(FrameSelector::TimeMs(time_ms)) => {
+            (f64::from(time_ms) / 1000.0 * animation.framerate()) as usize % count
+        }
+        Some(FrameSelector::Progress(progress)) => {
+            ((count - 1) as f32 * progress as f32 / FrameSelector::PROGRESS_SCALE).round() as usize
+        }
+    };
+
+    let mut surface = Surface::new(animation.size());
+    animation.render(frame_index, &mut surface);
+    let (width, height) = (surface.width(), surface.height());
+    // rlottie draws premultiplied BGRA pixels
+    let info = ImageInfo::new((width as i32, height as i32), ColorType::BGRA8888, AlphaType::Premul, None);
+    images::raster_from_data(&info, Data::new_copy(surface.data_as_bytes()), width * 4)
+}
+
+#[cfg(not(feature = "lottie"))]
+fn decode_lottie(_bytes: &[u8], _frame: Option<FrameSelector>) -> Option<Image> {
+    tracing::warn!("Lottie animations need the `lottie` feature");
+    None
+}
+
+// Wrap decoded RGBA pixels in a Skia image
+#[cfg(any(feature = "avif", feature = "heic"))]
+fn rgba_image(width: u32, height: u32, pixels: &[u8], row_bytes: usize) -> Option<Image> {
+    let info = ImageInfo::new((width as i32, height as i32), ColorType::RGBA8888, AlphaType::Unpremul, None);
+    images::raster_from_data(&info, Data::new_copy(pixels), row_bytes)
+}
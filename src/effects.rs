@@ -0,0 +1,276 @@
+//! Effect elements that post-process or overlay a region of the canvas.
+
+use crate::{Element, PosterElement, PosterError, RenderContext, Result};
+use serde::{Deserialize, Serialize};
+use skia_safe::{
+    canvas::SaveLayerRec, color_filters, image_filters, BlendMode, Canvas, FilterMode, ImageFilter, Matrix,
+    MipmapMode, Paint, Point, Rect, SamplingOptions, Shader,
+};
+
+/// How an overlay is blended with what is rendered beneath it.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlendModeType {
+    /// Draw the overlay on top.
+    Normal,
+    /// Darken by multiplying colors.
+    Multiply,
+    /// Lighten by inverting, multiplying and inverting again.
+    Screen,
+    /// Multiply dark areas and screen light ones, keeping contrast.
+    Overlay,
+    /// A gentler overlay.
+    SoftLight,
+}
+
+impl From<BlendModeType> for BlendMode {
+    fn from(mode: BlendModeType) -> Self {
+        match mode {
+            BlendModeType::Normal => BlendMode::SrcOver,
+            BlendModeType::Multiply => BlendMode::Multiply,
+            BlendModeType::Screen => BlendMode::Screen,
+            BlendModeType::Overlay => BlendMode::Overlay,
+            BlendModeType::SoftLight => BlendMode::SoftLight,
+        }
+    }
+}
+
+/// Film grain overlay configuration.
+///
+/// Overlays procedurally generated noise on everything rendered beneath it
+/// (lower z-index). The region defaults to the whole canvas.
+///
+/// # Example
+///
+/// ```
+/// use poster_generator::{PosterGenerator, NoiseElement};
+///
+/// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+/// generator.add_noise(NoiseElement {
+///     intensity: 0.2,
+///     z_index: Some(100),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NoiseElement {
+    /// X-coordinate of the region (top-left corner). Defaults to 0.
+    pub x: Option<f32>,
+    /// Y-coordinate of the region (top-left corner). Defaults to 0.
+    pub y: Option<f32>,
+    /// Width of the region. Defaults to the canvas width.
+    pub width: Option<f32>,
+    /// Height of the region. Defaults to the canvas height.
+    pub height: Option<f32>,
+    /// Strength of the grain from 0 (invisible) to 1.
+    #[serde(default = "default_noise_intensity")]
+    pub intensity: f32,
+    /// Size of the grain in pixels. Larger values give coarser grain.
+    #[serde(default = "default_grain_size")]
+    pub grain_size: f32,
+    /// Whether the grain is gray rather than colored.
+    #[serde(default = "default_monochrome")]
+    pub monochrome: bool,
+    /// How the grain is blended with the poster.
+    #[serde(default = "default_noise_blend_mode")]
+    pub blend_mode: BlendModeType,
+    /// Seed of the noise pattern; the same seed always gives the same grain.
+    #[serde(default)]
+    pub seed: f32,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+impl Default for NoiseElement {
+    fn default() -> Self {
+        Self {
+            x: None,
+            y: None,
+            width: None,
+            height: None,
+            intensity: default_noise_intensity(),
+            grain_size: default_grain_size(),
+            monochrome: default_monochrome(),
+            blend_mode: default_noise_blend_mode(),
+            seed: 0.0,
+            id: None,
+            z_index: None,
+        }
+    }
+}
+
+fn default_noise_intensity() -> f32 {
+    0.15
+}
+
+fn default_grain_size() -> f32 {
+    1.0
+}
+
+fn default_monochrome() -> bool {
+    true
+}
+
+fn default_noise_blend_mode() -> BlendModeType {
+    BlendModeType::Overlay
+}
+
+impl NoiseElement {
+    fn rect(&self, ctx: &RenderContext) -> Rect {
+        let canvas = ctx.canvas_rect();
+        Rect::from_xywh(
+            self.x.unwrap_or(canvas.left),
+            self.y.unwrap_or(canvas.top),
+            self.width.unwrap_or(canvas.width()),
+            self.height.unwrap_or(canvas.height()),
+        )
+    }
+}
+
+impl PosterElement for NoiseElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Noise(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let Some(shader) = grain_shader(self.intensity, self.grain_size, self.monochrome, self.seed) else {
+            return Ok(());
+        };
+        let mut paint = Paint::default();
+        paint.set_shader(shader);
+        paint.set_blend_mode(self.blend_mode.into());
+        canvas.draw_rect(self.rect(ctx), &paint);
+        Ok(())
+    }
+
+    fn bounds(&self, ctx: &RenderContext) -> Option<Rect> {
+        Some(self.rect(ctx))
+    }
+}
+
+/// Pixelated (mosaic) region configuration.
+///
+/// Replaces everything rendered beneath it (lower z-index) within a rectangle with
+/// square blocks, e.g. to redact faces or license plates. Give it the highest
+/// z-index to pixelate the finished poster.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PixelateElement {
+    /// X-coordinate of the region (top-left corner).
+    pub x: f32,
+    /// Y-coordinate of the region (top-left corner).
+    pub y: f32,
+    /// Width of the region.
+    pub width: f32,
+    /// Height of the region.
+    pub height: f32,
+    /// Size of the blocks in pixels.
+    #[serde(default = "default_block_size")]
+    pub block_size: f32,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+impl Default for PixelateElement {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+            block_size: default_block_size(),
+            id: None,
+            z_index: None,
+        }
+    }
+}
+
+fn default_block_size() -> f32 {
+    12.0
+}
+
+impl PosterElement for PixelateElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Pixelate(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, _ctx: &RenderContext) -> Result<()> {
+        let rect = Rect::from_xywh(self.x, self.y, self.width, self.height);
+        let filter = pixelate_filter(self.block_size, Point::new(self.x, self.y))
+            .ok_or_else(|| PosterError::RenderError("Failed to create pixelate filter".to_string()))?;
+
+        // A layer with a backdrop filter starts from a pixelated copy of what is beneath it
+        canvas.save();
+        canvas.clip_rect(rect, None, None);
+        canvas.save_layer(&SaveLayerRec::default().bounds(&rect).backdrop(&filter));
+        canvas.restore();
+        canvas.restore();
+        Ok(())
+    }
+
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        Some(Rect::from_xywh(self.x, self.y, self.width, self.height))
+    }
+}
+
+/// Film grain of the given strength (0 to 1), gray if `monochrome`.
+pub(crate) fn grain_shader(intensity: f32, grain_size: f32, monochrome: bool, seed: f32) -> Option<Shader> {
+    // High-frequency fractal noise reads as grain; lower frequencies give coarser grain
+    let frequency = 0.9 / grain_size.max(0.1);
+    let shader = Shader::fractal_perlin_noise((frequency, frequency), 3, seed, None)?;
+
+    // The noise shader also varies alpha; replace it with the intensity, and make the grain gray if monochrome
+    let alpha = intensity.clamp(0.0, 1.0);
+    #[rustfmt::skip]
+    let matrix: [f32; 20] = if monochrome {
+        [
+            1.0, 0.0, 0.0, 0.0, 0.0,
+            1.0, 0.0, 0.0, 0.0, 0.0,
+            1.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.0, alpha,
+        ]
+    } else {
+        [
+            1.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.0, alpha,
+        ]
+    };
+    Some(shader.with_color_filter(color_filters::matrix_row_major(&matrix, None)))
+}
+
+/// Image filter that turns its input into square blocks of `block_size` pixels,
+/// with the block grid starting at `origin`.
+pub(crate) fn pixelate_filter(block_size: f32, origin: Point) -> Option<ImageFilter> {
+    let block_size = block_size.max(1.0);
+
+    // Shrink so each block becomes one pixel, then grow back without smoothing
+    let mut down = Matrix::scale((1.0 / block_size, 1.0 / block_size));
+    down.pre_translate((-origin.x, -origin.y));
+    let mut up = Matrix::translate(origin);
+    up.pre_scale((block_size, block_size), None);
+
+    let sampling = SamplingOptions::new(FilterMode::Linear, MipmapMode::Linear);
+    let shrunk = image_filters::matrix_transform(&down, sampling, None)?;
+    image_filters::matrix_transform(&up, FilterMode::Nearest, shrunk)
+}
@@ -0,0 +1,168 @@
+//! Emoji shortcode expansion (`:rocket:` → 🚀).
+//!
+//! Text elements with `expand_shortcodes` enabled run their text through
+//! [`expand_shortcodes`] before layout, so strings from chat tools or a CMS that
+//! use shortcodes render as emoji.
+//!
+//! ```
+//! use poster_generator::emoji;
+//!
+//! assert_eq!(emoji::expand_shortcodes("Launch day :rocket:"), "Launch day 🚀");
+//! assert_eq!(emoji::expand_shortcodes("at 10:30: sharp"), "at 10:30: sharp");
+//! ```
+
+/// Shortcodes (without colons) and the emoji they expand to, sorted by shortcode.
+///
+/// Names follow the common GitHub/Slack spellings.
+pub const SHORTCODES: &[(&str, &str)] = &[
+    ("+1", "👍"),
+    ("-1", "👎"),
+    ("100", "💯"),
+    ("alarm_clock", "⏰"),
+    ("angry", "😠"),
+    ("apple", "🍎"),
+    ("arrow_down", "⬇️"),
+    ("arrow_left", "⬅️"),
+    ("arrow_right", "➡️"),
+    ("arrow_up", "⬆️"),
+    ("balloon", "🎈"),
+    ("bell", "🔔"),
+    ("birthday", "🎂"),
+    ("blush", "😊"),
+    ("books", "📚"),
+    ("bulb", "💡"),
+    ("cake", "🍰"),
+    ("calendar", "📆"),
+    ("camera", "📷"),
+    ("car", "🚗"),
+    ("chart_with_downwards_trend", "📉"),
+    ("chart_with_upwards_trend", "📈"),
+    ("check", "✔️"),
+    ("christmas_tree", "🎄"),
+    ("clap", "👏"),
+    ("clock", "🕒"),
+    ("coffee", "☕"),
+    ("cool", "🆒"),
+    ("crown", "👑"),
+    ("cry", "😢"),
+    ("dart", "🎯"),
+    ("dollar", "💵"),
+    ("earth_asia", "🌏"),
+    ("email", "📧"),
+    ("exclamation", "❗"),
+    ("eyes", "👀"),
+    ("fire", "🔥"),
+    ("flag_cn", "🇨🇳"),
+    ("flower", "🌸"),
+    ("free", "🆓"),
+    ("gem", "💎"),
+    ("gift", "🎁"),
+    ("globe_with_meridians", "🌐"),
+    ("grin", "😁"),
+    ("grinning", "😀"),
+    ("hammer", "🔨"),
+    ("heart", "❤️"),
+    ("heart_eyes", "😍"),
+    ("hourglass", "⌛"),
+    ("house", "🏠"),
+    ("joy", "😂"),
+    ("key", "🔑"),
+    ("laughing", "😆"),
+    ("link", "🔗"),
+    ("lock", "🔒"),
+    ("loudspeaker", "📢"),
+    ("mag", "🔍"),
+    ("medal", "🏅"),
+    ("memo", "📝"),
+    ("money_with_wings", "💸"),
+    ("moneybag", "💰"),
+    ("moon", "🌙"),
+    ("muscle", "💪"),
+    ("new", "🆕"),
+    ("ok", "🆗"),
+    ("ok_hand", "👌"),
+    ("package", "📦"),
+    ("partying_face", "🥳"),
+    ("phone", "📞"),
+    ("pizza", "🍕"),
+    ("point_down", "👇"),
+    ("point_left", "👈"),
+    ("point_right", "👉"),
+    ("point_up", "👆"),
+    ("pray", "🙏"),
+    ("pushpin", "📌"),
+    ("question", "❓"),
+    ("rainbow", "🌈"),
+    ("raised_hands", "🙌"),
+    ("red_circle", "🔴"),
+    ("rocket", "🚀"),
+    ("rose", "🌹"),
+    ("sad", "😞"),
+    ("shopping_cart", "🛒"),
+    ("smile", "😄"),
+    ("smiley", "😃"),
+    ("snowflake", "❄️"),
+    ("sob", "😭"),
+    ("sparkles", "✨"),
+    ("star", "⭐"),
+    ("star2", "🌟"),
+    ("sun", "☀️"),
+    ("sunglasses", "😎"),
+    ("tada", "🎉"),
+    ("thinking", "🤔"),
+    ("thumbsdown", "👎"),
+    ("thumbsup", "👍"),
+    ("ticket", "🎫"),
+    ("trophy", "🏆"),
+    ("warning", "⚠️"),
+    ("wave", "👋"),
+    ("white_check_mark", "✅"),
+    ("wink", "😉"),
+    ("x", "❌"),
+    ("yum", "😋"),
+    ("zap", "⚡"),
+];
+
+/// Looks up the emoji for a shortcode, given without the surrounding colons.
+pub fn lookup(shortcode: &str) -> Option<&'static str> {
+    SHORTCODES
+        .iter()
+        .find(|(name, _)| *name == shortcode)
+        .map(|(_, emoji)| *emoji)
+}
+
+/// Replaces `:shortcode:` occurrences with their emoji.
+///
+/// Unknown shortcodes and colons that don't form a shortcode (like in "10:30")
+/// are left as they are.
+pub fn expand_shortcodes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(':') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        // A shortcode runs up to the next colon and contains no whitespace
+        let emoji = after
+            .find(':')
+            .map(|end| (end, &after[..end]))
+            .filter(|(_, name)| !name.is_empty() && !name.contains(char::is_whitespace))
+            .and_then(|(end, name)| lookup(name).map(|emoji| (end, emoji)));
+
+        match emoji {
+            Some((end, emoji)) => {
+                result.push_str(emoji);
+                rest = &after[end + 1..];
+            }
+            None => {
+                // Keep the colon; it may still open a shortcode that follows
+                result.push(':');
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
@@ -0,0 +1,403 @@
+//! Arithmetic expressions in the position and size fields of a config.
+//!
+//! Instead of a number, a field such as `x`, `y`, `width` or `font_size` can hold
+//! an expression, so a template describes its layout rather than hard-coding
+//! pixel values:
+//!
+//! ```json
+//! "elements": [
+//!     { "type": "text", "id": "title", "text": "Sale", "x": "width / 2", "y": "height * 0.2", "font_size": 64, "align": "center" },
+//!     { "type": "text", "text": "This week only", "x": "width / 2", "y": "prev.bottom + 24", "font_size": 28, "align": "center" }
+//! ]
+//! ```
+//!
+//! Expressions use `+`, `-`, `*`, `/`, `%`, parentheses and the functions `min`,
+//! `max`, `abs`, `round`, `floor` and `ceil`. Names refer to:
+//!
+//! - `width` and `height`: the canvas size;
+//! - `prev.<edge>`: the bounds of the previous element;
+//! - `<id>.<edge>`: the bounds of an earlier element with that `id` (made of
+//!   letters, digits and underscores);
+//! - any other name: a numeric template variable (see [`template`](crate::template)).
+//!
+//! Edges are `left`, `top`, `right`, `bottom`, `width`, `height`, `center_x` and `center_y`.
+//!
+//! Expressions are evaluated on the JSON config before it is deserialized.
+//! [`template::render_config`](crate::template::render_config), [`render_config`](crate::render_config)
+//! and the CLI do this through [`preprocess`](crate::preprocess); other callers use [`apply`]:
+//!
+//! ```
+//! use poster_generator::expr;
+//! use poster_generator::template::Variables;
+//! use poster_generator::PosterConfig;
+//! use serde_json::json;
+//!
+//! let mut config = json!({
+//!     "width": 800,
+//!     "height": 600,
+//!     "background_color": "#ffffff",
+//!     "elements": [
+//!         { "type": "text", "text": "Hello", "x": "width / 2 - 100", "y": "height - 80", "font_size": 48, "color": "#000000" }
+//!     ]
+//! });
+//! expr::apply(&mut config, &Variables::new()).unwrap();
+//! assert_eq!(config["elements"][0]["x"], 300);
+//!
+//! let config: PosterConfig = serde_json::from_value(config).unwrap();
+//! ```
+
+use crate::template::Variables;
+use crate::theme::{self, Theme};
+use crate::{Element, PosterConfig, PosterElement, PosterGenerator, RenderContext};
+use serde::Deserialize;
+use serde_json::Value;
+use skia_safe::Rect;
+use std::cell::OnceCell;
+
+// Element fields that may hold an expression
+const FIELDS: &[&str] = &[
+    "x", "y", "x1", "y1", "x2", "y2", "width", "height", "max_width", "font_size", "line_height_px",
+    "radius", "inner_radius", "size", "padding", "padding_x", "padding_y", "margin", "offset", "offset_x",
+    "offset_y", "blur", "spread", "stroke_width", "line_width", "border_width", "icon_size", "icon_gap",
+    "cell_size", "block_size",
+];
+
+/// Replaces the expressions in a JSON config by their values, in place.
+///
+/// The canvas `width` and `height` may use template variables; element fields may
+/// also use the canvas size and the bounds of earlier elements.
+///
+/// # Errors
+///
+/// Returns an error if an expression is invalid or names something unknown, or if
+/// an element referenced by an expression can't be parsed.
+pub fn apply(config: &mut Value, vars: &Variables) -> serde_json::Result<()> {
+    let lookup = |name: &str| variable(vars, name);
+    let mut canvas = [0.0; 2];
+    for (size, key) in canvas.iter_mut().zip(["width", "height"]) {
+        let Some(value) = config.get_mut(key) else {
+            continue;
+        };
+        if let Value::String(expr) = value {
+            // The canvas size is a whole number of pixels
+            let evaluated = evaluate(key, expr, &lookup)?;
+            *value = Value::from(evaluated.as_f64().unwrap_or_default().round().max(0.0) as u64);
+        }
+        *size = value.as_f64().unwrap_or_default();
+    }
+
+    let mut elements = match config.get_mut("elements") {
+        Some(Value::Array(elements)) if elements.iter().any(has_expressions) => std::mem::take(elements),
+        _ => return Ok(()),
+    };
+    let result = apply_elements(config, &mut elements, canvas, vars);
+    if let Some(slot) = config.get_mut("elements") {
+        *slot = Value::Array(elements);
+    }
+    result
+}
+
+// Evaluate the elements in order, so expressions can refer to the elements before them
+fn apply_elements(config: &Value, elements: &mut [Value], canvas: [f64; 2], vars: &Variables) -> serde_json::Result<()> {
+    let theme = match config.get("theme") {
+        Some(theme) => Theme::deserialize(theme)?,
+        None => Theme::new(),
+    };
+    let mut layout = Layout { config, canvas, vars, ctx: OnceCell::new(), placed: Vec::new() };
+    for element in elements.iter_mut() {
+        evaluate_fields(element, &|name| layout.lookup(name))?;
+
+        // Bounds are measured with theme tokens resolved, as when rendering
+        let mut themed = element.clone();
+        theme::apply(&mut themed, &theme);
+        let element = Element::deserialize(&themed)?.into_boxed();
+        layout.placed.push(Placed { element, bounds: OnceCell::new() });
+    }
+    Ok(())
+}
+
+// Whether a JSON value holds an expression anywhere
+fn has_expressions(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => map.iter().any(|(key, item)| match item {
+            Value::String(s) => FIELDS.contains(&key.as_str()) && s != "auto",
+            _ => has_expressions(item),
+        }),
+        Value::Array(items) => items.iter().any(has_expressions),
+        _ => false,
+    }
+}
+
+fn evaluate_fields(value: &mut Value, lookup: &dyn Fn(&str) -> Result<f64, String>) -> serde_json::Result<()> {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                match item {
+                    Value::String(expr) if FIELDS.contains(&key.as_str()) && expr != "auto" => {
+                        *item = evaluate(key, expr, lookup)?;
+                    }
+                    _ => evaluate_fields(item, lookup)?,
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                evaluate_fields(item, lookup)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// Evaluate the expression of a field, as a JSON number
+fn evaluate(key: &str, expr: &str, lookup: &dyn Fn(&str) -> Result<f64, String>) -> serde_json::Result<Value> {
+    let value = eval(expr, lookup).map_err(|message| {
+        serde::de::Error::custom(format!("invalid expression '{}' in field '{}': {}", expr, key, message))
+    })?;
+    // Whole numbers stay integers, so integer fields accept them
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        Ok(Value::from(value as i64))
+    } else {
+        Ok(Value::from(value))
+    }
+}
+
+// A template variable as a number; CSV values are numeric strings
+fn variable(vars: &Variables, name: &str) -> Result<f64, String> {
+    match vars.get(name) {
+        Some(Value::Number(number)) => number.as_f64(),
+        Some(Value::String(s)) => s.trim().parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("unknown name '{}'", name))
+}
+
+// Names available to element expressions
+struct Layout<'a> {
+    // The config without its elements, for the render settings bounds depend on
+    config: &'a Value,
+    canvas: [f64; 2],
+    vars: &'a Variables,
+    ctx: OnceCell<RenderContext>,
+    placed: Vec<Placed>,
+}
+
+// An element already evaluated, whose bounds are measured when first referenced
+struct Placed {
+    element: Box<dyn PosterElement>,
+    bounds: OnceCell<Option<Rect>>,
+}
+
+impl Layout<'_> {
+    fn lookup(&self, name: &str) -> Result<f64, String> {
+        match name {
+            "width" => return Ok(self.canvas[0]),
+            "height" => return Ok(self.canvas[1]),
+            _ => {}
+        }
+        let Some((target, edge)) = name.split_once('.') else {
+            return variable(self.vars, name);
+        };
+
+        let placed = if target == "prev" {
+            self.placed.last().ok_or_else(|| "'prev' used in the first element".to_string())?
+        } else {
+            self.placed
+                .iter()
+                .rev()
+                .find(|placed| placed.element.id() == Some(target))
+                .ok_or_else(|| format!("no earlier element has id '{}'", target))?
+        };
+        let bounds = self.bounds(placed)?.ok_or_else(|| format!("'{}' has no bounds", target))?;
+        let value = match edge {
+            "left" => bounds.left,
+            "top" => bounds.top,
+            "right" => bounds.right,
+            "bottom" => bounds.bottom,
+            "width" => bounds.width(),
+            "height" => bounds.height(),
+            "center_x" => bounds.center_x(),
+            "center_y" => bounds.center_y(),
+            _ => return Err(format!("unknown edge '{}'", edge)),
+        };
+        Ok(value as f64)
+    }
+
+    fn bounds(&self, placed: &Placed) -> Result<Option<Rect>, String> {
+        if self.ctx.get().is_none() {
+            let config = PosterConfig::deserialize(self.config).map_err(|e| e.to_string())?;
+            let _ = self.ctx.set(PosterGenerator::from_config(config).render_context());
+        }
+        let ctx = self.ctx.get().expect("the context was just set");
+        Ok(*placed.bounds.get_or_init(|| placed.element.bounds(ctx)))
+    }
+}
+
+// Evaluates an expression, looking names up with `lookup`
+fn eval(expr: &str, lookup: &dyn Fn(&str) -> Result<f64, String>) -> Result<f64, String> {
+    let mut parser = Parser { src: expr, pos: 0, depth: 0, lookup };
+    let value = parser.sum()?;
+    if parser.peek().is_some() {
+        return Err(parser.unexpected());
+    }
+    if !value.is_finite() {
+        return Err("the result is not a finite number".to_string());
+    }
+    Ok(value)
+}
+
+// Deepest nesting of parentheses, function calls and signs, so that a long chain
+// of them is an error rather than a stack overflow
+const MAX_DEPTH: usize = 64;
+
+// Recursive descent parser, evaluating as it parses
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    depth: usize,
+    lookup: &'a dyn Fn(&str) -> Result<f64, String>,
+}
+
+impl<'a> Parser<'a> {
+    // Next character after whitespace, without consuming it
+    fn peek(&mut self) -> Option<u8> {
+        let bytes = self.src.as_bytes();
+        while bytes.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+        bytes.get(self.pos).copied()
+    }
+
+    fn unexpected(&self) -> String {
+        match self.src[self.pos..].chars().next() {
+            Some(c) => format!("unexpected '{}'", c),
+            None => "unexpected end of expression".to_string(),
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), String> {
+        if self.peek() != Some(c) {
+            return Err(self.unexpected());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    // Parse a part nested in the current one, one level deeper
+    fn nested(&mut self, parse: impl FnOnce(&mut Self) -> Result<f64, String>) -> Result<f64, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!("nested more than {} levels deep", MAX_DEPTH));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    // sum := product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<f64, String> {
+        let mut value = self.product()?;
+        loop {
+            match self.peek() {
+                Some(b'+') => {
+                    self.pos += 1;
+                    value += self.product()?;
+                }
+                Some(b'-') => {
+                    self.pos += 1;
+                    value -= self.product()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    // product := unary (('*' | '/' | '%') unary)*
+    fn product(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(op @ (b'*' | b'/' | b'%')) => op,
+                _ => return Ok(value),
+            };
+            self.pos += 1;
+            let rhs = self.unary()?;
+            value = match op {
+                b'*' => value * rhs,
+                b'/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+    }
+
+    // unary := ('-' | '+') unary | atom
+    fn unary(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some(b'-') => {
+                self.pos += 1;
+                Ok(-self.nested(Self::unary)?)
+            }
+            Some(b'+') => {
+                self.pos += 1;
+                self.nested(Self::unary)
+            }
+            _ => self.atom(),
+        }
+    }
+
+    // atom := number | name | function '(' sum (',' sum)* ')' | '(' sum ')'
+    fn atom(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.nested(Self::sum)?;
+                self.expect(b')')?;
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == b'.' => {
+                let number = self.take_while(|c| c.is_ascii_digit() || c == b'.');
+                number.parse().map_err(|_| format!("invalid number '{}'", number))
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == b'_' => {
+                let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'.');
+                if self.peek() != Some(b'(') {
+                    return (self.lookup)(name);
+                }
+                self.pos += 1;
+                let mut args = vec![self.nested(Self::sum)?];
+                while self.peek() == Some(b',') {
+                    self.pos += 1;
+                    args.push(self.nested(Self::sum)?);
+                }
+                self.expect(b')')?;
+                call(name, &args)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.src.as_bytes().get(self.pos).is_some_and(|&c| f(c)) {
+            self.pos += 1;
+        }
+        &self.src[start..self.pos]
+    }
+}
+
+fn call(name: &str, args: &[f64]) -> Result<f64, String> {
+    let one = || match args {
+        [value] => Ok(*value),
+        _ => Err(format!("{}() takes one argument", name)),
+    };
+    match name {
+        "min" => Ok(args.iter().copied().fold(f64::INFINITY, f64::min)),
+        "max" => Ok(args.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+        "abs" => Ok(one()?.abs()),
+        "round" => Ok(one()?.round()),
+        "floor" => Ok(one()?.floor()),
+        "ceil" => Ok(one()?.ceil()),
+        _ => Err(format!("unknown function '{}'", name)),
+    }
+}
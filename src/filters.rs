@@ -0,0 +1,124 @@
+//! Color adjustments applied through a color matrix.
+//!
+//! The adjustments follow the CSS `filter` functions of the same names and are
+//! applied in the order of the fields.
+
+use serde::{Deserialize, Serialize};
+use skia_safe::{color_filters, ColorFilter};
+
+/// Color adjustments for an element, flattened into its config.
+///
+/// ```json
+/// { "type": "image", "src": "product.jpg", "brightness": 1.1, "contrast": 1.2, "saturation": 0.9 }
+/// { "type": "image", "src": "icon-blue.png", "hue_rotate": 120 }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct ColorAdjustments {
+    /// Brightness multiplier: 0 is black, 1 is unchanged, above 1 is brighter.
+    pub brightness: Option<f32>,
+    /// Contrast multiplier: 0 is flat gray, 1 is unchanged, above 1 is more contrast.
+    pub contrast: Option<f32>,
+    /// Saturation multiplier: 0 is grayscale, 1 is unchanged, above 1 is more saturated.
+    pub saturation: Option<f32>,
+    /// Hue rotation in degrees.
+    pub hue_rotate: Option<f32>,
+    /// Amount of inversion: 0 is unchanged, 1 is fully inverted.
+    pub invert: Option<f32>,
+}
+
+// 4x5 row-major color matrix; the fifth column is an offset in the 0-1 range
+type ColorMatrix = [f32; 20];
+
+#[rustfmt::skip]
+const IDENTITY: ColorMatrix = [
+    1.0, 0.0, 0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0, 0.0, 0.0,
+    0.0, 0.0, 1.0, 0.0, 0.0,
+    0.0, 0.0, 0.0, 1.0, 0.0,
+];
+
+impl ColorAdjustments {
+    /// Returns true if no adjustment is set.
+    pub fn is_empty(&self) -> bool {
+        self.brightness.is_none()
+            && self.contrast.is_none()
+            && self.saturation.is_none()
+            && self.hue_rotate.is_none()
+            && self.invert.is_none()
+    }
+
+    /// Builds a color filter applying all adjustments, or `None` if there are none.
+    pub fn color_filter(&self) -> Option<ColorFilter> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut matrix = IDENTITY;
+        if let Some(brightness) = self.brightness {
+            matrix = concat(&matrix, &scale_rgb(brightness, 0.0));
+        }
+        if let Some(contrast) = self.contrast {
+            matrix = concat(&matrix, &scale_rgb(contrast, 0.5 * (1.0 - contrast)));
+        }
+        if let Some(saturation) = self.saturation {
+            matrix = concat(&matrix, &saturate(saturation));
+        }
+        if let Some(degrees) = self.hue_rotate {
+            matrix = concat(&matrix, &hue_rotate(degrees));
+        }
+        if let Some(invert) = self.invert {
+            let amount = invert.clamp(0.0, 1.0);
+            matrix = concat(&matrix, &scale_rgb(1.0 - 2.0 * amount, amount));
+        }
+        Some(color_filters::matrix_row_major(&matrix, None))
+    }
+}
+
+// Scale the color channels and add an offset, leaving alpha alone
+#[rustfmt::skip]
+fn scale_rgb(scale: f32, offset: f32) -> ColorMatrix {
+    [
+        scale, 0.0, 0.0, 0.0, offset,
+        0.0, scale, 0.0, 0.0, offset,
+        0.0, 0.0, scale, 0.0, offset,
+        0.0, 0.0, 0.0, 1.0, 0.0,
+    ]
+}
+
+// Saturation matrix from the Filter Effects spec (`feColorMatrix type="saturate"`)
+#[rustfmt::skip]
+fn saturate(s: f32) -> ColorMatrix {
+    [
+        0.213 + 0.787 * s, 0.715 - 0.715 * s, 0.072 - 0.072 * s, 0.0, 0.0,
+        0.213 - 0.213 * s, 0.715 + 0.285 * s, 0.072 - 0.072 * s, 0.0, 0.0,
+        0.213 - 0.213 * s, 0.715 - 0.715 * s, 0.072 + 0.928 * s, 0.0, 0.0,
+        0.0, 0.0, 0.0, 1.0, 0.0,
+    ]
+}
+
+// Hue rotation matrix from the Filter Effects spec (`feColorMatrix type="hueRotate"`)
+#[rustfmt::skip]
+fn hue_rotate(degrees: f32) -> ColorMatrix {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    [
+        0.213 + cos * 0.787 - sin * 0.213, 0.715 - cos * 0.715 - sin * 0.715, 0.072 - cos * 0.072 + sin * 0.928, 0.0, 0.0,
+        0.213 - cos * 0.213 + sin * 0.143, 0.715 + cos * 0.285 + sin * 0.140, 0.072 - cos * 0.072 - sin * 0.283, 0.0, 0.0,
+        0.213 - cos * 0.213 - sin * 0.787, 0.715 - cos * 0.715 + sin * 0.715, 0.072 + cos * 0.928 + sin * 0.072, 0.0, 0.0,
+        0.0, 0.0, 0.0, 1.0, 0.0,
+    ]
+}
+
+// Matrix that applies `first`, then `then`
+fn concat(first: &ColorMatrix, then: &ColorMatrix) -> ColorMatrix {
+    let mut result = [0.0; 20];
+    for row in 0..4 {
+        for col in 0..5 {
+            let mut value: f32 = (0..4).map(|k| then[row * 5 + k] * first[k * 5 + col]).sum();
+            if col == 4 {
+                value += then[row * 5 + 4];
+            }
+            result[row * 5 + col] = value;
+        }
+    }
+    result
+}
@@ -0,0 +1,449 @@
+//! Font discovery helpers and fallback configuration.
+//!
+//! The discovery helpers report which font families the renderer can see, which
+//! is the first thing to check when a `font_family` silently falls back to
+//! another font.
+//!
+//! Text without a `font_file`, or whose `font_family` isn't installed, is drawn
+//! with the first installed family of a [`FontFallback`] chain. The default chain
+//! lists macOS and Windows fonts; deployments on Linux can name their own fonts
+//! per script in the config:
+//!
+//! ```json
+//! "font_fallback": {
+//!     "scripts": {
+//!         "arabic": ["Noto Naskh Arabic", "UKIJ Tuz Tom"],
+//!         "han": ["Noto Sans CJK SC"]
+//!     },
+//!     "default": ["Noto Sans", "DejaVu Sans"]
+//! }
+//! ```
+//!
+//! How glyphs are rasterized can be pinned with [`FontRendering`], so small text
+//! looks the same on every machine rendering a template.
+
+use base64::{engine::general_purpose, Engine};
+use serde::{Deserialize, Serialize};
+use skia_safe::{font::Edging, Font, FontHinting, FontMgr, FontStyle, Typeface};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use tracing::warn;
+
+/// A writing system, used to check whether a font covers the characters of a language.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Script {
+    /// Latin alphabet (English, Uyghur Latin script, ...).
+    Latin,
+    /// Arabic script (Arabic, Persian, Uyghur, ...).
+    Arabic,
+    /// Hebrew script.
+    Hebrew,
+    /// Chinese characters.
+    Han,
+    /// Cyrillic alphabet.
+    Cyrillic,
+    /// Greek alphabet.
+    Greek,
+    /// Japanese kana.
+    Kana,
+    /// Korean Hangul.
+    Hangul,
+    /// Thai script.
+    Thai,
+    /// Devanagari script (Hindi, Marathi, Nepali, ...).
+    Devanagari,
+}
+
+impl Script {
+    /// All supported scripts.
+    pub const ALL: [Script; 10] = [
+        Script::Latin,
+        Script::Arabic,
+        Script::Hebrew,
+        Script::Han,
+        Script::Cyrillic,
+        Script::Greek,
+        Script::Kana,
+        Script::Hangul,
+        Script::Thai,
+        Script::Devanagari,
+    ];
+
+    /// Lowercase name of the script, as used in configs and on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Script::Latin => "latin",
+            Script::Arabic => "arabic",
+            Script::Hebrew => "hebrew",
+            Script::Han => "han",
+            Script::Cyrillic => "cyrillic",
+            Script::Greek => "greek",
+            Script::Kana => "kana",
+            Script::Hangul => "hangul",
+            Script::Thai => "thai",
+            Script::Devanagari => "devanagari",
+        }
+    }
+
+    /// Script of a character, or `None` for characters shared by all scripts
+    /// (digits, punctuation, spaces, emoji) and for unsupported scripts.
+    pub fn of(c: char) -> Option<Script> {
+        match c as u32 {
+            0x41..=0x5A | 0x61..=0x7A | 0xC0..=0x24F | 0x1E00..=0x1EFF => Some(Script::Latin),
+            0x600..=0x6FF | 0x750..=0x77F | 0x8A0..=0x8FF | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => Some(Script::Arabic),
+            0x590..=0x5FF | 0xFB1D..=0xFB4F => Some(Script::Hebrew),
+            0x3000..=0x303F | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FFFF => Some(Script::Han),
+            0x400..=0x52F => Some(Script::Cyrillic),
+            0x370..=0x3FF | 0x1F00..=0x1FFF => Some(Script::Greek),
+            0x3040..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F => Some(Script::Kana),
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Some(Script::Hangul),
+            0xE00..=0xE7F => Some(Script::Thai),
+            0x900..=0x97F => Some(Script::Devanagari),
+            _ => None,
+        }
+    }
+
+    /// Main script of a text: the first script other than Latin it contains,
+    /// since Latin text is covered by most fonts of other scripts as well.
+    pub fn detect(text: &str) -> Option<Script> {
+        let mut scripts = text.chars().filter_map(Script::of);
+        let first = scripts.next()?;
+        if first != Script::Latin {
+            return Some(first);
+        }
+        Some(scripts.find(|script| *script != Script::Latin).unwrap_or(Script::Latin))
+    }
+
+    // Representative characters a font must contain to be considered covering the script
+    fn sample(&self) -> &'static str {
+        match self {
+            Script::Latin => "AaZz",
+            Script::Arabic => "ابتئۇ",
+            Script::Hebrew => "אבגש",
+            Script::Han => "中文字海报",
+            Script::Cyrillic => "АаЯя",
+            Script::Greek => "ΑαΩω",
+            Script::Kana => "あいアイ",
+            Script::Hangul => "가나한글",
+            Script::Thai => "กขคง",
+            Script::Devanagari => "अकखग",
+        }
+    }
+}
+
+impl FromStr for Script {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Script::ALL
+            .into_iter()
+            .find(|script| script.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Script::ALL.iter().map(|script| script.name()).collect();
+                format!("unknown script '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+/// Ordered font families to try for text that doesn't name an installed font.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FontFallback {
+    /// Families to try first for text in a script, in order.
+    #[serde(default)]
+    pub scripts: HashMap<Script, Vec<String>>,
+    /// Families to try for any text once its script's families are exhausted.
+    #[serde(default = "default_fallback_families")]
+    pub default: Vec<String>,
+}
+
+impl Default for FontFallback {
+    fn default() -> Self {
+        Self { scripts: HashMap::new(), default: default_fallback_families() }
+    }
+}
+
+impl FontFallback {
+    /// Sets the families tried first for text in a script.
+    pub fn with_script<S: Into<String>>(mut self, script: Script, families: impl IntoIterator<Item = S>) -> Self {
+        self.scripts.insert(script, families.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Families to try for text in a script (or of unknown script), in order.
+    pub fn families(&self, script: Option<Script>) -> impl Iterator<Item = &str> {
+        script
+            .and_then(|script| self.scripts.get(&script))
+            .into_iter()
+            .flatten()
+            .chain(&self.default)
+            .map(String::as_str)
+    }
+}
+
+fn default_fallback_families() -> Vec<String> {
+    // Arial Unicode MS first for its Unicode coverage
+    ["Arial Unicode MS", "Arial", "Helvetica", "Times New Roman"].map(String::from).to_vec()
+}
+
+/// How glyph edges are smoothed.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FontEdgingType {
+    /// No smoothing: every pixel is either covered or not.
+    Alias,
+    /// Grayscale anti-aliasing (default).
+    #[default]
+    Antialias,
+    /// LCD anti-aliasing, using the red, green and blue subpixels of each pixel.
+    /// Only applies to text drawn on an opaque background.
+    Subpixel,
+}
+
+/// How strongly glyph outlines are snapped to the pixel grid.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FontHintingType {
+    /// Outlines are kept as designed.
+    None,
+    /// Outlines are snapped vertically only.
+    Slight,
+    /// Default hinting.
+    #[default]
+    Normal,
+    /// Outlines are snapped as far as the font allows, for the crispest small text.
+    Full,
+}
+
+/// Rasterization settings applied to every font of a poster.
+///
+/// Fonts otherwise render with Skia's defaults, whose results differ slightly
+/// between platforms:
+///
+/// ```json
+/// "font_rendering": { "edging": "antialias", "hinting": "slight", "subpixel_positioning": true }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+pub struct FontRendering {
+    /// How glyph edges are smoothed.
+    #[serde(default)]
+    pub edging: FontEdgingType,
+    /// How strongly glyph outlines are snapped to the pixel grid.
+    #[serde(default)]
+    pub hinting: FontHintingType,
+    /// Place glyphs at fractional pixel positions, for more even spacing in small text.
+    #[serde(default)]
+    pub subpixel_positioning: bool,
+}
+
+impl FontRendering {
+    /// Applies the settings to a font.
+    pub fn apply(&self, font: &mut Font) {
+        font.set_edging(match self.edging {
+            FontEdgingType::Alias => Edging::Alias,
+            FontEdgingType::Antialias => Edging::AntiAlias,
+            FontEdgingType::Subpixel => Edging::SubpixelAntiAlias,
+        });
+        font.set_hinting(match self.hinting {
+            FontHintingType::None => FontHinting::None,
+            FontHintingType::Slight => FontHinting::Slight,
+            FontHintingType::Normal => FontHinting::Normal,
+            FontHintingType::Full => FontHinting::Full,
+        });
+        font.set_subpixel(self.subpixel_positioning);
+    }
+}
+
+/// A font sent along with a poster's config, which its elements can use as a
+/// `font_family` without the font being installed or on disk:
+///
+/// ```json
+/// "fonts": [{ "name": "Brand Sans", "base64": "AAEAAAASAQAABAAgR0RFRr..." }]
+/// ```
+///
+/// The name is only visible to the poster that sends the font, so posters of
+/// different users rendered by one process can't see each other's fonts.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct EmbeddedFont {
+    /// Family name the elements use in `font_family`.
+    pub name: String,
+    /// Font file (TTF, OTF, ...) encoded as base64.
+    pub base64: String,
+}
+
+/// Largest font file accepted in a config's `fonts`, in bytes.
+pub const MAX_EMBEDDED_FONT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Most fonts accepted in a config's `fonts`.
+pub const MAX_EMBEDDED_FONTS: usize = 16;
+
+// Number of decoded fonts kept for later posters sending the same font file
+const TYPEFACE_CACHE_SIZE: usize = 64;
+
+// Decoded fonts, keyed by a hash of their font file. The hash only narrows the
+// search: a cached typeface is used only if its font file is the same.
+static TYPEFACES: LazyLock<Mutex<HashMap<u64, Typeface>>> = LazyLock::new(Mutex::default);
+
+/// Decodes embedded fonts by name. Fonts that are too large, or can't be decoded,
+/// are left out with a warning. Font files already decoded for another poster
+/// are reused.
+pub(crate) fn load_embedded_fonts(fonts: &[EmbeddedFont]) -> HashMap<String, Typeface> {
+    if fonts.len() > MAX_EMBEDDED_FONTS {
+        warn!("Only the first {} of {} embedded fonts are used", MAX_EMBEDDED_FONTS, fonts.len());
+    }
+    fonts
+        .iter()
+        .take(MAX_EMBEDDED_FONTS)
+        .filter_map(|font| match load_embedded_font(font) {
+            Ok(typeface) => Some((font.name.clone(), typeface)),
+            Err(err) => {
+                warn!("Skipping embedded font '{}': {}", font.name, err);
+                None
+            }
+        })
+        .collect()
+}
+
+fn load_embedded_font(font: &EmbeddedFont) -> Result<Typeface, String> {
+    // Checked before decoding, so oversized fonts are never held in memory twice
+    if font.base64.len() / 4 * 3 > MAX_EMBEDDED_FONT_BYTES {
+        return Err(format!("larger than {} bytes", MAX_EMBEDDED_FONT_BYTES));
+    }
+    let bytes = general_purpose::STANDARD
+        .decode(font.base64.trim())
+        .map_err(|err| format!("invalid base64: {}", err))?;
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    let key = hasher.finish();
+    let cached = TYPEFACES.lock().unwrap_or_else(PoisonError::into_inner).get(&key).cloned();
+    if let Some(typeface) = cached {
+        // Another poster's font must never be used in place of this one
+        if typeface.to_font_data().is_some_and(|(data, _)| data == bytes) {
+            return Ok(typeface);
+        }
+    }
+
+    let typeface = FontMgr::new()
+        .new_from_data(&bytes, None)
+        .ok_or_else(|| "not a supported font file".to_string())?;
+
+    let mut typefaces = TYPEFACES.lock().unwrap_or_else(PoisonError::into_inner);
+    // Rarely reached, so the whole cache is simply dropped rather than tracking use
+    if typefaces.len() >= TYPEFACE_CACHE_SIZE {
+        typefaces.clear();
+    }
+    typefaces.insert(key, typeface.clone());
+    Ok(typeface)
+}
+
+/// A font family visible to the renderer.
+#[derive(Debug, Clone, Serialize)]
+pub struct FontFamilyInfo {
+    /// Family name, as used in `font_family`.
+    pub family: String,
+    /// Style names available in the family (e.g. "Regular", "Bold").
+    pub styles: Vec<String>,
+    /// Font file the family was loaded from, or `None` for system fonts.
+    pub path: Option<PathBuf>,
+    /// Scripts the family's default style has glyphs for.
+    pub scripts: Vec<Script>,
+}
+
+/// Returns `true` if the typeface has glyphs for all sample characters of the script.
+pub fn covers_script(typeface: &Typeface, script: Script) -> bool {
+    script.sample().chars().all(|c| typeface.unichar_to_glyph(c as i32) != 0)
+}
+
+/// Lists the font families installed on the system, sorted by name.
+pub fn system_fonts() -> Vec<FontFamilyInfo> {
+    let font_mgr = FontMgr::default();
+    let mut families = Vec::new();
+
+    for index in 0..font_mgr.count_families() {
+        let family = font_mgr.family_name(index);
+        let mut style_set = font_mgr.new_style_set(index);
+
+        let styles = (0..style_set.count())
+            .map(|i| {
+                let (style, name) = style_set.style(i);
+                name.unwrap_or_else(|| describe_style(style))
+            })
+            .collect();
+        let scripts = style_set
+            .match_style(FontStyle::normal())
+            .map(|typeface| scripts_covered(&typeface))
+            .unwrap_or_default();
+
+        families.push(FontFamilyInfo { family, styles, path: None, scripts });
+    }
+
+    families.sort_by(|a, b| a.family.cmp(&b.family));
+    families
+}
+
+/// Lists the fonts in a directory (`.ttf`, `.otf`, `.ttc`), searched recursively.
+///
+/// Files that cannot be parsed as fonts are skipped.
+pub fn fonts_in_dir<P: AsRef<Path>>(dir: P) -> std::io::Result<Vec<FontFamilyInfo>> {
+    let font_mgr = FontMgr::new();
+    let mut fonts = Vec::new();
+    let mut pending = vec![dir.as_ref().to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+
+            let is_font = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "ttf" | "otf" | "ttc"));
+            if !is_font {
+                continue;
+            }
+
+            let bytes = std::fs::read(&path)?;
+            if let Some(typeface) = font_mgr.new_from_data(&bytes, None) {
+                fonts.push(FontFamilyInfo {
+                    family: typeface.family_name(),
+                    styles: vec![describe_style(typeface.font_style())],
+                    path: Some(path),
+                    scripts: scripts_covered(&typeface),
+                });
+            }
+        }
+    }
+
+    fonts.sort_by(|a, b| a.family.cmp(&b.family));
+    Ok(fonts)
+}
+
+fn scripts_covered(typeface: &Typeface) -> Vec<Script> {
+    Script::ALL.into_iter().filter(|script| covers_script(typeface, *script)).collect()
+}
+
+// Fallback style description for fonts that don't name their styles
+fn describe_style(style: FontStyle) -> String {
+    let weight = *style.weight();
+    let weight = match weight {
+        w if w <= 150 => "Thin",
+        w if w <= 250 => "ExtraLight",
+        w if w <= 350 => "Light",
+        w if w <= 450 => "Regular",
+        w if w <= 550 => "Medium",
+        w if w <= 650 => "SemiBold",
+        w if w <= 750 => "Bold",
+        w if w <= 850 => "ExtraBold",
+        _ => "Black",
+    };
+    match style.slant() {
+        skia_safe::font_style::Slant::Upright => weight.to_string(),
+        _ => format!("{} Italic", weight),
+    }
+}
@@ -0,0 +1,315 @@
+//! Procedural artwork, so posters get unique backgrounds without image assets.
+//!
+//! A [`GenerativeElement`] draws mesh gradients, soft blobs or a geometric
+//! pattern from a seed and a palette. The same seed and palette always give the
+//! same artwork; without a seed, every poster gets its own:
+//!
+//! ```json
+//! { "type": "generative", "style": "blobs", "seed": 42, "palette": ["#fdf6ec", "#f7a399", "#a0c4ff"] }
+//! ```
+//!
+//! The first palette color fills the region, and the others are used for the artwork.
+
+use crate::{parse_color, Element, PosterElement, RenderContext, Result};
+use serde::{Deserialize, Serialize};
+use skia_safe::{BlurStyle, Canvas, Color, MaskFilter, Paint, Path as SkPath, Point, Rect, Shader, TileMode};
+use std::f32::consts::TAU;
+
+/// Most glows or blobs drawn by a [`GenerativeElement`]; each one is a full draw of its shape.
+pub const MAX_GENERATIVE_COUNT: usize = 64;
+
+/// Kind of artwork drawn by a [`GenerativeElement`].
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GenerativeStyle {
+    /// Overlapping soft color glows, like a mesh gradient (default).
+    #[default]
+    Mesh,
+    /// Rounded organic shapes with soft edges.
+    Blobs,
+    /// A grid of cells, each holding a circle, quarter circle, triangle or square.
+    Geometric,
+}
+
+/// Procedurally generated artwork configuration.
+///
+/// The region defaults to the whole canvas; give it a low z-index to use it as a background.
+///
+/// # Example
+///
+/// ```
+/// use poster_generator::{GenerativeElement, GenerativeStyle, PosterGenerator};
+///
+/// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+/// generator.add_generative(GenerativeElement {
+///     style: GenerativeStyle::Geometric,
+///     seed: 7,
+///     z_index: Some(-1),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GenerativeElement {
+    /// Kind of artwork.
+    #[serde(default)]
+    pub style: GenerativeStyle,
+    /// Seed of the artwork; the same seed always gives the same result. Picked at
+    /// random when the element is created if not set.
+    #[serde(default = "random_seed")]
+    pub seed: u64,
+    /// Colors in hex format: the fill of the region, then the artwork colors.
+    #[serde(default = "default_generative_palette")]
+    pub palette: Vec<String>,
+    /// Number of glows or blobs. Defaults to twice the number of artwork colors for
+    /// mesh gradients and 5 for blobs, and is at most [`MAX_GENERATIVE_COUNT`];
+    /// unused by geometric patterns.
+    pub count: Option<usize>,
+    /// Size of the cells of geometric patterns in pixels.
+    #[serde(default = "default_cell_size")]
+    pub cell_size: f32,
+    /// X-coordinate of the region (top-left corner). Defaults to 0.
+    pub x: Option<f32>,
+    /// Y-coordinate of the region (top-left corner). Defaults to 0.
+    pub y: Option<f32>,
+    /// Width of the region. Defaults to the canvas width.
+    pub width: Option<f32>,
+    /// Height of the region. Defaults to the canvas height.
+    pub height: Option<f32>,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+impl Default for GenerativeElement {
+    fn default() -> Self {
+        Self {
+            style: GenerativeStyle::default(),
+            seed: random_seed(),
+            palette: default_generative_palette(),
+            count: None,
+            cell_size: default_cell_size(),
+            x: None,
+            y: None,
+            width: None,
+            height: None,
+            id: None,
+            z_index: None,
+        }
+    }
+}
+
+fn default_generative_palette() -> Vec<String> {
+    ["#fdf6ec", "#f9c5b4", "#f7a399", "#c3b1e1", "#a0c4ff"].map(String::from).to_vec()
+}
+
+fn default_cell_size() -> f32 {
+    80.0
+}
+
+impl GenerativeElement {
+    fn count(&self, default: usize) -> usize {
+        self.count.unwrap_or(default).min(MAX_GENERATIVE_COUNT)
+    }
+
+    fn rect(&self, ctx: &RenderContext) -> Rect {
+        let canvas = ctx.canvas_rect();
+        Rect::from_xywh(
+            self.x.unwrap_or(canvas.left),
+            self.y.unwrap_or(canvas.top),
+            self.width.unwrap_or(canvas.width()),
+            self.height.unwrap_or(canvas.height()),
+        )
+    }
+}
+
+impl PosterElement for GenerativeElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Generative(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let palette = if self.palette.is_empty() { default_generative_palette() } else { self.palette.clone() };
+        let colors = palette
+            .iter()
+            .map(|color| parse_color(color, "palette"))
+            .collect::<Result<Vec<_>>>()?;
+        // With a single color, the artwork uses it too
+        let (fill, accents) = match colors.split_first() {
+            Some((fill, [])) => (*fill, &colors[..]),
+            Some((fill, accents)) => (*fill, accents),
+            None => unreachable!("the palette is never empty"),
+        };
+
+        let rect = self.rect(ctx);
+        let mut rng = Rng::new(self.seed);
+        canvas.save();
+        canvas.clip_rect(rect, None, true);
+        let mut background = Paint::default();
+        background.set_color(fill);
+        canvas.draw_rect(rect, &background);
+        match self.style {
+            GenerativeStyle::Mesh => {
+                draw_mesh(canvas, rect, accents, self.count(accents.len() * 2), &mut rng, ctx.dither())
+            }
+            GenerativeStyle::Blobs => draw_blobs(canvas, rect, accents, self.count(5), &mut rng),
+            GenerativeStyle::Geometric => draw_geometric(canvas, rect, accents, self.cell_size, &mut rng),
+        }
+        canvas.restore();
+        Ok(())
+    }
+
+    fn bounds(&self, ctx: &RenderContext) -> Option<Rect> {
+        Some(self.rect(ctx))
+    }
+}
+
+// Radial glows fading to transparent, scattered over and just beyond the region
+fn draw_mesh(canvas: &Canvas, rect: Rect, colors: &[Color], count: usize, rng: &mut Rng, dither: bool) {
+    let size = rect.width().max(rect.height());
+    for i in 0..count {
+        let color = colors[i % colors.len()];
+        let center = Point::new(
+            rect.left + rect.width() * rng.range(-0.1, 1.1),
+            rect.top + rect.height() * rng.range(-0.1, 1.1),
+        );
+        let radius = size * rng.range(0.35, 0.8);
+        let gradient = [color, color.with_a(0)];
+        let Some(shader) = Shader::radial_gradient(center, radius, &gradient[..], None, TileMode::Clamp, None, None)
+        else {
+            continue;
+        };
+        let mut paint = Paint::default();
+        paint.set_shader(shader);
+        paint.set_dither(dither);
+        canvas.draw_rect(rect, &paint);
+    }
+}
+
+// Smooth closed shapes with wobbly outlines and blurred edges
+fn draw_blobs(canvas: &Canvas, rect: Rect, colors: &[Color], count: usize, rng: &mut Rng) {
+    const POINTS: usize = 8;
+    let size = rect.width().min(rect.height());
+    for i in 0..count {
+        let center = Point::new(
+            rect.left + rect.width() * rng.next_f32(),
+            rect.top + rect.height() * rng.next_f32(),
+        );
+        let radius = size * rng.range(0.15, 0.35);
+        let outline: Vec<Point> = (0..POINTS)
+            .map(|k| {
+                let angle = k as f32 / POINTS as f32 * TAU;
+                let r = radius * rng.range(0.7, 1.3);
+                center + Point::new(angle.cos() * r, angle.sin() * r)
+            })
+            .collect();
+
+        // Curve through the midpoints between outline points, which keeps the shape smooth
+        let mid = |a: Point, b: Point| Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+        let mut path = SkPath::new();
+        path.move_to(mid(outline[POINTS - 1], outline[0]));
+        for k in 0..POINTS {
+            path.quad_to(outline[k], mid(outline[k], outline[(k + 1) % POINTS]));
+        }
+        path.close();
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(colors[i % colors.len()].with_a(217));
+        paint.set_mask_filter(MaskFilter::blur(BlurStyle::Normal, radius * 0.08, false));
+        canvas.draw_path(&path, &paint);
+    }
+}
+
+// Grid of cells, each with a random shape in a random palette color
+fn draw_geometric(canvas: &Canvas, rect: Rect, colors: &[Color], cell_size: f32, rng: &mut Rng) {
+    let cell = cell_size.max(4.0);
+    let columns = (rect.width() / cell).ceil() as usize;
+    let rows = (rect.height() / cell).ceil() as usize;
+    for row in 0..rows {
+        for column in 0..columns {
+            let cell_rect = Rect::from_xywh(rect.left + column as f32 * cell, rect.top + row as f32 * cell, cell, cell);
+            let mut paint = Paint::default();
+            paint.set_anti_alias(true);
+            paint.set_color(*rng.pick(colors));
+
+            // One corner of the cell, for quarter circles and triangles
+            let corners = [
+                Point::new(cell_rect.left, cell_rect.top),
+                Point::new(cell_rect.right, cell_rect.top),
+                Point::new(cell_rect.right, cell_rect.bottom),
+                Point::new(cell_rect.left, cell_rect.bottom),
+            ];
+            let corner = (rng.next_u64() % 4) as usize;
+            match rng.next_u64() % 5 {
+                0 => {
+                    canvas.draw_circle(cell_rect.center(), cell * 0.4, &paint);
+                }
+                1 => {
+                    canvas.save();
+                    canvas.clip_rect(cell_rect, None, true);
+                    canvas.draw_circle(corners[corner], cell, &paint);
+                    canvas.restore();
+                }
+                2 => {
+                    let mut path = SkPath::new();
+                    path.move_to(corners[corner]);
+                    path.line_to(corners[(corner + 1) % 4]);
+                    path.line_to(corners[(corner + 3) % 4]);
+                    path.close();
+                    canvas.draw_path(&path, &paint);
+                }
+                3 => {
+                    canvas.draw_rect(cell_rect.with_inset((cell * 0.2, cell * 0.2)), &paint);
+                }
+                // Empty cells keep the pattern from looking crowded
+                _ => {}
+            }
+        }
+    }
+}
+
+// Seed for elements created without one
+fn random_seed() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    std::collections::hash_map::RandomState::new().build_hasher().finish()
+}
+
+// Small deterministic random number generator (SplitMix64), so the artwork only
+// depends on the seed and not on the platform or a dependency's version
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1)
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[(self.next_u64() % items.len() as u64) as usize]
+    }
+}
@@ -0,0 +1,219 @@
+//! Named icons for the icon element.
+//!
+//! A small set of common icons is built in (see [`BUILTIN_ICONS`]). More icons can be
+//! registered at startup, either as SVG path data or as a glyph from an icon font:
+//!
+//! ```
+//! use poster_generator::{icons, IconElement, PosterGenerator};
+//!
+//! // A 24x24 diamond
+//! icons::register_svg_path("diamond", "M12 2 22 12 12 22 2 12z", 24.0);
+//!
+//! let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+//! generator.add_icon(IconElement {
+//!     name: "diamond".to_string(),
+//!     x: 100.0,
+//!     y: 100.0,
+//!     size: 48.0,
+//!     color: "#3f51b5".to_string(),
+//!     ..Default::default()
+//! });
+//! ```
+
+use crate::{
+    load_font_from_file, parse_color, Element, MissingAssetPolicy, PosterElement, PosterError, RenderContext, Result,
+};
+use serde::{Deserialize, Serialize};
+use skia_safe::{Canvas, Paint, Path as SkPath, Point, Rect, TextBlob};
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use tracing::warn;
+
+/// Names and SVG path data (24x24 view box) of the built-in icons.
+///
+/// The paths are from Material Icons (Apache License 2.0).
+pub const BUILTIN_ICONS: &[(&str, &str)] = &[
+    ("arrow-left", "M20 11H7.83l5.59-5.59L12 4l-8 8 8 8 1.41-1.41L7.83 13H20v-2z"),
+    ("arrow-right", "M12 4l-1.41 1.41L16.17 11H4v2h12.17l-5.58 5.59L12 20l8-8z"),
+    ("check", "M9 16.17L4.83 12l-1.42 1.41L9 19 21 7l-1.41-1.41z"),
+    ("check-circle", "M12 2C6.48 2 2 6.48 2 12s4.48 10 10 10 10-4.48 10-10S17.52 2 12 2zm-2 15l-5-5 1.41-1.41L10 14.17l7.59-7.59L19 8l-9 9z"),
+    ("close", "M19 6.41L17.59 5 12 10.59 6.41 5 5 6.41 10.59 12 5 17.59 6.41 19 12 13.41 17.59 19 19 17.59 13.41 12z"),
+    ("heart", "M12 21.35l-1.45-1.32C5.4 15.36 2 12.28 2 8.5 2 5.42 4.42 3 7.5 3c1.74 0 3.41.81 4.5 2.09C13.09 3.81 14.76 3 16.5 3 19.58 3 22 5.42 22 8.5c0 3.78-3.4 6.86-8.55 11.54L12 21.35z"),
+    ("info", "M12 2C6.48 2 2 6.48 2 12s4.48 10 10 10 10-4.48 10-10S17.52 2 12 2zm1 15h-2v-6h2v6zm0-8h-2V7h2v2z"),
+    ("location", "M12 2C8.13 2 5 5.13 5 9c0 5.25 7 13 7 13s7-7.75 7-13c0-3.87-3.13-7-7-7zm0 9.5c-1.38 0-2.5-1.12-2.5-2.5s1.12-2.5 2.5-2.5 2.5 1.12 2.5 2.5-1.12 2.5-2.5 2.5z"),
+    ("mail", "M20 4H4c-1.1 0-1.99.9-1.99 2L2 18c0 1.1.9 2 2 2h16c1.1 0 2-.9 2-2V6c0-1.1-.9-2-2-2zm0 4l-8 5-8-5V6l8 5 8-5v2z"),
+    ("phone", "M6.62 10.79c1.44 2.83 3.76 5.14 6.59 6.59l2.2-2.2c.27-.27.67-.36 1.02-.24 1.12.37 2.33.57 3.57.57.55 0 1 .45 1 1V20c0 .55-.45 1-1 1-9.39 0-17-7.61-17-17 0-.55.45-1 1-1h3.5c.55 0 1 .45 1 1 0 1.25.2 2.45.57 3.57.11.35.03.74-.25 1.02l-2.2 2.2z"),
+    ("star", "M12 17.27L18.18 21l-1.64-7.03L22 9.24l-7.19-.61L12 2 9.19 8.63 2 9.24l5.46 4.73L5.82 21z"),
+    ("warning", "M1 21h22L12 2 1 21zm12-3h-2v-2h2v2zm0-4h-2v-4h2v4z"),
+];
+
+/// Where a registered icon is drawn from.
+#[derive(Debug, Clone)]
+pub enum IconSource {
+    /// SVG path data drawn in a square view box of the given size.
+    SvgPath {
+        /// SVG path data (the `d` attribute).
+        path: String,
+        /// Size of the square view box the path is drawn in.
+        view_box: f32,
+    },
+    /// A glyph from an icon font file.
+    FontGlyph {
+        /// Path of the font file.
+        font_file: String,
+        /// Character the icon is mapped to in the font.
+        glyph: char,
+    },
+}
+
+fn registry() -> &'static RwLock<HashMap<String, IconSource>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, IconSource>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Registers an icon drawn from SVG path data in a square view box (e.g. 24 for 24x24 icons).
+///
+/// Registering an existing name, including a built-in one, replaces it.
+pub fn register_svg_path(name: &str, path: impl Into<String>, view_box: f32) {
+    register(name, IconSource::SvgPath { path: path.into(), view_box });
+}
+
+/// Registers an icon drawn from a glyph of an icon font.
+///
+/// Registering an existing name, including a built-in one, replaces it.
+pub fn register_font_glyph(name: &str, font_file: impl Into<String>, glyph: char) {
+    register(name, IconSource::FontGlyph { font_file: font_file.into(), glyph });
+}
+
+/// Registers an icon from any source.
+pub fn register(name: &str, source: IconSource) {
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.to_string(), source);
+}
+
+/// Looks up an icon by name, checking registered icons before the built-in set.
+pub fn lookup(name: &str) -> Option<IconSource> {
+    if let Some(source) = registry().read().unwrap_or_else(|e| e.into_inner()).get(name) {
+        return Some(source.clone());
+    }
+    BUILTIN_ICONS
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, path)| IconSource::SvgPath { path: path.to_string(), view_box: 24.0 })
+}
+
+/// Icon element configuration.
+///
+/// Draws a named icon from the built-in set or from icons registered with this module,
+/// scaled to a square of `size` pixels.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct IconElement {
+    /// Name of the icon (e.g. "star", "check-circle").
+    pub name: String,
+    /// X-coordinate of the icon (top-left corner).
+    pub x: f32,
+    /// Y-coordinate of the icon (top-left corner).
+    pub y: f32,
+    /// Width and height of the icon.
+    #[serde(default = "default_icon_size")]
+    pub size: f32,
+    /// Icon color in hex format.
+    #[serde(default = "default_icon_color")]
+    pub color: String,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+    /// Missing asset policy for icon font files, overriding the poster-wide policy.
+    #[serde(default)]
+    pub on_missing_asset: Option<MissingAssetPolicy>,
+}
+
+impl Default for IconElement {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            x: 0.0,
+            y: 0.0,
+            size: default_icon_size(),
+            color: default_icon_color(),
+            id: None,
+            z_index: None,
+            on_missing_asset: None,
+        }
+    }
+}
+
+fn default_icon_size() -> f32 {
+    24.0
+}
+
+fn default_icon_color() -> String {
+    "#000000".to_string()
+}
+
+impl PosterElement for IconElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Icon(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let source = lookup(&self.name).ok_or_else(|| PosterError::UnknownIcon(self.name.clone()))?;
+
+        let mut paint = Paint::default();
+        paint.set_color(parse_color(&self.color, "color")?);
+        paint.set_anti_alias(true);
+
+        match source {
+            IconSource::SvgPath { path, view_box } => {
+                let path = SkPath::from_svg(&path).ok_or_else(|| {
+                    PosterError::RenderError(format!("Invalid SVG path for icon '{}'", self.name))
+                })?;
+                let scale = if view_box > 0.0 { self.size / view_box } else { 1.0 };
+
+                canvas.save();
+                canvas.translate((self.x, self.y));
+                canvas.scale((scale, scale));
+                canvas.draw_path(&path, &paint);
+                canvas.restore();
+            }
+            IconSource::FontGlyph { font_file, glyph } => {
+                let Some(font) = load_font_from_file(&font_file, self.size, ctx) else {
+                    let err = PosterError::FontNotFound(font_file);
+                    return match ctx.missing_asset_policy(self.on_missing_asset) {
+                        MissingAssetPolicy::Error => Err(err),
+                        MissingAssetPolicy::Skip | MissingAssetPolicy::Placeholder => {
+                            warn!("Skipping icon '{}': {}", self.name, err);
+                            Ok(())
+                        }
+                    };
+                };
+
+                // Center the glyph's ink bounds in the icon square
+                let text = glyph.to_string();
+                let (_, ink) = font.measure_str(&text, Some(&paint));
+                if let Some(blob) = TextBlob::new(&text, &font) {
+                    let x = self.x + (self.size - ink.width()) / 2.0 - ink.left;
+                    let y = self.y + (self.size - ink.height()) / 2.0 - ink.top;
+                    canvas.draw_text_blob(blob, Point::new(x, y), &paint);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        Some(Rect::from_xywh(self.x, self.y, self.size, self.size))
+    }
+}
@@ -0,0 +1,152 @@
+//! Incremental re-rendering for preview and watch workflows.
+//!
+//! An [`IncrementalRenderer`] keeps the surface of the last render. When it is
+//! given a config that only differs in a few elements, it redraws just the
+//! region those elements cover (before and after the change) instead of the
+//! whole canvas. Anything it can't reason about, such as a changed canvas
+//! size or an element with unknown bounds, falls back to a full render.
+//! The [warnings](crate::warnings) of the latest render are kept as well.
+//!
+//! ```no_run
+//! use poster_generator::incremental::IncrementalRenderer;
+//! use poster_generator::PosterConfig;
+//!
+//! let mut renderer = IncrementalRenderer::new();
+//! # let load = || -> PosterConfig { unimplemented!() };
+//! loop {
+//!     let config: PosterConfig = load();
+//!     let png = renderer.render(&config).expect("Failed to render");
+//!     std::fs::write("preview.png", png).unwrap();
+//! }
+//! ```
+
+use crate::{effect_reach, Element, PosterConfig, PosterGenerator, RenderWarning, Result, AUTO_COLOR};
+use serde_json::Value;
+use skia_safe::{Rect, Surface};
+use tracing::debug;
+
+// Extra room around element bounds for anti-aliasing and glyph overhang
+const EDGE_MARGIN: f32 = 4.0;
+
+/// Renders a sequence of configs, redrawing only what changed since the previous one.
+#[derive(Default)]
+pub struct IncrementalRenderer {
+    previous: Option<Previous>,
+}
+
+// What the last render left behind
+struct Previous {
+    surface: Surface,
+    png: Vec<u8>,
+    settings: Value,
+    elements: Vec<Value>,
+    areas: Vec<Option<Rect>>,
+    warnings: Vec<RenderWarning>,
+}
+
+impl IncrementalRenderer {
+    /// Creates a renderer with nothing rendered yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renders the config as PNG data, reusing the previous render where possible.
+    pub fn render(&mut self, config: &PosterConfig) -> Result<Vec<u8>> {
+        let elements = config.elements.iter().map(serde_json::to_value).collect::<serde_json::Result<Vec<_>>>()?;
+        let settings = serde_json::to_value(PosterConfig { elements: Vec::new(), ..config.clone() })?;
+
+        let generator = PosterGenerator::from_config(config.clone());
+        let ctx = generator.render_context();
+        let areas: Vec<Option<Rect>> = generator
+            .themed_elements()?
+            .iter()
+            .zip(&config.elements)
+            .map(|(element, config)| element.bounds(&ctx).map(|bounds| bounds.with_outset(margin(config))))
+            .collect();
+
+        let previous = self.previous.take().filter(|previous| previous.settings == settings);
+        let (mut surface, dirty, previous_warnings) = match previous {
+            Some(previous) => {
+                let dirty = dirty_region(&previous, &elements, &areas);
+                // Elements that read what is beneath them see stale pixels at the edge of a partial redraw
+                let reads_backdrop = config.elements.iter().zip(&areas).any(|(element, area)| {
+                    matches!(element, Element::BackdropBlur(_) | Element::Pixelate(_))
+                        && dirty.flatten().is_none_or(|dirty| area.is_none_or(|area| area.intersects(dirty)))
+                });
+                match dirty {
+                    Some(None) => {
+                        debug!("Config unchanged, reusing previous render");
+                        let png = previous.png.clone();
+                        self.previous = Some(previous);
+                        return Ok(png);
+                    }
+                    // A supersampled partial redraw would need the larger surface, which isn't kept,
+                    // and an "auto" background changes with the images, wherever they are
+                    Some(Some(dirty))
+                        if !reads_backdrop && config.quality.supersample <= 1 && config.background_color != AUTO_COLOR =>
+                    {
+                        (previous.surface, Some(dirty), previous.warnings)
+                    }
+                    _ => (previous.surface, None, Vec::new()),
+                }
+            }
+            None => (generator.create_surface()?, None, Vec::new()),
+        };
+
+        let warnings = match dirty {
+            Some(dirty) => {
+                debug!(?dirty, "Redrawing changed region");
+                let skip = |index: usize| areas[index].is_some_and(|area| !area.intersects(dirty));
+                let drawn = generator.draw(surface.canvas(), Some(dirty), skip, true)?;
+                // Elements left out of the redraw are unchanged, and so are their warnings
+                let mut warnings: Vec<_> =
+                    previous_warnings.into_iter().filter(|warning| warning.index < areas.len() && skip(warning.index)).collect();
+                warnings.extend(drawn.warnings);
+                warnings
+            }
+            None => {
+                generator.draw_supersampled(&mut surface, |canvas| generator.draw(canvas, None, |_| false, true))?.warnings
+            }
+        };
+        let png = generator.encode(&mut surface)?;
+
+        self.previous = Some(Previous { surface, png: png.clone(), settings, elements, areas, warnings });
+        Ok(png)
+    }
+
+    /// Quality problems found in the latest render. Empty before the first render.
+    pub fn warnings(&self) -> &[RenderWarning] {
+        self.previous.as_ref().map_or(&[], |previous| &previous.warnings)
+    }
+
+    /// Forgets the previous render, so the next one redraws the whole canvas.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+// Region covering every changed element before and after the change, snapped to whole pixels.
+// `Some(None)` means nothing changed; `None` means the region is unknown and everything must be redrawn.
+fn dirty_region(previous: &Previous, elements: &[Value], areas: &[Option<Rect>]) -> Option<Option<Rect>> {
+    let mut dirty: Option<Rect> = None;
+    for index in 0..elements.len().max(previous.elements.len()) {
+        if previous.elements.get(index) == elements.get(index) {
+            continue;
+        }
+        let before = previous.elements.get(index).map(|_| previous.areas[index]);
+        let after = elements.get(index).map(|_| areas[index]);
+        for area in [before, after].into_iter().flatten() {
+            let area = area?;
+            dirty = Some(match dirty {
+                Some(dirty) => Rect::join2(dirty, area),
+                None => area,
+            });
+        }
+    }
+    Some(dirty.map(|dirty| Rect::new(dirty.left.floor(), dirty.top.floor(), dirty.right.ceil(), dirty.bottom.ceil())))
+}
+
+// How far an element may paint beyond its bounds
+fn margin(element: &Element) -> f32 {
+    EDGE_MARGIN + effect_reach(element)
+}
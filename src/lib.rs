@@ -7,14 +7,38 @@
 //!
 //! - Create customizable poster canvas with configurable width and height
 //! - Background elements with colors, images, and rounded corners
+//! - Badges: pills that size themselves around their text
+//! - Named icons from a built-in set, SVG paths or icon fonts
+//! - Shapes (lines, rectangles, circles, SVG paths) with gradient or pattern fills and dashed strokes
+//! - Frosted-glass regions that blur whatever is rendered beneath them
+//! - Film grain overlays with adjustable intensity and blend mode
+//! - Pixelated regions for redacting faces or license plates
+//! - Procedural backgrounds (mesh gradients, blobs, geometric patterns) from a seed and palette
+//! - Native chart elements (pie, donut and bar charts, sparklines)
 //! - Image elements with positioning, scaling (cover/contain/stretch), and rounded corners
 //! - Text elements with:
 //!   - Multi-line text with automatic word wrapping
 //!   - RTL/LTR text direction support
 //!   - Custom fonts and styling
+//!   - Fallback fonts for mixed-script text (e.g. Chinese, Latin and Uyghur in one line)
 //!   - Text backgrounds with padding and border radius
 //!   - Z-index layering
 //! - Export as PNG file or base64 encoded string
+//! - Extra output sizes (e.g. Open Graph and square) rendered from the same config
+//! - Crop, resize and sharpen the finished poster before it is encoded
+//! - Brand fonts sent as base64 in the config, visible to that poster only
+//! - Animated GIF and APNG output with keyframed opacity, offset and rotation
+//! - Element ids and bounds alongside the PNG, for clickable hotspots
+//! - BlurHash placeholders of the rendered poster
+//! - Element content bound to a JSON data document with JSONPath
+//! - Texts translated into several languages, picked by locale with fallbacks
+//! - Positions and sizes as expressions of the canvas size, variables and earlier elements
+//! - Squircle corners smoothed into the sides, for images, rectangles and text backgrounds
+//! - Frames of Lottie animations as image sources (`lottie` feature)
+//! - BlurHash strings as image sources, decoded into blurred placeholders
+//! - Print output converted to an ICC profile, as CMYK TIFF or PDF (`print` feature)
+//! - Bleed and crop marks around the poster for print shops
+//! - Render warnings for elements off the canvas, overflowing text, missing fonts and upscaled images
 //!
 //! # Example
 //!
@@ -30,36 +54,90 @@
 //!     font_size: 48.0,
 //!     color: "#333333".to_string(),
 //!     align: TextAlignType::Center,
-//!     font_family: None,
-//!     max_width: None,
-//!     line_height: 1.5,
-//!     max_lines: None,
 //!     z_index: Some(1),
 //!     bold: true,
-//!     prefix: None,
-//!     background_color: None,
-//!     padding: 0.0,
-//!     border_radius: None,
-//!     width: None,
-//!     height: None,
 //!     direction: TextDirectionType::Ltr,
+//!     ..Default::default()
 //! };
 //!
 //! generator.add_text(text);
 //! generator.generate_file("output.png").expect("Failed to generate poster");
 //! ```
 
-use anyhow::Result;
 use base64::{engine::general_purpose, Engine};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::any::Any;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Instant;
 use skia_safe::{
-    Canvas, Color, Data, EncodedImageFormat, Font,
-    FontMgr, FontStyle, Image, Paint, Path as SkPath, Point, Rect,
-    TextBlob,
-    textlayout::{FontCollection, ParagraphBuilder, ParagraphStyle, TextAlign, TextDirection, TextStyle}
+    canvas::SaveLayerRec, codec, image_filters, images, paint::Style as PaintStyle, path::ArcSize, AlphaType, BlendMode,
+    BlurStyle, Canvas, ClipOp, Codec, Color, ColorType, Data, EncodedImageFormat, FilterMode, Font, FontHinting, FontMgr,
+    FontStyle, IRect, Image, ImageInfo, MaskFilter, Matrix, MipmapMode, Paint, Path as SkPath, PathDirection,
+    PixelGeometry, Point, Rect, SamplingOptions, Shader, Surface, SurfaceProps, SurfacePropsFlags, TextBlob,
+    TextBlobBuilder, TileMode, Typeface, Vector,
+    textlayout::{
+        FontCollection, Paragraph, ParagraphBuilder, ParagraphStyle, TextAlign, TextDirection, TextStyle, TypefaceFontProvider,
+    },
 };
 use thiserror::Error;
+use tracing::{debug, debug_span, info_span, warn};
+
+use assets::{AssetResolver, DefaultResolver};
+use animation::{Animation, AnimationFormat, FrameFormat, FrameSequence, Frames};
+use fonts::{EmbeddedFont, FontEdgingType, FontFallback, FontRendering, Script};
+use print::{Length, PrintFormat, PrintProfile};
+use profile::{Phase, PhaseTimer, PhaseTimings};
+use theme::Theme;
+
+pub mod animation;
+pub mod assets;
+mod badge;
+pub mod binding;
+pub mod blurhash;
+mod builder;
+mod charts;
+mod decoders;
+mod effects;
+pub mod expr;
+mod generative;
+mod pattern;
+mod post;
+pub mod filters;
+pub mod emoji;
+pub mod fonts;
+pub mod icons;
+pub mod incremental;
+pub mod locale;
+pub mod palette;
+pub mod print;
+pub mod profile;
+pub mod registry;
+mod shapes;
+mod shaping;
+pub mod template;
+pub mod testing;
+pub mod theme;
+pub mod warnings;
+
+pub use badge::BadgeElement;
+pub use builder::{BackgroundElementBuilder, ImageElementBuilder, TextElementBuilder};
+pub use icons::IconElement;
+pub use charts::{BarChartElement, PieChartElement, PieSlice, SparklineElement};
+pub use effects::{BlendModeType, NoiseElement, PixelateElement};
+pub use filters::ColorAdjustments;
+pub use generative::{GenerativeElement, GenerativeStyle, MAX_GENERATIVE_COUNT};
+pub use pattern::{Pattern, PatternKind};
+pub use post::{Crop, PostProcess, Resize, ResizeFilter};
+pub use profile::{ElementProfile, RenderProfile};
+pub use shapes::{CircleElement, LineElement, PathElement, RectElement, ShapeStyle, StrokeCap, StrokeJoin};
+pub use skia_safe;
+pub use warnings::{RenderWarning, WarningKind};
 
 /// Custom error type for poster generation.
 #[derive(Error, Debug)]
@@ -75,8 +153,57 @@ pub enum PosterError {
     /// Error occurred while encoding or saving output.
     #[error("Failed to generate output: {0}")]
     OutputError(String),
+
+    /// A font file given in `font_file` could not be loaded.
+    #[error("Font not found: {0}")]
+    FontNotFound(String),
+
+    /// A color string could not be parsed.
+    #[error("Invalid color '{value}' in field '{field}'")]
+    InvalidColor {
+        /// The color string as given.
+        value: String,
+        /// Name of the field holding the color.
+        field: String,
+    },
+
+    /// An element failed to render.
+    #[error("Failed to render element {index}")]
+    ElementRender {
+        /// Index of the element in the order it was added.
+        index: usize,
+        /// The underlying error.
+        source: Box<PosterError>,
+    },
+
+    /// An element's configuration could not be parsed.
+    #[error("Invalid element config: {0}")]
+    InvalidConfig(#[from] serde_json::Error),
+
+    /// A config element has a type that is neither built in nor registered.
+    #[error("Unknown element type '{0}'")]
+    UnknownElementType(String),
+
+    /// An icon element names an icon that is neither built in nor registered.
+    #[error("Unknown icon '{0}'")]
+    UnknownIcon(String),
+
+    /// Error reading or writing a file.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Error encoding the rendered image.
+    #[error("Failed to encode image: {0}")]
+    Encode(String),
+
+    /// An element has no config representation, e.g. a Rust type added with `add_custom`.
+    #[error("Element {0} cannot be exported to a config")]
+    NotExportable(usize),
 }
 
+/// Result type used throughout the library.
+pub type Result<T, E = PosterError> = std::result::Result<T, E>;
+
 /// Main configuration structure for poster generation.
 ///
 /// # Example
@@ -99,24 +226,180 @@ pub enum PosterError {
 ///             ..Default::default()
 ///         }),
 ///     ],
+///     ..Default::default()
 /// };
 /// ```
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct PosterConfig {
     /// Canvas width in pixels.
     pub width: u32,
     /// Canvas height in pixels.
     pub height: u32,
-    /// Background color in hex format (e.g., "#ffffff" or "#ffffffff" with alpha).
+    /// Background color in hex format (e.g., "#ffffff" or "#ffffffff" with alpha),
+    /// or "auto" for the dominant color of the poster's largest image.
     pub background_color: String,
     /// List of elements to render on the poster.
     pub elements: Vec<Element>,
+    /// What to do when an image or font file cannot be loaded, unless an element overrides it.
+    #[serde(default)]
+    pub on_missing_asset: MissingAssetPolicy,
+    /// Appearance of placeholders drawn for missing images.
+    #[serde(default)]
+    pub placeholder: Placeholder,
+    /// Optional watermark drawn over all elements.
+    #[serde(default)]
+    pub watermark: Option<Watermark>,
+    /// Directory that relative image and font paths are resolved against.
+    /// Defaults to the current directory; the CLI uses the config file's directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<PathBuf>,
+    /// Font families tried for text without an installed `font_family` or a `font_file`.
+    #[serde(default)]
+    pub font_fallback: FontFallback,
+    /// Margins kept clear of content, e.g. where a story viewer overlays its UI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safe_area: Option<SafeArea>,
+    /// Trade-off between render speed and edge quality.
+    #[serde(default)]
+    pub quality: Quality,
+    /// Glyph edging, hinting and positioning used for all text.
+    #[serde(default)]
+    pub font_rendering: FontRendering,
+    /// Dither gradients, which hides the banding of large soft gradients in 8-bit PNGs.
+    #[serde(default)]
+    pub dither: bool,
+    /// Design tokens, referenced from element strings as `"$name"`. See [`theme`].
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub theme: Theme,
+    /// Tokens replacing those of `theme` in the dark variant, rendered by
+    /// [`PosterGenerator::generate_light_and_dark`].
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub dark_theme: Theme,
+    /// Other sizes to render the poster at, with [`PosterGenerator::generate_outputs`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<OutputSize>,
+    /// Crop, resize and sharpen steps applied to the poster before it is encoded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post: Option<PostProcess>,
+    /// Font files sent with the config, usable as `font_family` by its elements.
+    /// See [`EmbeddedFont`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fonts: Vec<EmbeddedFont>,
+    /// Keyframed element properties, rendered by [`PosterGenerator::generate_animation`].
+    /// See [`animation`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation: Option<Animation>,
+    /// Printing condition rendered for by [`PosterGenerator::generate_print`]. See [`print`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub print: Option<PrintProfile>,
+    /// Area added past each edge of the poster to be trimmed off after printing, as pixels
+    /// or a length such as `"3mm"`. Backgrounds extend into it. Millimeters and inches are
+    /// converted at the print profile's dpi, or 300 dpi without one. The rendered image
+    /// grows by the bleed; element metadata stays in poster coordinates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bleed: Option<Length>,
+    /// Draw trim marks at the corners of the poster, outside the bleed.
+    #[serde(default)]
+    pub crop_marks: bool,
+}
+
+/// An additional size to render a poster at, e.g. for social media previews.
+///
+/// Outputs with the poster's aspect ratio are the poster scaled. For other aspect
+/// ratios, the canvas is scaled to fit the output and centered in it, and elements
+/// that fill the poster, such as backgrounds, fill the whole output instead
+/// (see [`RenderContext::canvas_rect`]):
+///
+/// ```json
+/// "outputs": [
+///     { "width": 1200, "height": 630, "suffix": "-og" },
+///     { "width": 1080, "height": 1080, "suffix": "-square" }
+/// ]
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct OutputSize {
+    /// Width of the output in pixels.
+    pub width: u32,
+    /// Height of the output in pixels.
+    pub height: u32,
+    /// Appended to the output's file name (before the extension) by the command line tool.
+    pub suffix: String,
+}
+
+/// A poster rendered at one of its sizes, returned by [`PosterGenerator::generate_outputs`].
+#[derive(Debug, Clone)]
+pub struct RenderedOutput {
+    /// Suffix of the size, empty for the poster's own size.
+    pub suffix: String,
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    /// PNG-encoded image data.
+    pub png: Vec<u8>,
+}
+
+/// Margins of the canvas kept clear of important content.
+///
+/// The watermark is placed inside the safe area, [`RenderContext::safe_area`]
+/// exposes it to custom elements, and guides can be drawn over the poster while
+/// designing a template:
+///
+/// ```json
+/// "safe_area": { "top": 250, "bottom": 340, "show_guides": true }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct SafeArea {
+    /// Margin at the top of the canvas.
+    #[serde(default)]
+    pub top: f32,
+    /// Margin at the right of the canvas.
+    #[serde(default)]
+    pub right: f32,
+    /// Margin at the bottom of the canvas.
+    #[serde(default)]
+    pub bottom: f32,
+    /// Margin at the left of the canvas.
+    #[serde(default)]
+    pub left: f32,
+    /// Shade the margins and outline the safe area over the poster.
+    #[serde(default)]
+    pub show_guides: bool,
+}
+
+/// Trade-off between render speed and edge quality.
+///
+/// Raster surfaces have no multisampling, so smoother edges come from
+/// supersampling: the poster is drawn at a multiple of its size and scaled down.
+///
+/// ```json
+/// "quality": { "supersample": 2 }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct Quality {
+    /// Draw the poster at this many times its size and scale it down, from 1 (off) to 4.
+    /// Rendering takes about the square of this factor in time and memory.
+    #[serde(default = "default_supersample")]
+    pub supersample: u32,
+    /// Anti-alias clips such as rounded image corners, clip paths and clipped text boxes.
+    /// Turning it off is slightly faster but leaves jagged edges.
+    #[serde(default = "default_antialias_clips")]
+    pub antialias_clips: bool,
+}
+
+impl Default for Quality {
+    fn default() -> Self {
+        Self {
+            supersample: default_supersample(),
+            antialias_clips: default_antialias_clips(),
+        }
+    }
 }
 
 /// Poster element types.
 ///
 /// Elements are rendered in order of their z-index (lowest to highest).
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(tag = "type")]
 pub enum Element {
     /// Background element (always rendered first).
@@ -130,19 +413,179 @@ pub enum Element {
     /// Text element with RTL support.
     #[serde(rename = "text")]
     Text(TextElement),
+
+    /// Frosted-glass region that blurs what is rendered beneath it.
+    #[serde(rename = "backdrop_blur")]
+    BackdropBlur(BackdropBlurElement),
+
+    /// Pie or donut chart.
+    #[serde(rename = "pie_chart")]
+    PieChart(PieChartElement),
+
+    /// Bar chart.
+    #[serde(rename = "bar_chart")]
+    BarChart(BarChartElement),
+
+    /// Compact line chart of a numeric series.
+    #[serde(rename = "sparkline")]
+    Sparkline(SparklineElement),
+
+    /// Pill that sizes itself around its text.
+    #[serde(rename = "badge")]
+    Badge(BadgeElement),
+
+    /// Named icon from the built-in set or the [`icons`] registry.
+    #[serde(rename = "icon")]
+    Icon(IconElement),
+
+    /// Straight line.
+    #[serde(rename = "line")]
+    Line(LineElement),
+
+    /// Rectangle with optional rounded corners.
+    #[serde(rename = "rect")]
+    Rect(RectElement),
+
+    /// Circle.
+    #[serde(rename = "circle")]
+    Circle(CircleElement),
+
+    /// Shape drawn from SVG path data.
+    #[serde(rename = "path")]
+    Path(PathElement),
+
+    /// Film grain overlay.
+    #[serde(rename = "noise")]
+    Noise(NoiseElement),
+
+    /// Region that pixelates what is rendered beneath it.
+    #[serde(rename = "pixelate")]
+    Pixelate(PixelateElement),
+
+    /// Procedurally generated artwork, e.g. as a background.
+    #[serde(rename = "generative")]
+    Generative(GenerativeElement),
+
+    /// Element of a custom type, rendered through the [`registry`].
+    ///
+    /// Any object whose `"type"` is not a built-in element type is parsed as this variant.
+    #[serde(untagged)]
+    Custom(registry::CustomElement),
+}
+
+impl Element {
+    // The element as a drawable poster element
+    fn into_boxed(self) -> Box<dyn PosterElement> {
+        match self {
+            Element::Background(bg) => Box::new(bg),
+            Element::Image(img) => Box::new(img),
+            Element::Text(txt) => Box::new(txt),
+            Element::BackdropBlur(blur) => Box::new(blur),
+            Element::PieChart(chart) => Box::new(chart),
+            Element::BarChart(chart) => Box::new(chart),
+            Element::Sparkline(chart) => Box::new(chart),
+            Element::Badge(badge) => Box::new(badge),
+            Element::Icon(icon) => Box::new(icon),
+            Element::Line(line) => Box::new(line),
+            Element::Rect(rect) => Box::new(rect),
+            Element::Circle(circle) => Box::new(circle),
+            Element::Path(path) => Box::new(path),
+            Element::Noise(noise) => Box::new(noise),
+            Element::Pixelate(pixelate) => Box::new(pixelate),
+            Element::Generative(generative) => Box::new(generative),
+            Element::Custom(custom) => custom.into_boxed(),
+        }
+    }
 }
 
 /// Background element configuration.
 ///
 /// The background element fills the entire canvas and supports both solid colors and images.
+///
+/// A poster can stack several backgrounds, like CSS multiple backgrounds: each one is a
+/// layer with its own color or gradient, image and opacity, e.g. a texture image at
+/// low opacity over a gradient. Layers are drawn in the order they were added unless
+/// `z_index` says otherwise.
+///
+/// Each layer blends over the ones below it. The bottom layer, unless rounded, replaces
+/// the poster's `background_color` instead, so a semi-transparent color stays transparent.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct BackgroundElement {
     /// Optional background image path or base64 data URL.
     pub image: Option<String>,
-    /// Background color in hex format.
+    /// Background color in hex format. Defaults to transparent in configs, for layers
+    /// that only draw an image or a gradient.
+    #[serde(default = "default_background_layer_color")]
     pub color: String,
+    /// Optional gradient filling the background, drawn instead of `color`.
+    #[serde(default)]
+    pub gradient: Option<Gradient>,
+    /// Optional pattern (stripes, dots or checker) filling the background, drawn
+    /// instead of `color` and `gradient`.
+    #[serde(default)]
+    pub pattern: Option<Pattern>,
     /// Optional border radius for rounded corners.
     pub radius: Option<Radius>,
+    /// Repeats the image at its original size instead of scaling it to cover the canvas.
+    #[serde(default)]
+    pub repeat: Option<BackgroundRepeat>,
+    /// Missing asset policy for the background image, overriding the poster-wide policy.
+    #[serde(default)]
+    pub on_missing_asset: Option<MissingAssetPolicy>,
+    /// Optional color with alpha in hex format drawn over the background image, e.g.
+    /// `"#00000066"` to keep light text readable over a photo.
+    #[serde(default)]
+    pub overlay: Option<String>,
+    /// Optional gradient drawn over the background image, e.g. a scrim from transparent
+    /// to dark at the bottom. Takes priority over `overlay`.
+    #[serde(default)]
+    pub overlay_gradient: Option<Gradient>,
+    /// Optional border color in hex format, for an outline ring around the card.
+    #[serde(default)]
+    pub border_color: Option<String>,
+    /// Border width in pixels. The border is drawn inside the canvas, following `radius`.
+    #[serde(default)]
+    pub border_width: f32,
+    /// Opacity of the whole layer from 0.0 (invisible) to 1.0 (opaque).
+    #[serde(default = "default_background_opacity")]
+    pub opacity: f32,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering. Defaults to -1000, below all other elements.
+    pub z_index: Option<i32>,
+}
+
+impl Default for BackgroundElement {
+    fn default() -> Self {
+        Self {
+            image: None,
+            color: "#ffffff".to_string(),
+            gradient: None,
+            pattern: None,
+            radius: None,
+            repeat: None,
+            on_missing_asset: None,
+            overlay: None,
+            overlay_gradient: None,
+            border_color: None,
+            border_width: 0.0,
+            opacity: default_background_opacity(),
+            id: None,
+            z_index: None,
+        }
+    }
+}
+
+/// How a background image is repeated.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackgroundRepeat {
+    /// Repeat in both directions.
+    Tile,
+    /// Repeat horizontally along the top edge.
+    RepeatX,
+    /// Repeat vertically along the left edge.
+    RepeatY,
 }
 
 /// Image element configuration.
@@ -150,7 +593,8 @@ pub struct BackgroundElement {
 /// Supports various scaling modes and rounded corners.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ImageElement {
-    /// Image source: file path or base64 data URL.
+    /// Image source: file path, base64 data URL, `asset://` name of a registered asset, or
+    /// `blurhash:` followed by a BlurHash to draw as a blurred placeholder.
     pub src: String,
     /// X-coordinate of the image (top-left corner).
     pub x: f32,
@@ -162,11 +606,91 @@ pub struct ImageElement {
     pub height: f32,
     /// Optional border radius for rounded corners.
     pub radius: Option<Radius>,
+    /// Smoothing of the rounded corners, from 0 for circular arcs to 1 for corners that
+    /// blend into the sides like iOS app icons (a squircle).
+    #[serde(default)]
+    pub corner_smoothing: f32,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
     /// Z-index for layering (higher values are rendered on top).
     pub z_index: Option<i32>,
+    /// Optional region of the source image to draw, e.g. one sprite of a sprite sheet.
+    /// Scaling and `object_fit` apply to the cropped region.
+    #[serde(default)]
+    pub crop: Option<CropRect>,
     /// Image scaling mode.
     #[serde(default = "default_object_fit")]
     pub object_fit: ObjectFit,
+    /// Corner insets for `nine_patch` scaling. Defaults to a third of the image on each side.
+    #[serde(default)]
+    pub insets: Option<Insets>,
+    /// Optional clip shape: a preset name ("circle", "hexagon", "diamond", "triangle",
+    /// "star", "blob") or SVG path data in coordinates relative to the image's top-left corner.
+    #[serde(default)]
+    pub clip_path: Option<String>,
+    /// Color adjustments: brightness, contrast, saturation, hue rotation and inversion.
+    #[serde(flatten)]
+    pub adjustments: ColorAdjustments,
+    /// Optional block size in pixels to pixelate (mosaic) the image with.
+    #[serde(default)]
+    pub pixelate: Option<f32>,
+    /// Draw the pixels as stored, ignoring the EXIF orientation of photos.
+    #[serde(default)]
+    pub ignore_orientation: bool,
+    /// Mirror the image left to right within its box. Rounded corners and clip paths stay in place.
+    #[serde(default)]
+    pub flip_horizontal: bool,
+    /// Mirror the image top to bottom within its box. Rounded corners and clip paths stay in place.
+    #[serde(default)]
+    pub flip_vertical: bool,
+    /// Frame of an animated GIF, WebP or Lottie animation to draw, starting at 0. Defaults
+    /// to the first frame; indexes past the end draw the last frame.
+    #[serde(default)]
+    pub frame: Option<usize>,
+    /// Playback time in milliseconds of the animation frame to draw, looping over the
+    /// animation. Ignored if `frame` is set.
+    #[serde(default)]
+    pub time_ms: Option<u32>,
+    /// Position of the animation frame to draw, from 0 for the first frame to 1 for the
+    /// last. Ignored if `frame` or `time_ms` is set.
+    #[serde(default)]
+    pub progress: Option<f32>,
+    /// Missing asset policy for this image, overriding the poster-wide policy.
+    #[serde(default)]
+    pub on_missing_asset: Option<MissingAssetPolicy>,
+    /// Alternative text describing the image. Its initials are drawn on placeholders.
+    #[serde(default)]
+    pub alt: Option<String>,
+}
+
+impl Default for ImageElement {
+    fn default() -> Self {
+        Self {
+            src: String::new(),
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+            radius: None,
+            corner_smoothing: 0.0,
+            id: None,
+            z_index: None,
+            crop: None,
+            object_fit: ObjectFit::Cover,
+            insets: None,
+            clip_path: None,
+            adjustments: ColorAdjustments::default(),
+            pixelate: None,
+            ignore_orientation: false,
+            flip_horizontal: false,
+            flip_vertical: false,
+            frame: None,
+            time_ms: None,
+            progress: None,
+            on_missing_asset: None,
+            alt: None,
+        }
+    }
 }
 
 /// Text element configuration with RTL support.
@@ -176,17 +700,40 @@ pub struct ImageElement {
 pub struct TextElement {
     /// Text content to render.
     pub text: String,
-    /// X-coordinate of the text anchor point.
+    /// X-coordinate of the text anchor point, or of the box's left edge in box mode.
     pub x: f32,
-    /// Y-coordinate of the text baseline.
+    /// Y-coordinate of the text anchor point, by default the first line's baseline (see `baseline`),
+    /// or of the box's top edge in box mode.
     pub y: f32,
     /// Font size in points.
     pub font_size: f32,
     /// Text color in hex format.
     pub color: String,
+    /// Optional image (file path, base64 data URL or `asset://` name) showing through the
+    /// glyphs, scaled to cover the text box. Takes priority over `fill_gradient` and `color`.
+    #[serde(default)]
+    pub fill_image: Option<String>,
+    /// Optional gradient filling the glyphs, laid out over the text box. Takes priority over `color`.
+    #[serde(default)]
+    pub fill_gradient: Option<Gradient>,
+    /// Strength of film grain added to the glyph fill, from 0 (none) to 1.
+    #[serde(default)]
+    pub fill_noise: f32,
     /// Text alignment.
     #[serde(default = "default_text_align")]
     pub align: TextAlignType,
+    /// What `y` is aligned to. Not used in box mode.
+    #[serde(default)]
+    pub baseline: BaselineType,
+    /// How `x` and `y` position the text.
+    #[serde(default)]
+    pub position_mode: PositionMode,
+    /// Vertical alignment of the text inside its box, in box mode.
+    #[serde(default)]
+    pub vertical_align: VerticalAlignType,
+    /// What happens when the text is wider than `max_width` (or `width`) or taller than `height`.
+    #[serde(default)]
+    pub overflow: OverflowType,
     /// Optional font family name from system fonts (e.g., "Arial", "PingFang SC").
     pub font_family: Option<String>,
     /// Optional font file path (e.g., "fonts/custom.ttf", "UKIJBasma.ttf").
@@ -197,8 +744,19 @@ pub struct TextElement {
     /// Line height multiplier (e.g., 1.5 = 150% of font size).
     #[serde(default = "default_line_height")]
     pub line_height: f32,
+    /// Absolute line height in pixels. Takes priority over `line_height`.
+    #[serde(default)]
+    pub line_height_px: Option<f32>,
     /// Maximum number of lines. Text exceeding this will be truncated with ellipsis.
     pub max_lines: Option<u32>,
+    /// Text ending the last line when `max_lines` truncates, e.g. "… read more". Defaults to "...".
+    #[serde(default)]
+    pub ellipsis: Option<String>,
+    /// Color of the ellipsis in hex format. Defaults to the text color.
+    #[serde(default)]
+    pub ellipsis_color: Option<String>,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
     /// Z-index for layering.
     pub z_index: Option<i32>,
     /// Whether to use bold font weight.
@@ -208,18 +766,63 @@ pub struct TextElement {
     pub prefix: Option<String>,
     /// Optional background color for the text box.
     pub background_color: Option<String>,
-    /// Padding around the text when background color is set.
+    /// Optional gradient fill for the text box. Takes priority over `background_color`.
+    #[serde(default)]
+    pub background_gradient: Option<Gradient>,
+    /// Padding around the text when background color or border is set, or always in box mode.
     #[serde(default = "default_padding")]
     pub padding: f32,
     /// Optional border radius for the text background.
     pub border_radius: Option<Radius>,
-    /// Optional fixed width for the text box.
+    /// Smoothing of the rounded corners of the background, from 0 for circular arcs to 1 for corners that
+    /// blend into the sides like iOS app icons (a squircle).
+    #[serde(default)]
+    pub corner_smoothing: f32,
+    /// Optional border color for the text box in hex format.
+    /// Combined with no `background_color`, this draws an outlined pill.
+    pub border_color: Option<String>,
+    /// Optional gradient for the border. Takes priority over `border_color`.
+    #[serde(default)]
+    pub border_gradient: Option<Gradient>,
+    /// Border width in pixels. The border is drawn inside the text box.
+    #[serde(default)]
+    pub border_width: f32,
+    /// Optional shadow under the text box. Only drawn when a background or border is set.
+    #[serde(default)]
+    pub box_shadow: Option<BoxShadow>,
+    /// Optional marker stripe behind some of the words, drawn over the text box.
+    #[serde(default)]
+    pub highlight: Option<Highlight>,
+    /// Optional fixed width for the text box. `"auto"` (or leaving it out) fits the text.
+    #[serde(default, deserialize_with = "deserialize_auto_size")]
     pub width: Option<f32>,
-    /// Optional fixed height for the text box.
+    /// Optional fixed height for the text box. `"auto"` (or leaving it out) fits the text;
+    /// the resulting size is reported in the render metadata bounds.
+    #[serde(default, deserialize_with = "deserialize_auto_size")]
     pub height: Option<f32>,
     /// Text direction (LTR or RTL). Automatically detected if set to LTR.
     #[serde(default = "default_text_direction")]
     pub direction: TextDirectionType,
+    /// Missing asset policy for `font_file` and `fill_image`, overriding the poster-wide policy.
+    /// Both `skip` and `placeholder` fall back to the default font, or to `color` for the fill.
+    #[serde(default)]
+    pub on_missing_asset: Option<MissingAssetPolicy>,
+    /// Expand `:shortcode:` emoji (e.g. `:rocket:`) in the text before layout.
+    #[serde(default)]
+    pub expand_shortcodes: bool,
+    /// OpenType features applied to the text, e.g. `["tnum", "smcp", "-liga"]` for tabular
+    /// figures and small caps without ligatures. `"salt=2"` selects an alternate.
+    #[serde(default)]
+    pub font_features: Vec<String>,
+    /// Case change applied to the text (including the prefix) before layout.
+    #[serde(default)]
+    pub text_transform: Option<TextTransform>,
+    /// Language of the text as a BCP 47 tag (e.g. "ug" or "en-US"), reported in the text extraction.
+    #[serde(default)]
+    pub lang: Option<String>,
+    /// Shaper used to turn the text into glyphs.
+    #[serde(default)]
+    pub shaper: ShaperType,
 }
 
 impl Default for TextElement {
@@ -230,25 +833,196 @@ impl Default for TextElement {
             y: 0.0,
             font_size: 16.0,
             color: "#000000".to_string(),
+            fill_image: None,
+            fill_gradient: None,
+            fill_noise: 0.0,
             align: TextAlignType::Left,
+            baseline: BaselineType::Alphabetic,
+            position_mode: PositionMode::Anchor,
+            vertical_align: VerticalAlignType::Top,
+            overflow: OverflowType::Visible,
             font_family: None,
             font_file: None,
             max_width: None,
             line_height: 1.5,
+            line_height_px: None,
             max_lines: None,
+            ellipsis: None,
+            ellipsis_color: None,
+            id: None,
             z_index: None,
             bold: false,
             prefix: None,
             background_color: None,
+            background_gradient: None,
             padding: 0.0,
             border_radius: None,
+            corner_smoothing: 0.0,
+            border_color: None,
+            border_gradient: None,
+            border_width: 0.0,
+            box_shadow: None,
+            highlight: None,
             width: None,
             height: None,
             direction: TextDirectionType::Ltr,
+            on_missing_asset: None,
+            expand_shortcodes: false,
+            font_features: Vec::new(),
+            text_transform: None,
+            lang: None,
+            shaper: ShaperType::Skia,
+        }
+    }
+}
+
+/// Frosted-glass region configuration.
+///
+/// Blurs everything rendered beneath it (lower z-index) within a rectangle,
+/// optionally tinted, for glassmorphism-style cards.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BackdropBlurElement {
+    /// X-coordinate of the region (top-left corner).
+    pub x: f32,
+    /// Y-coordinate of the region (top-left corner).
+    pub y: f32,
+    /// Width of the region.
+    pub width: f32,
+    /// Height of the region.
+    pub height: f32,
+    /// Optional border radius for rounded corners.
+    pub radius: Option<Radius>,
+    /// Blur sigma in pixels.
+    #[serde(default = "default_blur_sigma")]
+    pub blur: f32,
+    /// Optional tint color drawn over the blurred area, usually semi-transparent (e.g. "#ffffff40").
+    pub tint: Option<String>,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+impl Default for BackdropBlurElement {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+            radius: None,
+            blur: default_blur_sigma(),
+            tint: None,
+            id: None,
+            z_index: None,
+        }
+    }
+}
+
+/// Policy for images and font files that cannot be loaded.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingAssetPolicy {
+    /// Fail the whole render (default).
+    #[default]
+    Error,
+    /// Leave the element out and keep rendering.
+    Skip,
+    /// Draw a placeholder box in place of missing images.
+    Placeholder,
+}
+
+/// Appearance of placeholders drawn for missing images.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Placeholder {
+    /// Fill color of the placeholder box.
+    #[serde(default = "default_placeholder_color")]
+    pub color: String,
+    /// Color of the initials drawn from the image's `alt` text.
+    #[serde(default = "default_placeholder_text_color")]
+    pub text_color: String,
+}
+
+impl Default for Placeholder {
+    fn default() -> Self {
+        Self {
+            color: default_placeholder_color(),
+            text_color: default_placeholder_text_color(),
+        }
+    }
+}
+
+/// Watermark drawn over the finished poster, after all elements.
+///
+/// Either `text` or `image` must be set. If both are set, the image is used.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Watermark {
+    /// Watermark text.
+    pub text: Option<String>,
+    /// Watermark image path or base64 data URL.
+    pub image: Option<String>,
+    /// Where the watermark is placed. Ignored when tiling.
+    #[serde(default = "default_watermark_position")]
+    pub position: WatermarkPosition,
+    /// Opacity from 0.0 (invisible) to 1.0 (opaque).
+    #[serde(default = "default_watermark_opacity")]
+    pub opacity: f32,
+    /// Repeat the watermark across the whole canvas.
+    #[serde(default)]
+    pub tile: bool,
+    /// Distance from the canvas edges, or between tiles when tiling.
+    #[serde(default = "default_watermark_margin")]
+    pub margin: f32,
+    /// Font size of the watermark text.
+    #[serde(default = "default_watermark_font_size")]
+    pub font_size: f32,
+    /// Color of the watermark text in hex format.
+    #[serde(default = "default_watermark_color")]
+    pub color: String,
+    /// Optional font family for the watermark text.
+    pub font_family: Option<String>,
+    /// Width of the watermark image. Defaults to the image's own width,
+    /// or keeps the aspect ratio if only `height` is set.
+    pub width: Option<f32>,
+    /// Height of the watermark image. Defaults to the image's own height,
+    /// or keeps the aspect ratio if only `width` is set.
+    pub height: Option<f32>,
+}
+
+impl Default for Watermark {
+    fn default() -> Self {
+        Self {
+            text: None,
+            image: None,
+            position: default_watermark_position(),
+            opacity: default_watermark_opacity(),
+            tile: false,
+            margin: default_watermark_margin(),
+            font_size: default_watermark_font_size(),
+            color: default_watermark_color(),
+            font_family: None,
+            width: None,
+            height: None,
         }
     }
 }
 
+/// Placement of a non-tiled watermark.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WatermarkPosition {
+    /// Top-left corner.
+    TopLeft,
+    /// Top-right corner.
+    TopRight,
+    /// Bottom-left corner.
+    BottomLeft,
+    /// Bottom-right corner (default).
+    BottomRight,
+    /// Center of the canvas.
+    Center,
+}
+
 /// Border radius configuration.
 ///
 /// Can be either a single value for all corners or individual values for each corner.
@@ -261,6 +1035,144 @@ pub enum Radius {
     Multiple([f32; 4]),
 }
 
+/// Soft shadow drawn under a box, like CSS `box-shadow`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BoxShadow {
+    /// Horizontal offset of the shadow.
+    #[serde(default)]
+    pub offset_x: f32,
+    /// Vertical offset of the shadow.
+    #[serde(default = "default_shadow_offset_y")]
+    pub offset_y: f32,
+    /// Blur radius. 0 draws a hard shadow.
+    #[serde(default = "default_shadow_blur")]
+    pub blur: f32,
+    /// Distance the shadow extends beyond the box on each side (negative shrinks it).
+    #[serde(default)]
+    pub spread: f32,
+    /// Shadow color in hex format, usually semi-transparent.
+    #[serde(default = "default_shadow_color")]
+    pub color: String,
+}
+
+impl Default for BoxShadow {
+    fn default() -> Self {
+        Self {
+            offset_x: 0.0,
+            offset_y: default_shadow_offset_y(),
+            blur: default_shadow_blur(),
+            spread: 0.0,
+            color: default_shadow_color(),
+        }
+    }
+}
+
+/// Marker-pen stripe painted behind words of a text element.
+///
+/// ```json
+/// { "color": "#ffe066", "words": ["50% off"] }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Highlight {
+    /// Stripe color in hex format.
+    pub color: String,
+    /// Words or phrases to highlight wherever they occur. Highlights the whole text if empty.
+    #[serde(default)]
+    pub words: Vec<String>,
+    /// Height of the stripe as a fraction of the font size.
+    #[serde(default = "default_highlight_height")]
+    pub height: f32,
+    /// Distance the stripe extends below the baseline, as a fraction of the font size.
+    #[serde(default = "default_highlight_offset")]
+    pub offset: f32,
+    /// Distance the stripe extends past the words on each side, in pixels.
+    #[serde(default = "default_highlight_padding")]
+    pub padding: f32,
+    /// Corner radius of the stripe.
+    #[serde(default = "default_highlight_radius")]
+    pub radius: f32,
+}
+
+impl Default for Highlight {
+    fn default() -> Self {
+        Self {
+            color: "#ffe06699".to_string(),
+            words: Vec::new(),
+            height: default_highlight_height(),
+            offset: default_highlight_offset(),
+            padding: default_highlight_padding(),
+            radius: default_highlight_radius(),
+        }
+    }
+}
+
+/// Gradient used in place of a solid fill or stroke color.
+///
+/// The gradient is laid out over the bounding box of the shape it paints.
+///
+/// ```json
+/// { "type": "linear", "colors": ["#ff512f", "#dd2476"], "angle": 90 }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Gradient {
+    /// Gradient along a line through the center of the box.
+    Linear {
+        /// Colors in hex format, at least two.
+        colors: Vec<String>,
+        /// Optional positions of the colors from 0 to 1. Spread evenly if not set.
+        #[serde(default)]
+        stops: Option<Vec<f32>>,
+        /// Direction in degrees, clockwise: 0 runs left to right, 90 top to bottom.
+        #[serde(default)]
+        angle: f32,
+    },
+    /// Gradient from the center of the box out to its corners.
+    Radial {
+        /// Colors in hex format, at least two.
+        colors: Vec<String>,
+        /// Optional positions of the colors from 0 to 1. Spread evenly if not set.
+        #[serde(default)]
+        stops: Option<Vec<f32>>,
+    },
+}
+
+impl Gradient {
+    /// Creates a shader that paints this gradient over `rect`.
+    fn shader(&self, rect: Rect) -> Result<Shader> {
+        let (colors, stops) = match self {
+            Gradient::Linear { colors, stops, .. } | Gradient::Radial { colors, stops } => (colors, stops),
+        };
+        if colors.len() < 2 {
+            return Err(PosterError::RenderError("Gradient needs at least two colors".to_string()));
+        }
+        if stops.as_ref().is_some_and(|stops| stops.len() != colors.len()) {
+            return Err(PosterError::RenderError("Gradient stops must match the number of colors".to_string()));
+        }
+        let colors = colors
+            .iter()
+            .map(|color| parse_color(color, "gradient.colors"))
+            .collect::<Result<Vec<_>>>()?;
+
+        let center = rect.center();
+        let shader = match self {
+            Gradient::Linear { angle, .. } => {
+                // Like CSS, the gradient line is long enough for the corners to get the end colors
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let half = (rect.width() * cos.abs() + rect.height() * sin.abs()) / 2.0;
+                let offset = Point::new(cos * half, sin * half);
+                let points = (center - offset, center + offset);
+                Shader::linear_gradient(points, colors.as_slice(), stops.as_deref(), TileMode::Clamp, None, None)
+            }
+            Gradient::Radial { .. } => {
+                let radius = (rect.width().hypot(rect.height()) / 2.0).max(f32::EPSILON);
+                Shader::radial_gradient(center, radius, colors.as_slice(), stops.as_deref(), TileMode::Clamp, None, None)
+            }
+        };
+        shader.ok_or_else(|| PosterError::RenderError("Failed to create gradient shader".to_string()))
+    }
+}
+
 /// Image scaling mode.
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -271,6 +1183,35 @@ pub enum ObjectFit {
     Contain,
     /// Stretch the image to fill the container (may distort).
     Stretch,
+    /// Stretch the middle of the image and its edges while keeping the corners unscaled
+    /// (9-slice scaling). The corners are set by the element's `insets`.
+    #[serde(rename = "nine_patch")]
+    NinePatch,
+}
+
+/// Nine-patch insets, in pixels of the source image.
+///
+/// Can be either a single value for all sides or individual values for each side.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+pub enum Insets {
+    /// Single inset applied to all sides.
+    Single(f32),
+    /// Individual insets: [top, right, bottom, left].
+    Multiple([f32; 4]),
+}
+
+/// Region of a source image, in pixels of the image.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct CropRect {
+    /// X-coordinate of the region in the source image.
+    pub x: f32,
+    /// Y-coordinate of the region in the source image.
+    pub y: f32,
+    /// Width of the region.
+    pub width: f32,
+    /// Height of the region.
+    pub height: f32,
 }
 
 /// Text alignment options.
@@ -285,6 +1226,109 @@ pub enum TextAlignType {
     Right,
 }
 
+/// Case change applied to text before layout.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TextTransform {
+    /// All letters in upper case.
+    Uppercase,
+    /// All letters in lower case.
+    Lowercase,
+    /// First letter of each word in upper case, the rest unchanged.
+    Capitalize,
+}
+
+impl TextTransform {
+    /// Applies the case change with Unicode case mapping (e.g. "ß" becomes "SS").
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            TextTransform::Uppercase => text.to_uppercase(),
+            TextTransform::Lowercase => text.to_lowercase(),
+            TextTransform::Capitalize => {
+                let mut result = String::with_capacity(text.len());
+                let mut word_start = true;
+                for c in text.chars() {
+                    if word_start && c.is_alphabetic() {
+                        result.extend(c.to_uppercase());
+                    } else {
+                        result.push(c);
+                    }
+                    word_start = !c.is_alphanumeric() && c != '\'' && c != '’';
+                }
+                result
+            }
+        }
+    }
+}
+
+/// How a text element's `x` and `y` position it.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PositionMode {
+    /// `x` is the alignment anchor and `y` the anchor set by `baseline` (default).
+    /// A background box is placed around the text.
+    #[default]
+    Anchor,
+    /// `x` and `y` are the top-left corner of a `width` × `height` box, and the text
+    /// wraps and aligns inside it, inset by `padding`. Without a width or height,
+    /// the box fits the text.
+    Box,
+}
+
+/// What happens when text doesn't fit in its box.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OverflowType {
+    /// Text is drawn past the box (default).
+    #[default]
+    Visible,
+    /// Text is cut off at the edges of the box.
+    Clip,
+    /// Lines that don't fit in the box's height are dropped, ending the last one with the ellipsis.
+    Ellipsis,
+    /// The font size is reduced until the text fits.
+    Shrink,
+}
+
+/// Vertical alignment of text inside its box.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum VerticalAlignType {
+    /// Text starts at the top of the box (default).
+    #[default]
+    Top,
+    /// Text is centered in the box.
+    Middle,
+    /// Text ends at the bottom of the box.
+    Bottom,
+}
+
+/// Vertical anchor of a text element's `y` coordinate.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BaselineType {
+    /// `y` is the baseline of the first line (default).
+    #[default]
+    Alphabetic,
+    /// `y` is the top of the first line, the font's ascent above its baseline.
+    Top,
+    /// `y` is the vertical middle of all lines, from the first line's ascent to the last line's descent.
+    Middle,
+}
+
+/// Text shaping engine.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ShaperType {
+    /// Skia's text layout (default).
+    #[default]
+    Skia,
+    /// rustybuzz, shaping with the font's own data. Use it for fonts loaded from
+    /// `font_file` whose ligatures or contextual forms Skia gets wrong. Needs the
+    /// `rustybuzz` feature; falls back to Skia without it.
+    Rustybuzz,
+}
+
 /// Text direction for bi-directional text support.
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -316,22 +1360,17 @@ fn is_rtl_text(text: &str) -> bool {
 }
 
 // Function to load font from file
-fn load_font_from_file(font_path: &str, font_size: f32) -> Option<Font> {
-    use std::path::Path as StdPath;
-
+fn load_font_from_file(font_path: &str, font_size: f32, ctx: &RenderContext) -> Option<Font> {
+    let _timer = ctx.timer(Phase::Layout);
     // Try multiple possible paths to handle different working directories
-    let paths_to_try = vec![
-        font_path.to_string(),           // Original path
-        format!("./{}", font_path),      // Current directory
-        format!("../{}", font_path),     // Parent directory
-    ];
+    let mut paths_to_try = vec![font_path.to_string()]; // Original path
+    if !font_path.contains("://") && !font_path.starts_with("data:") {
+        paths_to_try.push(format!("./{}", font_path)); // Current directory
+        paths_to_try.push(format!("../{}", font_path)); // Parent directory
+    }
 
     for try_path in &paths_to_try {
-        if !StdPath::new(try_path).exists() {
-            continue;
-        }
-
-        if let Ok(font_bytes) = std::fs::read(try_path) {
+        if let Ok(font_bytes) = ctx.load_asset(try_path) {
             // Use Skia API: Data::new_copy() -> FontMgr::new_from_data()
             let font_data = Data::new_copy(&font_bytes);
             let font_mgr = FontMgr::new();
@@ -345,8 +1384,16 @@ fn load_font_from_file(font_path: &str, font_size: f32) -> Option<Font> {
     None
 }
 
-// Function to get appropriate font for text with optional font family or font file
-fn get_font_for_text_with_family(_text: &str, font_size: f32, bold: bool, font_family: Option<&str>, font_file: Option<&str>) -> Font {
+// Function to get appropriate font for text with optional font family or font file,
+// with the poster's font rendering settings applied
+fn get_font_for_text_with_family(text: &str, font_size: f32, bold: bool, font_family: Option<&str>, font_file: Option<&str>, ctx: &RenderContext) -> Result<Font> {
+    let mut font = find_font(text, font_size, bold, font_family, font_file, ctx)?;
+    ctx.font_rendering.apply(&mut font);
+    Ok(font)
+}
+
+fn find_font(text: &str, font_size: f32, bold: bool, font_family: Option<&str>, font_file: Option<&str>, ctx: &RenderContext) -> Result<Font> {
+    let _timer = ctx.timer(Phase::Layout);
     let font_mgr = FontMgr::default();
 
     let weight = if bold {
@@ -359,28 +1406,36 @@ fn get_font_for_text_with_family(_text: &str, font_size: f32, bold: bool, font_f
 
     // 1. Priority: User-specified font file
     if let Some(file_path) = font_file {
-        if let Some(font) = load_font_from_file(file_path, font_size) {
-            return font;
-        }
+        return load_font_from_file(file_path, font_size, ctx)
+            .ok_or_else(|| PosterError::FontNotFound(file_path.to_string()));
     }
 
+    // Fonts sent with the poster come before installed ones of the same name
+    let find_family =
+        |family: &str| ctx.typefaces.get(family).cloned().or_else(|| font_mgr.match_family_style(family, font_style));
+
     // 2. Next: User-specified font family
     if let Some(family) = font_family {
-        if let Some(typeface) = font_mgr.match_family_style(family, font_style) {
-            return Font::new(typeface, font_size);
+        if let Some(typeface) = find_family(family) {
+            return Ok(Font::new(typeface, font_size));
         }
     }
 
-    // 3. Finally: Simple universal fallback fonts
-    let default_fonts = vec![
-        "Arial Unicode MS",  // Best Unicode coverage
-        "Arial",
-        "Helvetica",
-        "Times New Roman",
-    ];
+    let font = fallback_font(text, font_size, find_family, ctx);
+    if let Some(family) = font_family {
+        ctx.add_warning(WarningKind::FontFallback {
+            family: family.to_string(),
+            fallback: font.typeface().family_name(),
+        });
+    }
+    Ok(font)
+}
 
-    for family in default_fonts {
-        if let Some(typeface) = font_mgr.match_family_style(family, font_style) {
+// Font for text without a font family of its own, or whose family wasn't found
+fn fallback_font(text: &str, font_size: f32, find_family: impl Fn(&str) -> Option<Typeface>, ctx: &RenderContext) -> Font {
+    // 3. Finally: The fallback families for the text's script
+    for family in ctx.font_fallback.families(Script::detect(text)) {
+        if let Some(typeface) = find_family(family) {
             return Font::new(typeface, font_size);
         }
     }
@@ -426,26 +1481,122 @@ fn default_text_direction() -> TextDirectionType {
     TextDirectionType::Ltr
 }
 
-/// Main poster generator.
-///
-/// This is the primary struct for creating posters. Elements are rendered in z-index order.
-///
-/// # Example
-///
-/// ```
-/// use poster_generator::{PosterGenerator, TextElement, TextAlignType, TextDirectionType};
-///
-/// let mut generator = PosterGenerator::new(800, 600, "#f0f0f0".to_string());
-///
-/// let text = TextElement {
-///     text: "مرحبا بالعالم".to_string(), // Arabic: Hello World
-///     x: 400.0,
-///     y: 300.0,
-///     font_size: 48.0,
-///     color: "#333333".to_string(),
-///     align: TextAlignType::Center,
-///     direction: TextDirectionType::Rtl,
-///     ..Default::default()
+fn default_shadow_offset_y() -> f32 {
+    4.0
+}
+
+fn default_shadow_blur() -> f32 {
+    8.0
+}
+
+fn default_shadow_color() -> String {
+    "#00000040".to_string()
+}
+
+// Read a size given as a number, `null` or "auto", which is the same as leaving it out
+fn deserialize_auto_size<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<f32>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Size {
+        Pixels(f32),
+        Keyword(String),
+    }
+
+    match Option::<Size>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Size::Pixels(pixels)) => Ok(Some(pixels)),
+        Some(Size::Keyword(keyword)) if keyword == "auto" => Ok(None),
+        Some(Size::Keyword(keyword)) => Err(serde::de::Error::custom(format!(
+            "invalid size '{}', expected a number or \"auto\"",
+            keyword
+        ))),
+    }
+}
+
+fn default_highlight_height() -> f32 {
+    0.45
+}
+
+fn default_highlight_offset() -> f32 {
+    0.1
+}
+
+fn default_highlight_padding() -> f32 {
+    4.0
+}
+
+fn default_highlight_radius() -> f32 {
+    4.0
+}
+
+fn default_blur_sigma() -> f32 {
+    10.0
+}
+
+fn default_supersample() -> u32 {
+    1
+}
+
+fn default_antialias_clips() -> bool {
+    true
+}
+
+fn default_placeholder_color() -> String {
+    "#e0e0e0".to_string()
+}
+
+fn default_placeholder_text_color() -> String {
+    "#9e9e9e".to_string()
+}
+
+fn default_watermark_position() -> WatermarkPosition {
+    WatermarkPosition::BottomRight
+}
+
+fn default_background_layer_color() -> String {
+    "#00000000".to_string()
+}
+
+fn default_background_opacity() -> f32 {
+    1.0
+}
+
+fn default_watermark_opacity() -> f32 {
+    0.3
+}
+
+fn default_watermark_margin() -> f32 {
+    20.0
+}
+
+fn default_watermark_font_size() -> f32 {
+    24.0
+}
+
+fn default_watermark_color() -> String {
+    "#000000".to_string()
+}
+
+/// Main poster generator.
+///
+/// This is the primary struct for creating posters. Elements are rendered in z-index order.
+///
+/// # Example
+///
+/// ```
+/// use poster_generator::{PosterGenerator, TextElement, TextAlignType, TextDirectionType};
+///
+/// let mut generator = PosterGenerator::new(800, 600, "#f0f0f0".to_string());
+///
+/// let text = TextElement {
+///     text: "مرحبا بالعالم".to_string(), // Arabic: Hello World
+///     x: 400.0,
+///     y: 300.0,
+///     font_size: 48.0,
+///     color: "#333333".to_string(),
+///     align: TextAlignType::Center,
+///     direction: TextDirectionType::Rtl,
+///     ..Default::default()
 /// };
 ///
 /// generator.add_text(text);
@@ -456,67 +1607,580 @@ pub struct PosterGenerator {
     height: u32,
     background_color: String,
     elements: Vec<Box<dyn PosterElement>>,
+    // Id of each element, see `element_ids`
+    ids: Vec<String>,
+    next_id: u64,
+    on_missing_asset: MissingAssetPolicy,
+    placeholder: Placeholder,
+    watermark: Option<Watermark>,
+    assets: HashMap<String, Arc<[u8]>>,
+    resolver: Arc<dyn AssetResolver>,
+    base_path: Option<PathBuf>,
+    font_fallback: FontFallback,
+    fonts: Vec<EmbeddedFont>,
+    typefaces: HashMap<String, Typeface>,
+    safe_area: Option<SafeArea>,
+    quality: Quality,
+    font_rendering: FontRendering,
+    dither: bool,
+    theme: Theme,
+    dark_theme: Theme,
+    outputs: Vec<OutputSize>,
+    post: Option<PostProcess>,
+    animation: Option<Animation>,
+    print: Option<PrintProfile>,
+    bleed: Option<Length>,
+    crop_marks: bool,
+    images: Option<ImageCache>,
+    // Area of the output being rendered, when it isn't the canvas
+    canvas: Option<Rect>,
+    // Time of the animation frame being rendered
+    time_ms: Option<f32>,
+    profiling: bool,
+}
+
+/// A generated poster together with the placement of its elements.
+///
+/// Returned by [`PosterGenerator::generate_with_metadata`].
+#[derive(Debug, Clone)]
+pub struct RenderedPoster {
+    /// PNG-encoded image data.
+    pub png: Vec<u8>,
+    /// Metadata of every element, in the order they were rendered.
+    pub elements: Vec<ElementMetadata>,
+    /// Render timings, if profiling is enabled with [`PosterGenerator::set_profiling`].
+    pub profile: Option<RenderProfile>,
+    /// Quality problems found while rendering, in the order the elements were rendered.
+    pub warnings: Vec<RenderWarning>,
+}
+
+/// Placement of one rendered element, e.g. for building clickable hotspots.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ElementMetadata {
+    /// Position of the element in the order it was added.
+    pub index: usize,
+    /// The element's `id`, if it has one.
+    pub id: Option<String>,
+    /// Z-index the element was rendered at.
+    pub z_index: i32,
+    /// Area covered by the element in canvas pixels, if it is known.
+    pub bounds: Option<ElementBounds>,
+    /// Text shown by the element, if any.
+    pub text: Option<TextContent>,
+    /// Y-coordinate of the first line's baseline, for elements that draw text.
+    pub baseline: Option<f32>,
+    /// Whether the element's content didn't fit in its box, before its `overflow` setting was applied.
+    pub overflowed: bool,
+}
+
+/// Text extracted from an element for accessibility.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TextContent {
+    /// The text, as drawn or as the image's alternative text.
+    pub text: String,
+    /// Whether the text is drawn or describes an image.
+    pub kind: TextKind,
+    /// Direction of the text.
+    pub direction: TextDirectionType,
+    /// Language of the text, if the element sets one.
+    pub lang: Option<String>,
+}
+
+/// Where extracted text comes from.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TextKind {
+    /// Text drawn on the poster.
+    Text,
+    /// Alternative text of an image.
+    Alt,
+}
+
+impl RenderedPoster {
+    /// Elements with text, in reading order: top to bottom, then left to right
+    /// (right to left for RTL text). Elements without known bounds come last.
+    pub fn text_in_reading_order(&self) -> Vec<&ElementMetadata> {
+        let mut elements: Vec<_> = self.elements.iter().filter(|element| element.text.is_some()).collect();
+        elements.sort_by(|a, b| match (&a.bounds, &b.bounds) {
+            (Some(a_bounds), Some(b_bounds)) => {
+                let x_order = a_bounds.x.total_cmp(&b_bounds.x);
+                let rtl = a.text.as_ref().is_some_and(|text| text.direction == TextDirectionType::Rtl);
+                a_bounds.y
+                    .total_cmp(&b_bounds.y)
+                    .then(if rtl { x_order.reverse() } else { x_order })
+            }
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        elements
+    }
+
+    /// Elements whose content didn't fit in their box.
+    pub fn overflowed(&self) -> Vec<&ElementMetadata> {
+        self.elements.iter().filter(|element| element.overflowed).collect()
+    }
+
+    /// [BlurHash](crate::blurhash) of the poster, for showing a placeholder while it loads.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PNG data can't be decoded.
+    pub fn blurhash(&self) -> Result<String> {
+        blurhash::from_png(&self.png)
+    }
+
+    /// Alt text for the whole poster: the text of all elements in reading order, one per line.
+    pub fn alt_text(&self) -> String {
+        self.text_in_reading_order()
+            .into_iter()
+            .filter_map(|element| element.text.as_ref())
+            .map(|text| text.text.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Rectangle covered by an element, in canvas pixels.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct ElementBounds {
+    /// X-coordinate of the top-left corner.
+    pub x: f32,
+    /// Y-coordinate of the top-left corner.
+    pub y: f32,
+    /// Width of the rectangle.
+    pub width: f32,
+    /// Height of the rectangle.
+    pub height: f32,
+}
+
+impl From<Rect> for ElementBounds {
+    fn from(rect: Rect) -> Self {
+        Self {
+            x: rect.left,
+            y: rect.top,
+            width: rect.width(),
+            height: rect.height(),
+        }
+    }
+}
+
+// Elements as they are rendered: with the theme applied where it changes them,
+// borrowed as they are otherwise (including custom elements without a config)
+struct ThemedElements<'a> {
+    elements: &'a [Box<dyn PosterElement>],
+    themed: Vec<Option<Box<dyn PosterElement>>>,
+}
+
+impl ThemedElements<'_> {
+    fn iter(&self) -> impl Iterator<Item = &dyn PosterElement> {
+        self.elements.iter().zip(&self.themed).map(|(element, themed)| themed.as_deref().unwrap_or(&**element))
+    }
+}
+
+// Output of drawing the poster, before it is encoded
+struct Drawn {
+    metadata: Vec<ElementMetadata>,
+    profile: Option<RenderProfile>,
+    warnings: Vec<RenderWarning>,
+}
+
+/// Settings shared by all elements while a poster is being rendered.
+pub struct RenderContext {
+    width: f32,
+    height: f32,
+    canvas: Rect,
+    bleed: f32,
+    on_missing_asset: MissingAssetPolicy,
+    placeholder: Placeholder,
+    assets: HashMap<String, Arc<[u8]>>,
+    resolver: Arc<dyn AssetResolver>,
+    base_path: Option<PathBuf>,
+    font_fallback: FontFallback,
+    typefaces: HashMap<String, Typeface>,
+    safe_area: Option<SafeArea>,
+    quality: Quality,
+    font_rendering: FontRendering,
+    dither: bool,
+    images: Option<ImageCache>,
+    timings: Option<RefCell<PhaseTimings>>,
+    // Warnings of the current element, if they are being collected
+    warnings: Option<RefCell<Vec<WarningKind>>>,
+    // Whether a background layer has been drawn yet
+    background_drawn: Cell<bool>,
+}
+
+impl RenderContext {
+    /// Canvas width in pixels.
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Area of the output in canvas coordinates. It is the canvas itself, except for
+    /// [`outputs`](PosterConfig::outputs) with another aspect ratio: the canvas is then
+    /// centered in the output and this area extends past two of its edges. It includes
+    /// the poster's [`bleed`](PosterConfig::bleed).
+    ///
+    /// Elements that fill the poster, such as backgrounds, fill this area.
+    pub fn canvas_rect(&self) -> Rect {
+        self.canvas.with_outset((self.bleed, self.bleed))
+    }
+
+    /// Area of the output inside the safe area margins, or the whole output without a safe area.
+    pub fn safe_area(&self) -> Rect {
+        let canvas = self.canvas;
+        match &self.safe_area {
+            Some(area) => Rect::new(
+                canvas.left + area.left,
+                canvas.top + area.top,
+                canvas.right - area.right,
+                canvas.bottom - area.bottom,
+            ),
+            None => canvas,
+        }
+    }
+
+    /// Canvas height in pixels.
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// Effective missing asset policy, given an element's own override.
+    pub fn missing_asset_policy(&self, element_policy: Option<MissingAssetPolicy>) -> MissingAssetPolicy {
+        element_policy.unwrap_or(self.on_missing_asset)
+    }
+
+    /// Appearance of placeholders for missing images.
+    pub fn placeholder(&self) -> &Placeholder {
+        &self.placeholder
+    }
+
+    /// Speed and edge quality settings, e.g. whether clips should be anti-aliased.
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    /// Glyph settings for the poster's text; custom elements can apply them to their own fonts.
+    pub fn font_rendering(&self) -> FontRendering {
+        self.font_rendering
+    }
+
+    /// Whether gradients should be dithered.
+    pub fn dither(&self) -> bool {
+        self.dither
+    }
+
+    /// Reports a quality problem with the element being rendered. Ignored unless
+    /// warnings are being collected, and reported once per element.
+    pub fn add_warning(&self, kind: WarningKind) {
+        if let Some(warnings) = &self.warnings {
+            let mut warnings = warnings.borrow_mut();
+            if !warnings.contains(&kind) {
+                warnings.push(kind);
+            }
+        }
+    }
+
+    // Time a phase of the current element's render, if profiling is enabled
+    fn timer(&self, phase: Phase) -> PhaseTimer<'_> {
+        PhaseTimer::start(self.timings.as_ref(), phase)
+    }
+
+    /// Bytes of an asset registered with [`PosterGenerator::register_asset`].
+    pub fn asset(&self, name: &str) -> Option<&[u8]> {
+        self.assets.get(name).map(|bytes| &bytes[..])
+    }
+
+    /// Loads the bytes of an image or font source the way built-in elements do:
+    /// `asset://` names from the registered assets, anything else through the
+    /// poster's [`AssetResolver`].
+    pub fn load_asset(&self, src: &str) -> std::io::Result<Vec<u8>> {
+        match src.strip_prefix(ASSET_SCHEME) {
+            Some(name) => self.asset(name).map(<[u8]>::to_vec).ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::NotFound, format!("Unknown asset: {}", name))
+            }),
+            None => self.resolver.resolve(&self.resolve_path(src)),
+        }
+    }
+
+    /// Resolves a relative file path against the poster's base path.
+    ///
+    /// URLs, data URLs, absolute paths and paths without a base path are returned unchanged.
+    pub fn resolve_path<'a>(&self, src: &'a str) -> Cow<'a, str> {
+        match &self.base_path {
+            Some(base) if !src.contains("://") && !src.starts_with("data:") && Path::new(src).is_relative() => {
+                Cow::Owned(base.join(src).to_string_lossy().into_owned())
+            }
+            _ => Cow::Borrowed(src),
+        }
+    }
 }
 
-// Element trait
-trait PosterElement {
+/// A drawable poster element.
+///
+/// The built-in elements implement this trait, and applications can implement it
+/// for their own element types and add them with [`PosterGenerator::add_custom`].
+///
+/// # Example
+///
+/// ```
+/// use poster_generator::{PosterElement, PosterError, PosterGenerator, RenderContext};
+/// use poster_generator::skia_safe::{Canvas, Color, Paint, Rect};
+///
+/// struct Dot {
+///     x: f32,
+///     y: f32,
+///     radius: f32,
+/// }
+///
+/// impl PosterElement for Dot {
+///     fn z_index(&self) -> i32 {
+///         1
+///     }
+///
+///     fn render(&self, canvas: &Canvas, _ctx: &RenderContext) -> Result<(), PosterError> {
+///         let mut paint = Paint::default();
+///         paint.set_color(Color::RED);
+///         paint.set_anti_alias(true);
+///         canvas.draw_circle((self.x, self.y), self.radius, &paint);
+///         Ok(())
+///     }
+///
+///     fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+///         Some(Rect::new(self.x - self.radius, self.y - self.radius, self.x + self.radius, self.y + self.radius))
+///     }
+/// }
+///
+/// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+/// generator.add_custom(Box::new(Dot { x: 400.0, y: 300.0, radius: 20.0 }));
+/// ```
+pub trait PosterElement: AsAnyMut {
+    /// Z-index used for layering (higher values are rendered on top).
     fn z_index(&self) -> i32;
-    fn render(&self, canvas: &Canvas) -> Result<()>;
+
+    /// Draws the element onto the canvas.
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()>;
+
+    /// Returns the area covered by the element, if it can be determined without rendering.
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        None
+    }
+
+    /// Identifier of the element, reported by [`PosterGenerator::generate_with_metadata`].
+    fn id(&self) -> Option<&str> {
+        None
+    }
+
+    /// Text shown by the element, used to build alt text for the poster.
+    fn text_content(&self) -> Option<TextContent> {
+        None
+    }
+
+    /// Y-coordinate of the baseline of the element's first line of text, if it draws text.
+    fn baseline(&self, _ctx: &RenderContext) -> Option<f32> {
+        None
+    }
+
+    /// Whether the element's content is larger than its box.
+    fn overflows(&self, _ctx: &RenderContext) -> bool {
+        false
+    }
+
+    /// Config representation of the element, used by [`PosterGenerator::to_config`].
+    ///
+    /// Elements that can't be written to a config return `None`.
+    fn to_element(&self) -> Option<Element> {
+        None
+    }
+}
+
+/// Access to an element's concrete type, implemented for every type, so
+/// [`PosterGenerator::get_element_mut`] can hand out elements as what they are.
+pub trait AsAnyMut {
+    /// The element as [`Any`], for downcasting.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAnyMut for T {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 // Implement background element
 impl PosterElement for BackgroundElement {
     fn z_index(&self) -> i32 {
-        -1000 // Background always at the bottom
+        self.z_index.unwrap_or(-1000) // Background at the bottom by default
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Background(self.clone()))
     }
     
-    fn render(&self, canvas: &Canvas) -> Result<()> {
-        // Parse color
-        let color = parse_color(&self.color);
-        
-        // Create paint
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        if self.opacity >= 1.0 {
+            return self.draw_layer(canvas, ctx);
+        }
+        canvas.save_layer_alpha_f(None, self.opacity.max(0.0));
+        let result = self.draw_layer(canvas, ctx);
+        canvas.restore();
+        result
+    }
+
+    fn bounds(&self, ctx: &RenderContext) -> Option<Rect> {
+        Some(ctx.canvas_rect())
+    }
+}
+
+impl BackgroundElement {
+    // Draw the layer at full opacity
+    fn draw_layer(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        // Area to fill, which is larger than the canvas for outputs of another aspect ratio
+        let area = ctx.canvas_rect();
+        let (width, height) = (area.width(), area.height());
+
+        // Create paint. Layers blend over the ones below instead of replacing them,
+        // except that the bottom layer replaces the background color, as a single
+        // background always has.
         let mut paint = Paint::default();
-        paint.set_color(color);
+        let bottom_layer = !ctx.background_drawn.replace(true);
+        if bottom_layer && self.radius.is_none() {
+            paint.set_blend_mode(BlendMode::Src);
+        }
+        match (&self.pattern, &self.gradient) {
+            (Some(pattern), _) => {
+                paint.set_shader(pattern.shader(area)?);
+            }
+            (None, Some(gradient)) => {
+                paint.set_shader(gradient.shader(area)?);
+                paint.set_dither(ctx.dither());
+            }
+            (None, None) => {
+                paint.set_color(parse_color(&self.color, "color")?);
+            }
+        }
         paint.set_anti_alias(true);
         
-        // Get canvas dimensions
-        let width = canvas.base_layer_size().width;
-        let height = canvas.base_layer_size().height;
-        
         if let Some(radius) = &self.radius {
             // Draw with rounded corners
-            let path = create_rounded_rect_path(0.0, 0.0, width as f32, height as f32, radius);
+            let path = create_rounded_rect_path(area.left, area.top, width, height, radius);
             canvas.draw_path(&path, &paint);
         } else {
             // Fill the entire canvas
-            canvas.clear(color);
+            canvas.draw_rect(area, &paint);
         }
         
         // If there's an image, draw it on top
         if let Some(img_path) = &self.image {
-            if let Ok(img) = load_image(img_path) {
+            let options = DecodeOptions { size: Some((width, height)), ..Default::default() };
+            let img = match load_image_with_options(img_path, &options, ctx) {
+                Ok(img) => Some(img),
+                Err(err) => match ctx.missing_asset_policy(self.on_missing_asset) {
+                    MissingAssetPolicy::Error => return Err(err),
+                    // The background color already acts as a placeholder
+                    MissingAssetPolicy::Skip | MissingAssetPolicy::Placeholder => {
+                        warn!("Skipping missing background image: {}", err);
+                        None
+                    }
+                },
+            };
+            if let (Some(img), Some(repeat)) = (&img, self.repeat) {
+                // Tile the image at its original size with a repeating shader
+                let tile_modes = match repeat {
+                    BackgroundRepeat::Tile => (TileMode::Repeat, TileMode::Repeat),
+                    BackgroundRepeat::RepeatX => (TileMode::Repeat, TileMode::Decal),
+                    BackgroundRepeat::RepeatY => (TileMode::Decal, TileMode::Repeat),
+                };
+                let shader = img.to_shader(tile_modes, SamplingOptions::default(), None).ok_or_else(|| {
+                    PosterError::RenderError("Failed to create background image shader".to_string())
+                })?;
+                let mut tile_paint = Paint::default();
+                tile_paint.set_shader(shader);
+                tile_paint.set_anti_alias(true);
+
+                match &self.radius {
+                    Some(radius) => {
+                        let path = create_rounded_rect_path(area.left, area.top, width, height, radius);
+                        canvas.draw_path(&path, &tile_paint);
+                    }
+                    None => {
+                        canvas.draw_rect(area, &tile_paint);
+                    }
+                }
+            } else if let Some(img) = img {
                 // Scale image to fit
-                let scaled_img = scale_image(img, width as f32, height as f32, &ObjectFit::Cover)?;
+                let scaled_img = scale_image(img, width, height, &ObjectFit::Cover, None)?;
                 
                 // Create a mask if radius is specified
                 if let Some(radius) = &self.radius {
                     canvas.save();
 
                     // Create clip path
-                    let path = create_rounded_rect_path(0.0, 0.0, width as f32, height as f32, radius);
-                    canvas.clip_path(&path, None, Some(true));
+                    let path = create_rounded_rect_path(area.left, area.top, width, height, radius);
+                    canvas.clip_path(&path, None, Some(ctx.quality.antialias_clips));
                     
                     // Draw image
-                    canvas.draw_image(scaled_img, Point::new(0.0, 0.0), None);
+                    canvas.draw_image(scaled_img, Point::new(area.left, area.top), None);
                     
                     canvas.restore();
                 } else {
                     // Draw without mask
-                    canvas.draw_image(scaled_img, Point::new(0.0, 0.0), None);
+                    canvas.draw_image(scaled_img, Point::new(area.left, area.top), None);
                 }
             }
         }
-        
+
+        if self.overlay.is_some() || self.overlay_gradient.is_some() {
+            let mut overlay_paint = Paint::default();
+            match (&self.overlay_gradient, &self.overlay) {
+                (Some(gradient), _) => {
+                    overlay_paint.set_shader(gradient.shader(area)?);
+                    overlay_paint.set_dither(ctx.dither());
+                }
+                (None, Some(overlay)) => {
+                    overlay_paint.set_color(parse_color(overlay, "overlay")?);
+                }
+                (None, None) => {}
+            }
+            overlay_paint.set_anti_alias(true);
+            match &self.radius {
+                Some(radius) => {
+                    let path = create_rounded_rect_path(area.left, area.top, width, height, radius);
+                    canvas.draw_path(&path, &overlay_paint);
+                }
+                None => {
+                    canvas.draw_rect(area, &overlay_paint);
+                }
+            }
+        }
+
+        if let (Some(border_color), true) = (&self.border_color, self.border_width > 0.0) {
+            let mut border_paint = Paint::default();
+            border_paint.set_color(parse_color(border_color, "border_color")?);
+            border_paint.set_anti_alias(true);
+            border_paint.set_style(PaintStyle::Stroke);
+            border_paint.set_stroke_width(self.border_width);
+
+            // Keep the stroke inside the canvas
+            let inset = self.border_width / 2.0;
+            let (x, y) = (area.left + inset, area.top + inset);
+            let (width, height) = (width - self.border_width, height - self.border_width);
+            match &self.radius {
+                Some(radius) => {
+                    let path = create_rounded_rect_path(x, y, width, height, radius);
+                    canvas.draw_path(&path, &border_paint);
+                }
+                None => {
+                    canvas.draw_rect(Rect::from_xywh(x, y, width, height), &border_paint);
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -526,63 +2190,328 @@ impl PosterElement for ImageElement {
     fn z_index(&self) -> i32 {
         self.z_index.unwrap_or(0)
     }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Image(self.clone()))
+    }
     
-    fn render(&self, canvas: &Canvas) -> Result<()> {
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
         // Load image
-        let img = load_image(&self.src)?;
+        let decode_options = DecodeOptions {
+            respect_orientation: !self.ignore_orientation,
+            frame: match (self.frame, self.time_ms, self.progress) {
+                (Some(frame), _, _) => Some(FrameSelector::Index(frame)),
+                (None, Some(time_ms), _) => Some(FrameSelector::TimeMs(time_ms)),
+                (None, None, Some(progress)) => Some(FrameSelector::progress(progress)),
+                (None, None, None) => None,
+            },
+            size: Some((self.width, self.height)),
+        };
+        let img = match load_image_with_options(&self.src, &decode_options, ctx) {
+            Ok(img) => img,
+            Err(err) => {
+                return match ctx.missing_asset_policy(self.on_missing_asset) {
+                    MissingAssetPolicy::Error => Err(err),
+                    MissingAssetPolicy::Skip => {
+                        warn!("Skipping missing image: {}", err);
+                        Ok(())
+                    }
+                    MissingAssetPolicy::Placeholder => {
+                        warn!("Drawing placeholder for missing image: {}", err);
+                        self.render_placeholder(canvas, ctx)
+                    }
+                };
+            }
+        };
         
+        let img = match &self.crop {
+            Some(crop) => crop_image(&img, crop)?,
+            None => img,
+        };
+        self.check_upscale(&img, ctx);
+
         // Scale image according to object_fit
         let scaled_img = scale_image(
             img,
             self.width,
             self.height,
             &self.object_fit,
+            self.insets.as_ref(),
         )?;
         
-        // Apply radius if specified
-        if let Some(radius) = &self.radius {
+        let clip_path = match &self.clip_path {
+            Some(spec) => Some(create_clip_path(spec, self.x, self.y, self.width, self.height)?),
+            None => None,
+        };
+
+        let mut paint = Paint::default();
+        paint.set_color_filter(self.adjustments.color_filter());
+        if let Some(block_size) = self.pixelate {
+            paint.set_image_filter(effects::pixelate_filter(block_size, Point::new(self.x, self.y)));
+        }
+
+        // Apply radius and clip path if specified
+        if self.radius.is_some() || clip_path.is_some() {
             canvas.save();
             
             // Create clip path
-            let path = create_rounded_rect_path(
-                self.x,
-                self.y,
-                self.width,
-                self.height,
-                radius,
-            );
-            canvas.clip_path(&path, None, Some(true));
+            if let Some(radius) = &self.radius {
+                let path = create_smooth_rect_path(
+                    self.x,
+                    self.y,
+                    self.width,
+                    self.height,
+                    radius,
+                    self.corner_smoothing,
+                );
+                canvas.clip_path(&path, None, Some(ctx.quality.antialias_clips));
+            }
+            if let Some(path) = &clip_path {
+                canvas.clip_path(path, None, Some(ctx.quality.antialias_clips));
+            }
             
             // Draw image
-            canvas.draw_image(scaled_img, Point::new(self.x, self.y), None);
+            self.draw_scaled(canvas, &scaled_img, &paint);
             
             canvas.restore();
         } else {
             // Draw without mask
-            canvas.draw_image(scaled_img, Point::new(self.x, self.y), None);
+            self.draw_scaled(canvas, &scaled_img, &paint);
         }
         
         Ok(())
     }
-}
 
-// Implement text element
-impl PosterElement for TextElement {
-    fn z_index(&self) -> i32 {
-        self.z_index.unwrap_or(0)
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        Some(Rect::from_xywh(self.x, self.y, self.width, self.height))
     }
-    
-    fn render(&self, canvas: &Canvas) -> Result<()> {
-        // Parse color
-        let color = parse_color(&self.color);
-        
-        // Prepare full text content
-        let full_text = match &self.prefix {
-            Some(prefix) => format!("{}{}", prefix, self.text),
-            None => self.text.clone(),
+
+    fn text_content(&self) -> Option<TextContent> {
+        let alt = self.alt.as_ref()?;
+        let direction = if is_rtl_text(alt) { TextDirectionType::Rtl } else { TextDirectionType::Ltr };
+        Some(TextContent { text: alt.clone(), kind: TextKind::Alt, direction, lang: None })
+    }
+}
+
+impl ImageElement {
+    // Report the image if it is drawn much larger than its pixels. BlurHash placeholders
+    // are meant to be blurry, and nine-patch images to stretch.
+    fn check_upscale(&self, img: &Image, ctx: &RenderContext) {
+        if self.src.starts_with(BLURHASH_SCHEME) {
+            return;
+        }
+        let scale_x = self.width / img.width() as f32;
+        let scale_y = self.height / img.height() as f32;
+        let scale = match self.object_fit {
+            ObjectFit::Cover | ObjectFit::Stretch => scale_x.max(scale_y),
+            ObjectFit::Contain => scale_x.min(scale_y),
+            ObjectFit::NinePatch => return,
         };
-        
-        // Auto-detect text direction if not explicitly set
+        if scale > warnings::MAX_UPSCALE {
+            ctx.add_warning(WarningKind::ImageUpscaled {
+                src: assets::describe_source(&self.src).to_string(),
+                scale,
+            });
+        }
+    }
+
+    // Draw the scaled image at the element's position, mirrored within its box if flipped
+    fn draw_scaled(&self, canvas: &Canvas, image: &Image, paint: &Paint) {
+        let flipped = self.flip_horizontal || self.flip_vertical;
+        if flipped {
+            let (center_x, center_y) = (self.x + self.width / 2.0, self.y + self.height / 2.0);
+            canvas.save();
+            canvas.translate((center_x, center_y));
+            canvas.scale((
+                if self.flip_horizontal { -1.0 } else { 1.0 },
+                if self.flip_vertical { -1.0 } else { 1.0 },
+            ));
+            canvas.translate((-center_x, -center_y));
+        }
+        canvas.draw_image(image, Point::new(self.x, self.y), Some(paint));
+        if flipped {
+            canvas.restore();
+        }
+    }
+
+    // Draw a box with the initials of the alt text in place of the image
+    fn render_placeholder(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let placeholder = ctx.placeholder();
+        let mut paint = Paint::default();
+        paint.set_color(parse_color(&placeholder.color, "placeholder.color")?);
+        paint.set_anti_alias(true);
+
+        match &self.radius {
+            Some(radius) => {
+                let path = create_smooth_rect_path(self.x, self.y, self.width, self.height, radius, self.corner_smoothing);
+                canvas.draw_path(&path, &paint);
+            }
+            None => {
+                canvas.draw_rect(Rect::from_xywh(self.x, self.y, self.width, self.height), &paint);
+            }
+        }
+
+        let initials: String = self.alt.as_deref().unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|word| word.chars().next())
+            .take(2)
+            .flat_map(char::to_uppercase)
+            .collect();
+        if initials.is_empty() {
+            return Ok(());
+        }
+
+        let font_size = self.width.min(self.height) * 0.4;
+        let font = get_font_for_text_with_family(&initials, font_size, true, None, None, ctx)?;
+        let mut text_paint = Paint::default();
+        text_paint.set_color(parse_color(&placeholder.text_color, "placeholder.text_color")?);
+        text_paint.set_anti_alias(true);
+
+        // Center the initials both horizontally and vertically
+        let (_, metrics) = font.metrics();
+        let center_x = self.x + self.width / 2.0;
+        let baseline = self.y + self.height / 2.0 - (metrics.ascent + metrics.descent) / 2.0;
+        let direction = if is_rtl_text(&initials) { TextDirectionType::Rtl } else { TextDirectionType::Ltr };
+        draw_text_line_improved(canvas, &initials, center_x, baseline, &font, &text_paint, &direction, &TextAlignType::Center);
+
+        Ok(())
+    }
+}
+
+// Implement backdrop blur element
+impl PosterElement for BackdropBlurElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::BackdropBlur(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let rect = Rect::from_xywh(self.x, self.y, self.width, self.height);
+        let path = match &self.radius {
+            Some(radius) => create_rounded_rect_path(self.x, self.y, self.width, self.height, radius),
+            None => SkPath::rect(rect, None),
+        };
+
+        canvas.save();
+        canvas.clip_path(&path, None, Some(ctx.quality.antialias_clips));
+
+        if self.blur > 0.0 {
+            // A layer with a backdrop filter starts from a blurred copy of what is beneath it
+            let filter = image_filters::blur((self.blur, self.blur), TileMode::Clamp, None, rect).ok_or_else(|| {
+                PosterError::RenderError("Failed to create blur filter".to_string())
+            })?;
+            canvas.save_layer(&SaveLayerRec::default().bounds(&rect).backdrop(&filter));
+            canvas.restore();
+        }
+
+        if let Some(tint) = &self.tint {
+            let mut paint = Paint::default();
+            paint.set_color(parse_color(tint, "tint")?);
+            paint.set_anti_alias(true);
+            canvas.draw_path(&path, &paint);
+        }
+
+        canvas.restore();
+        Ok(())
+    }
+
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        Some(Rect::from_xywh(self.x, self.y, self.width, self.height))
+    }
+}
+
+// Implement text element
+impl PosterElement for TextElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Text(self.clone()))
+    }
+    
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        // Parse color
+        let color = parse_color(&self.color, "color")?;
+        
+        let (full_text, text_direction) = self.display_text();
+        let font = self.font(&full_text, ctx)?;
+        let (element, font) = {
+            let _timer = ctx.timer(Phase::Layout);
+            self.fitted(&full_text, &text_direction, font)
+        };
+        
+        // Use TextLayout for proper RTL and complex text rendering
+        element.render_with_text_layout(canvas, &full_text, &text_direction, &font, color, ctx)?;
+        
+        Ok(())
+    }
+
+    fn bounds(&self, ctx: &RenderContext) -> Option<Rect> {
+        let (full_text, text_direction) = self.display_text();
+        let font = self.font(&full_text, ctx).ok()?;
+        let (element, font) = self.fitted(&full_text, &text_direction, font);
+        let (lines, _) = element.layout_lines(&full_text, &text_direction, &font);
+        Some(element.box_rect(&lines, &text_direction, &font))
+    }
+
+    fn text_content(&self) -> Option<TextContent> {
+        let (text, direction) = self.display_text();
+        Some(TextContent { text, kind: TextKind::Text, direction, lang: self.lang.clone() })
+    }
+
+    fn baseline(&self, ctx: &RenderContext) -> Option<f32> {
+        let (full_text, text_direction) = self.display_text();
+        let font = self.font(&full_text, ctx).ok()?;
+        let (element, font) = self.fitted(&full_text, &text_direction, font);
+        let (lines, _) = element.layout_lines(&full_text, &text_direction, &font);
+        Some(element.first_baseline(lines.len(), &font))
+    }
+
+    fn overflows(&self, ctx: &RenderContext) -> bool {
+        let (full_text, text_direction) = self.display_text();
+        let Ok(font) = self.font(&full_text, ctx) else {
+            return false;
+        };
+        let (lines, _) = self.layout_lines(&full_text, &text_direction, &font);
+        self.overflows_box(&lines, &font)
+    }
+}
+
+impl TextElement {
+    // Text as drawn (prefix added, shortcodes expanded) and its direction
+    fn display_text(&self) -> (String, TextDirectionType) {
+        // Prepare full text content
+        let text = if self.expand_shortcodes {
+            emoji::expand_shortcodes(&self.text)
+        } else {
+            self.text.clone()
+        };
+        let full_text = match &self.prefix {
+            Some(prefix) => format!("{}{}", prefix, text),
+            None => text,
+        };
+        let full_text = match self.text_transform {
+            Some(transform) => transform.apply(&full_text),
+            None => full_text,
+        };
+        
+        // Auto-detect text direction if not explicitly set
         let text_direction = match self.direction {
             TextDirectionType::Rtl => TextDirectionType::Rtl,
             TextDirectionType::Ltr => {
@@ -593,23 +2522,270 @@ impl PosterElement for TextElement {
                 }
             }
         };
-        
+
+        (full_text, text_direction)
+    }
+
+    fn font(&self, full_text: &str, ctx: &RenderContext) -> Result<Font> {
         // Get appropriate font for the text with optional font family and font file
-        let font = get_font_for_text_with_family(&full_text, self.font_size, self.bold, self.font_family.as_deref(), self.font_file.as_deref());
-        
-        // Use TextLayout for proper RTL and complex text rendering
-        self.render_with_text_layout(canvas, &full_text, &text_direction, &font, color)?;
-        
-        Ok(())
+        match get_font_for_text_with_family(full_text, self.font_size, self.bold, self.font_family.as_deref(), self.font_file.as_deref(), ctx) {
+            Ok(font) => Ok(font),
+            Err(err) => match ctx.missing_asset_policy(self.on_missing_asset) {
+                MissingAssetPolicy::Error => Err(err),
+                MissingAssetPolicy::Skip | MissingAssetPolicy::Placeholder => {
+                    warn!("Falling back to default font: {}", err);
+                    get_font_for_text_with_family(full_text, self.font_size, self.bold, self.font_family.as_deref(), None, ctx)
+                }
+            },
+        }
     }
-}
 
-impl TextElement {
-    fn render_with_text_layout(&self, canvas: &Canvas, full_text: &str, text_direction: &TextDirectionType, font: &Font, color: Color) -> Result<()> {
+    fn render_with_text_layout(&self, canvas: &Canvas, full_text: &str, text_direction: &TextDirectionType, font: &Font, color: Color, ctx: &RenderContext) -> Result<()> {
         let mut paint = Paint::default();
         paint.set_color(color);
         paint.set_anti_alias(true);
         
+        let (lines, truncated) = {
+            let _timer = ctx.timer(Phase::Layout);
+            self.layout_lines(full_text, text_direction, font)
+        };
+
+        // Draw background and border if specified
+        let has_fill = self.background_color.is_some() || self.background_gradient.is_some();
+        let has_border = (self.border_color.is_some() || self.border_gradient.is_some()) && self.border_width > 0.0;
+        if has_fill || has_border {
+            let bg_rect = self.box_rect(&lines, text_direction, font);
+            let (bg_x, bg_y, bg_width, bg_height) = (bg_rect.left, bg_rect.top, bg_rect.width(), bg_rect.height());
+
+            if let Some(shadow) = &self.box_shadow {
+                draw_box_shadow(
+                    canvas,
+                    shadow,
+                    (bg_x, bg_y, bg_width, bg_height),
+                    self.border_radius.as_ref(),
+                    self.corner_smoothing,
+                )?;
+            }
+
+            // Draw background with optional radius
+            if has_fill {
+                let mut bg_paint = Paint::default();
+                match (&self.background_gradient, &self.background_color) {
+                    (Some(gradient), _) => {
+                        bg_paint.set_shader(gradient.shader(bg_rect)?);
+                        bg_paint.set_dither(ctx.dither());
+                    }
+                    (None, Some(bg_color_str)) => {
+                        bg_paint.set_color(parse_color(bg_color_str, "background_color")?);
+                    }
+                    (None, None) => {}
+                }
+
+                if let Some(radius) = &self.border_radius {
+                    let path = create_smooth_rect_path(bg_x, bg_y, bg_width, bg_height, radius, self.corner_smoothing);
+                    canvas.draw_path(&path, &bg_paint);
+                } else {
+                    let rect = Rect::new(bg_x, bg_y, bg_x + bg_width, bg_y + bg_height);
+                    canvas.draw_rect(rect, &bg_paint);
+                }
+            }
+
+            if has_border {
+                let mut border_paint = Paint::default();
+                match (&self.border_gradient, &self.border_color) {
+                    (Some(gradient), _) => {
+                        border_paint.set_shader(gradient.shader(bg_rect)?);
+                        border_paint.set_dither(ctx.dither());
+                    }
+                    (None, Some(border_color)) => {
+                        border_paint.set_color(parse_color(border_color, "border_color")?);
+                    }
+                    (None, None) => {}
+                }
+                border_paint.set_anti_alias(true);
+                border_paint.set_style(PaintStyle::Stroke);
+                border_paint.set_stroke_width(self.border_width);
+
+                // Keep the stroke inside the box
+                let inset = self.border_width / 2.0;
+                let (x, y) = (bg_x + inset, bg_y + inset);
+                let (width, height) = (bg_width - self.border_width, bg_height - self.border_width);
+                if let Some(radius) = &self.border_radius {
+                    let path = create_smooth_rect_path(x, y, width, height, radius, self.corner_smoothing);
+                    canvas.draw_path(&path, &border_paint);
+                } else {
+                    canvas.draw_rect(Rect::from_xywh(x, y, width, height), &border_paint);
+                }
+            }
+        }
+
+        // Render all lines
+        if self.overflow == OverflowType::Clip {
+            canvas.save();
+            canvas.clip_rect(self.box_rect(&lines, text_direction, font), None, ctx.quality.antialias_clips);
+        }
+        let placements = self.place_lines(&lines, font);
+        if let Some(shader) = self.fill_shader(self.box_rect(&lines, text_direction, font), color, ctx)? {
+            paint.set_shader(shader);
+            paint.set_dither(ctx.dither());
+        }
+        if let Some(highlight) = &self.highlight {
+            for (line, place) in lines.iter().zip(&placements) {
+                draw_highlight(canvas, highlight, line, place.x, place.y, font, text_direction, &place.align)?;
+            }
+        }
+        let draw_line = |line: &str, place: &LinePlacement, paint: &Paint| {
+            let shaped = matches!(self.shaper, ShaperType::Rustybuzz)
+                && shaping::draw_shaped_line(canvas, line, place.x, place.y, font, paint, text_direction, &place.align, &self.font_features);
+            if !shaped {
+                draw_text_line_with_features(canvas, line, place.x, place.y, font, paint, text_direction, &place.align, &self.font_features);
+            }
+        };
+        let ellipsis_paint = match (&self.ellipsis_color, truncated) {
+            (Some(ellipsis_color), true) => {
+                let mut ellipsis_paint = paint.clone();
+                ellipsis_paint.set_shader(None);
+                ellipsis_paint.set_color(parse_color(ellipsis_color, "ellipsis_color")?);
+                Some(ellipsis_paint)
+            }
+            _ => None,
+        };
+        for (i, (line, place)) in lines.iter().zip(&placements).enumerate() {
+            match &ellipsis_paint {
+                Some(ellipsis_paint) if i == lines.len() - 1 => {
+                    // Draw the whole line twice, so it is shaped as one, clipped to either side of the ellipsis
+                    let ellipsis_rect = self.ellipsis_rect(line, place, font, text_direction);
+                    canvas.save();
+                    canvas.clip_rect(ellipsis_rect, ClipOp::Difference, ctx.quality.antialias_clips);
+                    draw_line(line, place, &paint);
+                    canvas.restore();
+                    canvas.save();
+                    canvas.clip_rect(ellipsis_rect, None, ctx.quality.antialias_clips);
+                    draw_line(line, place, ellipsis_paint);
+                    canvas.restore();
+                }
+                _ => draw_line(line, place, &paint),
+            }
+        }
+        if self.overflow == OverflowType::Clip {
+            canvas.restore();
+        }
+        
+        Ok(())
+    }
+
+    // Shader filling the glyphs with `fill_image` or `fill_gradient` and `fill_noise` grain,
+    // or None to fill them with `color`
+    fn fill_shader(&self, rect: Rect, color: Color, ctx: &RenderContext) -> Result<Option<Shader>> {
+        if rect.is_empty() {
+            return Ok(None);
+        }
+        let fill = match (&self.fill_image, &self.fill_gradient) {
+            (Some(src), _) => match load_image(src, ctx) {
+                Ok(img) => {
+                    let scaled = scale_image(img, rect.width(), rect.height(), &ObjectFit::Cover, None)?;
+                    let matrix = Matrix::translate((rect.left, rect.top));
+                    scaled.to_shader(None, SamplingOptions::default(), &matrix)
+                }
+                Err(err) => match ctx.missing_asset_policy(self.on_missing_asset) {
+                    MissingAssetPolicy::Error => return Err(err),
+                    MissingAssetPolicy::Skip | MissingAssetPolicy::Placeholder => {
+                        warn!("Filling text with its color instead of the missing image: {}", err);
+                        None
+                    }
+                },
+            },
+            (None, Some(gradient)) => Some(gradient.shader(rect)?),
+            (None, None) => None,
+        };
+        if self.fill_noise <= 0.0 {
+            return Ok(fill);
+        }
+        let Some(grain) = effects::grain_shader(self.fill_noise, 1.0, true, 0.0) else {
+            return Ok(fill);
+        };
+        let fill = fill.unwrap_or_else(|| Shader::color(color));
+        Ok(Some(Shader::blend(BlendMode::Overlay, fill, grain)))
+    }
+
+    // The element as drawn, with its font: with `overflow: shrink` or `ellipsis`,
+    // changed so its text fits in its box
+    fn fitted(&self, full_text: &str, text_direction: &TextDirectionType, font: Font) -> (Cow<'_, TextElement>, Font) {
+        match self.overflow {
+            OverflowType::Shrink => {
+                let mut element = self.clone();
+                let mut font = font;
+                loop {
+                    let (lines, _) = element.layout_lines(full_text, text_direction, &font);
+                    if element.font_size <= MIN_SHRINK_FONT_SIZE || !element.overflows_box(&lines, &font) {
+                        break;
+                    }
+                    let size = (element.font_size * 0.95).max(MIN_SHRINK_FONT_SIZE);
+                    element.line_height_px = element.line_height_px.map(|height| height * size / element.font_size);
+                    element.font_size = size;
+                    font.set_size(size);
+                }
+                (Cow::Owned(element), font)
+            }
+            OverflowType::Ellipsis => {
+                let Some(height) = self.height else {
+                    return (Cow::Borrowed(self), font);
+                };
+                // The first line takes its ascent and descent, each further line one line advance
+                let (_, metrics) = font.metrics();
+                let spare = height - self.box_padding() * 2.0 - (metrics.descent - metrics.ascent);
+                let fitting = (spare / self.line_advance()).floor().max(0.0) as u32 + 1;
+                let mut element = self.clone();
+                element.max_lines = Some(self.max_lines.map_or(fitting, |max| max.min(fitting)));
+                (Cow::Owned(element), font)
+            }
+            OverflowType::Visible | OverflowType::Clip => (Cow::Borrowed(self), font),
+        }
+    }
+
+    // Whether the lines are wider or taller than the inside of the box
+    fn overflows_box(&self, lines: &[String], font: &Font) -> bool {
+        // Allow for rounding in the measurements
+        const TOLERANCE: f32 = 0.5;
+        let padding = self.box_padding();
+        let content_width = self.wrap_width().or(self.width.map(|width| width - padding * 2.0));
+        let too_wide = content_width
+            .is_some_and(|width| lines.iter().any(|line| measure_text_with_font(line, font).0 > width + TOLERANCE));
+        let too_tall = self
+            .height
+            .is_some_and(|height| self.text_height(lines.len(), font) > height - padding * 2.0 + TOLERANCE);
+        too_wide || too_tall
+    }
+
+    // Padding between the box and the text: always in box mode, otherwise only when the box is drawn
+    fn box_padding(&self) -> f32 {
+        let has_box = self.background_color.is_some()
+            || self.background_gradient.is_some()
+            || ((self.border_color.is_some() || self.border_gradient.is_some()) && self.border_width > 0.0);
+        if has_box || self.position_mode == PositionMode::Box { self.padding } else { 0.0 }
+    }
+
+    // Text ending a truncated last line
+    fn ellipsis(&self) -> &str {
+        self.ellipsis.as_deref().unwrap_or("...")
+    }
+
+    // Area of the ellipsis at the end of the last line, reaching well above and below it
+    fn ellipsis_rect(&self, line: &str, place: &LinePlacement, font: &Font, text_direction: &TextDirectionType) -> Rect {
+        let (left, width) = line_extent(line, place.x, font, text_direction, &place.align);
+        let y = place.y;
+        let ellipsis_width = font.measure_str(self.ellipsis(), None).0;
+        // RTL lines end on the left
+        let ellipsis_left = if matches!(text_direction, TextDirectionType::Rtl) && is_rtl_text(line) {
+            left
+        } else {
+            left + width - ellipsis_width
+        };
+        Rect::new(ellipsis_left, y - font.size() * 2.0, ellipsis_left + ellipsis_width, y + font.size() * 2.0)
+    }
+    
+    // Break the text into the lines that are drawn, and whether `max_lines` cut it short
+    fn layout_lines(&self, full_text: &str, text_direction: &TextDirectionType, font: &Font) -> (Vec<String>, bool) {
         // For RTL text, we need special handling
         let processed_text = if matches!(text_direction, TextDirectionType::Rtl) {
             // For RTL languages like Uyghur, we need to process the text
@@ -620,96 +2796,159 @@ impl TextElement {
             full_text.to_string()
         };
         
-        // Determine if we have multi-line text
-        let has_manual_newlines = processed_text.contains('\n');
-        let lines: Vec<String> = if has_manual_newlines && self.max_width.is_some() {
-            // Both manual newlines and max_width: split by \n first, then wrap each line
-            let max_width = self.max_width.unwrap();
-            let mut all_lines = Vec::new();
-            for manual_line in processed_text.split('\n') {
-                let wrapped_lines = break_text_rtl(manual_line, max_width, font, None);
-                all_lines.extend(wrapped_lines);
-            }
-            // Apply max_lines limit if specified
-            if let Some(max) = self.max_lines {
-                all_lines.truncate(max as usize);
-            }
-            all_lines
-        } else if has_manual_newlines {
-            // Only manual newlines: split by \n
-            let mut lines: Vec<String> = processed_text.split('\n').map(|s| s.to_string()).collect();
-            // Apply max_lines limit if specified
-            if let Some(max) = self.max_lines {
+        // Split by manual newlines first, then wrap each line if max_width is set
+        let mut lines: Vec<String> = match self.wrap_width() {
+            Some(max_width) => processed_text
+                .split('\n')
+                .flat_map(|manual_line| break_text_rtl(manual_line, max_width, font))
+                .collect(),
+            None => processed_text.split('\n').map(|s| s.to_string()).collect(),
+        };
+
+        // Apply max_lines limit if specified, ending the last line with the ellipsis
+        let truncated = match self.max_lines {
+            Some(max) if lines.len() > max as usize => {
                 lines.truncate(max as usize);
+                if let Some(last_line) = lines.last_mut() {
+                    let max_width = self.wrap_width().unwrap_or(f32::INFINITY);
+                    *last_line = truncate_with_suffix(last_line, max_width, font, self.ellipsis());
+                }
+                true
             }
-            lines
-        } else if let Some(max_width) = self.max_width {
-            // Only auto word wrap based on max_width
-            break_text_rtl(&processed_text, max_width, font, self.max_lines)
-        } else {
-            // Single line
-            vec![processed_text.clone()]
+            _ => false,
         };
+        (lines, truncated)
+    }
 
-        // Draw background if specified
-        if let Some(bg_color_str) = &self.background_color {
-            let bg_color = parse_color(bg_color_str);
-            let mut bg_paint = Paint::default();
-            bg_paint.set_color(bg_color);
-
-            // Get font metrics for accurate vertical positioning
-            let (_line_spacing, metrics) = font.metrics();
-            let ascent = -metrics.ascent; // ascent is negative in Skia
-            let descent = metrics.descent; // descent is positive
-            let single_line_height = ascent + descent;
-
-            // Calculate total text dimensions for multi-line text
-            let max_line_width = lines.iter()
-                .map(|line| measure_text_with_font(line, font).0)
-                .max_by(|a, b| a.partial_cmp(b).unwrap())
-                .unwrap_or(0.0);
-
-            let total_text_height = if lines.len() > 1 {
-                // First line uses single_line_height, subsequent lines use line_height spacing
-                single_line_height + (lines.len() - 1) as f32 * self.font_size * self.line_height
-            } else {
-                single_line_height
-            };
-
-            let bg_width = self.width.unwrap_or_else(|| max_line_width + self.padding * 2.0);
-            let bg_height = self.height.unwrap_or_else(|| total_text_height + self.padding * 2.0);
-
-            // Adjust x position based on text alignment
-            let bg_x = match (self.align, text_direction) {
-                (TextAlignType::Left, TextDirectionType::Ltr) => self.x - self.padding,
-                (TextAlignType::Right, TextDirectionType::Ltr) => self.x - bg_width + self.padding,
-                (TextAlignType::Center, _) => self.x - bg_width / 2.0,
-                // For RTL text, reverse alignment
-                (TextAlignType::Left, TextDirectionType::Rtl) => self.x - bg_width + self.padding,
-                (TextAlignType::Right, TextDirectionType::Rtl) => self.x - self.padding,
-            };
+    // Distance between the baselines of consecutive lines
+    fn line_advance(&self) -> f32 {
+        self.line_height_px.unwrap_or(self.font_size * self.line_height)
+    }
 
-            // Position background box so text baseline is vertically centered
-            // self.y is the text baseline, ascent goes up, descent goes down
-            let bg_y = self.y - ascent - self.padding;
+    // Width lines are wrapped to: the inside of the box in box mode, otherwise `max_width`
+    fn wrap_width(&self) -> Option<f32> {
+        match self.position_mode {
+            PositionMode::Box => self.width.map(|width| (width - self.padding * 2.0).max(0.0)).or(self.max_width),
+            PositionMode::Anchor => self.max_width,
+        }
+    }
 
-            // Draw background with optional radius
-            if let Some(radius) = &self.border_radius {
-                let path = create_rounded_rect_path(bg_x, bg_y, bg_width, bg_height, radius);
-                canvas.draw_path(&path, &bg_paint);
-            } else {
-                let rect = Rect::new(bg_x, bg_y, bg_x + bg_width, bg_y + bg_height);
-                canvas.draw_rect(rect, &bg_paint);
+    // Where each line is drawn. In box mode, lines are aligned inside the box and
+    // drawn from their left edge.
+    fn place_lines(&self, lines: &[String], font: &Font) -> Vec<LinePlacement> {
+        let first_baseline = self.first_baseline(lines.len(), font);
+        let line_y = |i: usize| first_baseline + i as f32 * self.line_advance();
+        match self.position_mode {
+            PositionMode::Anchor => (0..lines.len())
+                .map(|i| LinePlacement { x: self.x, align: self.align, y: line_y(i) })
+                .collect(),
+            PositionMode::Box => {
+                let widths: Vec<f32> = lines.iter().map(|line| measure_text_with_font(line, font).0).collect();
+                let inner_width = match self.width {
+                    Some(width) => width - self.padding * 2.0,
+                    None => widths.iter().copied().fold(0.0, f32::max),
+                };
+                let factor = match self.align {
+                    TextAlignType::Left => 0.0,
+                    TextAlignType::Center => 0.5,
+                    TextAlignType::Right => 1.0,
+                };
+                widths
+                    .iter()
+                    .enumerate()
+                    .map(|(i, width)| LinePlacement {
+                        x: self.x + self.padding + (inner_width - width) * factor,
+                        align: TextAlignType::Left,
+                        y: line_y(i),
+                    })
+                    .collect()
             }
         }
+    }
 
-        // Render all lines
-        for (i, line) in lines.iter().enumerate() {
-            let y_pos = self.y + (i as f32 * self.font_size * self.line_height);
-            draw_text_line_improved(canvas, line, self.x, y_pos, font, &paint, text_direction, &self.align);
+    // Height from the first line's ascent to the last line's descent
+    fn text_height(&self, line_count: usize, font: &Font) -> f32 {
+        let (_, metrics) = font.metrics();
+        metrics.descent - metrics.ascent + line_count.saturating_sub(1) as f32 * self.line_advance()
+    }
+
+    // Baseline of the first line, from `y` and the anchor it is aligned to
+    fn first_baseline(&self, line_count: usize, font: &Font) -> f32 {
+        let (_, metrics) = font.metrics();
+        if self.position_mode == PositionMode::Box {
+            let text_height = self.text_height(line_count, font);
+            let inner_height = self.height.map_or(text_height, |height| height - self.padding * 2.0);
+            let factor = match self.vertical_align {
+                VerticalAlignType::Top => 0.0,
+                VerticalAlignType::Middle => 0.5,
+                VerticalAlignType::Bottom => 1.0,
+            };
+            return self.y + self.padding + (inner_height - text_height) * factor - metrics.ascent;
         }
-        
-        Ok(())
+        match self.baseline {
+            BaselineType::Alphabetic => self.y,
+            BaselineType::Top => self.y - metrics.ascent,
+            BaselineType::Middle => self.y - self.text_height(line_count, font) / 2.0 - metrics.ascent,
+        }
+    }
+
+    // Area of the text box, or of the bare text if no background or border is drawn
+    fn box_rect(&self, lines: &[String], text_direction: &TextDirectionType, font: &Font) -> Rect {
+        let has_box = self.background_color.is_some()
+            || self.background_gradient.is_some()
+            || ((self.border_color.is_some() || self.border_gradient.is_some()) && self.border_width > 0.0);
+        let padding = self.box_padding();
+
+        // Get font metrics for accurate vertical positioning
+        let (_line_spacing, metrics) = font.metrics();
+        let ascent = -metrics.ascent; // ascent is negative in Skia
+        let descent = metrics.descent; // descent is positive
+        let single_line_height = ascent + descent;
+
+        // Calculate total text dimensions for multi-line text
+        let max_line_width = lines.iter()
+            .map(|line| measure_text_with_font(line, font).0)
+            .max_by(|a, b| a.partial_cmp(b).unwrap())
+            .unwrap_or(0.0);
+
+        let total_text_height = if lines.len() > 1 {
+            // First line uses single_line_height, subsequent lines use line_height spacing
+            single_line_height + (lines.len() - 1) as f32 * self.line_advance()
+        } else {
+            single_line_height
+        };
+
+        // In box mode the box is where the element says, with the text placed inside it
+        if self.position_mode == PositionMode::Box {
+            let width = self.width.unwrap_or(max_line_width + self.padding * 2.0);
+            let height = self.height.unwrap_or(total_text_height + self.padding * 2.0);
+            return Rect::from_xywh(self.x, self.y, width, height);
+        }
+
+        let (bg_width, bg_height) = if has_box {
+            (
+                self.width.unwrap_or_else(|| max_line_width + padding * 2.0),
+                self.height.unwrap_or_else(|| total_text_height + padding * 2.0),
+            )
+        } else {
+            (max_line_width, total_text_height)
+        };
+
+        // Adjust x position based on text alignment
+        let bg_x = match (self.align, text_direction) {
+            (TextAlignType::Left, TextDirectionType::Ltr) => self.x - padding,
+            (TextAlignType::Right, TextDirectionType::Ltr) => self.x - bg_width + padding,
+            (TextAlignType::Center, _) => self.x - bg_width / 2.0,
+            // For RTL text, reverse alignment
+            (TextAlignType::Left, TextDirectionType::Rtl) => self.x - bg_width + padding,
+            (TextAlignType::Right, TextDirectionType::Rtl) => self.x - padding,
+        };
+
+        // Position background box so text baseline is vertically centered
+        // Ascent goes up from the first baseline, descent goes down
+        let bg_y = self.first_baseline(lines.len(), font) - ascent - padding;
+
+        Rect::from_xywh(bg_x, bg_y, bg_width, bg_height)
     }
     
     // Process RTL text for better display
@@ -722,6 +2961,114 @@ impl TextElement {
     }
 }
 
+// Position a line of a text element is drawn at
+struct LinePlacement {
+    // Anchor x, interpreted according to `align`
+    x: f32,
+    align: TextAlignType,
+    // Baseline
+    y: f32,
+}
+
+// What a watermark draws, measured once and stamped one or more times
+enum WatermarkMark {
+    Image(Image, f32, f32),
+    Text(String, Font, Paint),
+}
+
+impl Watermark {
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let mark = if let Some(src) = &self.image {
+            let img = match load_image(src, ctx) {
+                Ok(img) => img,
+                Err(err) => match ctx.missing_asset_policy(None) {
+                    MissingAssetPolicy::Error => return Err(err),
+                    MissingAssetPolicy::Skip | MissingAssetPolicy::Placeholder => {
+                        warn!("Skipping missing watermark image: {}", err);
+                        return Ok(());
+                    }
+                },
+            };
+            let (src_width, src_height) = (img.width() as f32, img.height() as f32);
+            let (width, height) = match (self.width, self.height) {
+                (Some(width), Some(height)) => (width, height),
+                (Some(width), None) => (width, src_height * width / src_width),
+                (None, Some(height)) => (src_width * height / src_height, height),
+                (None, None) => (src_width, src_height),
+            };
+            WatermarkMark::Image(img, width, height)
+        } else if let Some(text) = &self.text {
+            let font = get_font_for_text_with_family(text, self.font_size, false, self.font_family.as_deref(), None, ctx)?;
+            let mut paint = Paint::default();
+            paint.set_color(parse_color(&self.color, "watermark.color")?);
+            paint.set_anti_alias(true);
+            WatermarkMark::Text(text.clone(), font, paint)
+        } else {
+            return Ok(());
+        };
+
+        let (mark_width, mark_height) = match &mark {
+            WatermarkMark::Image(_, width, height) => (*width, *height),
+            WatermarkMark::Text(text, font, _) => {
+                let (_, metrics) = font.metrics();
+                (measure_text_with_font(text, font).0, metrics.descent - metrics.ascent)
+            }
+        };
+        if mark_width <= 0.0 || mark_height <= 0.0 {
+            return Ok(());
+        }
+
+        // Stamps the mark with its top-left corner at (x, y)
+        let stamp = |x: f32, y: f32| match &mark {
+            WatermarkMark::Image(img, width, height) => {
+                let mut paint = Paint::default();
+                paint.set_anti_alias(true);
+                canvas.draw_image_rect(img, None, Rect::from_xywh(x, y, *width, *height), &paint);
+            }
+            WatermarkMark::Text(text, font, paint) => {
+                let (_, metrics) = font.metrics();
+                let direction = if is_rtl_text(text) { TextDirectionType::Rtl } else { TextDirectionType::Ltr };
+                draw_text_line_improved(canvas, text, x, y - metrics.ascent, font, paint, &direction, &TextAlignType::Left);
+            }
+        };
+
+        canvas.save_layer_alpha_f(None, self.opacity.clamp(0.0, 1.0));
+
+        if self.tile {
+            let step_x = mark_width + self.margin.max(0.0);
+            let step_y = mark_height + self.margin.max(0.0);
+            let area = ctx.canvas_rect();
+            let mut y = area.top;
+            while y < area.bottom {
+                let mut x = area.left;
+                while x < area.right {
+                    stamp(x, y);
+                    x += step_x;
+                }
+                y += step_y;
+            }
+        } else {
+            // Placed inside the safe area
+            let area = ctx.safe_area();
+            let left = area.left + self.margin;
+            let right = area.right - mark_width - self.margin;
+            let top = area.top + self.margin;
+            let bottom = area.bottom - mark_height - self.margin;
+            let (x, y) = match self.position {
+                WatermarkPosition::TopLeft => (left, top),
+                WatermarkPosition::TopRight => (right, top),
+                WatermarkPosition::BottomLeft => (left, bottom),
+                WatermarkPosition::BottomRight => (right, bottom),
+                WatermarkPosition::Center => (area.center_x() - mark_width / 2.0, area.center_y() - mark_height / 2.0),
+            };
+            stamp(x, y);
+        }
+
+        canvas.restore();
+        Ok(())
+    }
+}
+
 // Implementation for PosterGenerator
 impl PosterGenerator {
     /// Creates a new poster generator.
@@ -745,12 +3092,348 @@ impl PosterGenerator {
             height,
             background_color,
             elements: Vec::new(),
+            ids: Vec::new(),
+            next_id: 0,
+            on_missing_asset: MissingAssetPolicy::default(),
+            placeholder: Placeholder::default(),
+            watermark: None,
+            assets: HashMap::new(),
+            resolver: Arc::new(DefaultResolver::default()),
+            base_path: None,
+            font_fallback: FontFallback::default(),
+            fonts: Vec::new(),
+            typefaces: HashMap::new(),
+            safe_area: None,
+            quality: Quality::default(),
+            font_rendering: FontRendering::default(),
+            dither: false,
+            theme: Theme::new(),
+            dark_theme: Theme::new(),
+            outputs: Vec::new(),
+            post: None,
+            animation: None,
+            print: None,
+            bleed: None,
+            crop_marks: false,
+            images: None,
+            canvas: None,
+            time_ms: None,
+            profiling: false,
         }
     }
 
+    /// Creates a poster generator from a configuration, including its elements and settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poster_generator::{PosterConfig, PosterGenerator};
+    ///
+    /// let config: PosterConfig = serde_json::from_str(r##"{
+    ///     "width": 800,
+    ///     "height": 600,
+    ///     "background_color": "#ffffff",
+    ///     "on_missing_asset": "placeholder",
+    ///     "elements": []
+    /// }"##).unwrap();
+    /// let generator = PosterGenerator::from_config(config);
+    /// ```
+    pub fn from_config(config: PosterConfig) -> Self {
+        let mut generator = Self::new(config.width, config.height, config.background_color);
+        generator.on_missing_asset = config.on_missing_asset;
+        generator.placeholder = config.placeholder;
+        generator.watermark = config.watermark;
+        generator.base_path = config.base_path;
+        generator.font_fallback = config.font_fallback;
+        generator.set_fonts(config.fonts);
+        generator.safe_area = config.safe_area;
+        generator.quality = config.quality;
+        generator.font_rendering = config.font_rendering;
+        generator.dither = config.dither;
+        generator.theme = config.theme;
+        generator.dark_theme = config.dark_theme;
+        generator.outputs = config.outputs;
+        generator.post = config.post;
+        generator.animation = config.animation;
+        generator.print = config.print;
+        generator.bleed = config.bleed;
+        generator.crop_marks = config.crop_marks;
+        generator.set_elements(config.elements);
+        generator
+    }
+
+    /// Exports the poster as a config, e.g. to save a programmatically built poster
+    /// as a reusable JSON template.
+    ///
+    /// Registered assets and the asset resolver are not part of the config.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PosterError::NotExportable`] if an element was added with
+    /// [`add_custom`](Self::add_custom) and doesn't implement [`PosterElement::to_element`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poster_generator::{PosterGenerator, TextElement};
+    ///
+    /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+    /// generator.add_text(TextElement {
+    ///     text: "{{title}}".to_string(),
+    ///     x: 400.0,
+    ///     y: 300.0,
+    ///     ..Default::default()
+    /// });
+    ///
+    /// let config = generator.to_config().expect("Failed to export");
+    /// let json = serde_json::to_string_pretty(&config).unwrap();
+    /// ```
+    pub fn to_config(&self) -> Result<PosterConfig> {
+        let elements = self
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(index, element)| element.to_element().ok_or(PosterError::NotExportable(index)))
+            .collect::<Result<_>>()?;
+
+        Ok(PosterConfig {
+            width: self.width,
+            height: self.height,
+            background_color: self.background_color.clone(),
+            elements,
+            on_missing_asset: self.on_missing_asset,
+            placeholder: self.placeholder.clone(),
+            watermark: self.watermark.clone(),
+            base_path: self.base_path.clone(),
+            font_fallback: self.font_fallback.clone(),
+            safe_area: self.safe_area.clone(),
+            quality: self.quality,
+            font_rendering: self.font_rendering,
+            dither: self.dither,
+            theme: self.theme.clone(),
+            dark_theme: self.dark_theme.clone(),
+            outputs: self.outputs.clone(),
+            post: self.post.clone(),
+            fonts: self.fonts.clone(),
+            animation: self.animation.clone(),
+            print: self.print.clone(),
+            bleed: self.bleed,
+            crop_marks: self.crop_marks,
+        })
+    }
+
+    /// Sets the poster-wide policy for images and font files that cannot be loaded.
+    ///
+    /// Elements can override it with their own `on_missing_asset` setting.
+    pub fn set_missing_asset_policy(&mut self, policy: MissingAssetPolicy) -> &mut Self {
+        self.on_missing_asset = policy;
+        self
+    }
+
+    /// Sets the appearance of placeholders drawn for missing images.
+    pub fn set_placeholder(&mut self, placeholder: Placeholder) -> &mut Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// Sets a watermark drawn over all elements, or removes it with `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poster_generator::{PosterGenerator, Watermark, WatermarkPosition};
+    ///
+    /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+    /// generator.set_watermark(Some(Watermark {
+    ///     text: Some("SAMPLE".to_string()),
+    ///     position: WatermarkPosition::Center,
+    ///     opacity: 0.2,
+    ///     ..Default::default()
+    /// }));
+    /// ```
+    pub fn set_watermark(&mut self, watermark: Option<Watermark>) -> &mut Self {
+        self.watermark = watermark;
+        self
+    }
+
+    /// Registers in-memory image data that sources can refer to as `asset://<name>`.
+    ///
+    /// This lets applications pass images from memory or a database without writing
+    /// temporary files or embedding base64 data in configs. Registering a name again
+    /// replaces its data.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use poster_generator::{ImageElement, PosterGenerator};
+    ///
+    /// let logo = std::fs::read("logo.png").unwrap();
+    ///
+    /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+    /// generator.register_asset("logo", logo);
+    /// generator.add_image(ImageElement::builder().src("asset://logo").size(200.0, 200.0).build());
+    /// ```
+    pub fn register_asset(&mut self, name: impl Into<String>, bytes: impl Into<Vec<u8>>) -> &mut Self {
+        self.assets.insert(name.into(), Arc::from(bytes.into()));
+        self
+    }
+
+    /// Enables timing of each render, reported in [`RenderedPoster::profile`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poster_generator::PosterGenerator;
+    ///
+    /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+    /// generator.set_profiling(true);
+    /// let poster = generator.generate_with_metadata().expect("Failed to generate");
+    /// if let Some(slowest) = poster.profile.as_ref().and_then(|profile| profile.slowest()) {
+    ///     println!("element {} took {:.1} ms", slowest.index, slowest.total_ms);
+    /// }
+    /// ```
+    pub fn set_profiling(&mut self, enabled: bool) -> &mut Self {
+        self.profiling = enabled;
+        self
+    }
+
+    /// Sets the directory that relative image and font paths are resolved against.
+    ///
+    /// Without a base path, relative paths are resolved against the current directory.
+    pub fn set_base_path(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.base_path = Some(path.into());
+        self
+    }
+
+    /// Sets the font families tried for text without an installed `font_family` or a `font_file`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poster_generator::fonts::{FontFallback, Script};
+    /// use poster_generator::PosterGenerator;
+    ///
+    /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+    /// generator.set_font_fallback(
+    ///     FontFallback::default()
+    ///         .with_script(Script::Arabic, ["Noto Naskh Arabic"])
+    ///         .with_script(Script::Han, ["Noto Sans CJK SC"]),
+    /// );
+    /// ```
+    pub fn set_font_fallback(&mut self, fallback: FontFallback) -> &mut Self {
+        self.font_fallback = fallback;
+        self
+    }
+
+    /// Sets the fonts sent with the poster, which its elements can use by name as
+    /// `font_family`. The fonts are decoded here; fonts that are too large or
+    /// invalid are skipped with a warning.
+    pub fn set_fonts(&mut self, fonts: Vec<EmbeddedFont>) -> &mut Self {
+        self.typefaces = fonts::load_embedded_fonts(&fonts);
+        self.fonts = fonts;
+        self
+    }
+
+    /// Sets the margins of the canvas kept clear of important content, or removes them with `None`.
+    pub fn set_safe_area(&mut self, safe_area: Option<SafeArea>) -> &mut Self {
+        self.safe_area = safe_area;
+        self
+    }
+
+    /// Sets the trade-off between render speed and edge quality.
+    pub fn set_quality(&mut self, quality: Quality) -> &mut Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Sets the glyph edging, hinting and positioning used for all text.
+    pub fn set_font_rendering(&mut self, font_rendering: FontRendering) -> &mut Self {
+        self.font_rendering = font_rendering;
+        self
+    }
+
+    /// Sets whether gradients are dithered to hide banding.
+    pub fn set_dither(&mut self, dither: bool) -> &mut Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Returns up to `n` of the most common colors of an image, most common first.
+    ///
+    /// The image is loaded like an element's `src`, so registered assets and the
+    /// poster's resolver and base path are used. See [`palette`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image can't be read or decoded.
+    pub fn extract_palette(&self, src: &str, n: usize) -> Result<Vec<Color>> {
+        let image = load_image(src, &self.render_context())?;
+        Ok(palette::image_palette(&image, n))
+    }
+
+    /// Sets the design tokens that `"$name"` strings in the elements are replaced with,
+    /// e.g. to render the same poster in a dark theme. See [`theme`].
+    pub fn set_theme(&mut self, theme: Theme) -> &mut Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Sets the tokens replacing those of the theme in the dark variant.
+    pub fn set_dark_theme(&mut self, dark_theme: Theme) -> &mut Self {
+        self.dark_theme = dark_theme;
+        self
+    }
+
+    /// Sets the other sizes rendered by [`generate_outputs`](Self::generate_outputs).
+    pub fn set_outputs(&mut self, outputs: Vec<OutputSize>) -> &mut Self {
+        self.outputs = outputs;
+        self
+    }
+
+    /// Sets the crop, resize and sharpen steps applied to the poster before it is encoded.
+    pub fn set_post_process(&mut self, post: PostProcess) -> &mut Self {
+        self.post = Some(post);
+        self
+    }
+
+    /// Sets the keyframed element properties rendered by
+    /// [`generate_animation`](Self::generate_animation).
+    pub fn set_animation(&mut self, animation: Animation) -> &mut Self {
+        self.animation = Some(animation);
+        self
+    }
+
+    /// Sets the printing condition rendered for by [`generate_print`](Self::generate_print).
+    pub fn set_print_profile(&mut self, profile: PrintProfile) -> &mut Self {
+        self.print = Some(profile);
+        self
+    }
+
+    /// Sets the area added past each edge of the poster for trimming after printing.
+    pub fn set_bleed(&mut self, bleed: Length) -> &mut Self {
+        self.bleed = Some(bleed);
+        self
+    }
+
+    /// Sets whether trim marks are drawn at the corners of the poster, outside the bleed.
+    pub fn set_crop_marks(&mut self, crop_marks: bool) -> &mut Self {
+        self.crop_marks = crop_marks;
+        self
+    }
+
+    /// Sets the resolver that loads image and font sources.
+    ///
+    /// The default resolver reads files, base64 data URLs and HTTP(S) URLs.
+    /// See [`assets`] for an example.
+    pub fn set_asset_resolver(&mut self, resolver: impl AssetResolver + 'static) -> &mut Self {
+        self.resolver = Arc::new(resolver);
+        self
+    }
+
     /// Adds a background element to the poster.
     ///
-    /// Background elements are always rendered first (z-index: -1000).
+    /// Background elements are rendered first (z-index -1000) unless they set a `z_index`.
+    /// Several backgrounds are stacked in the order they were added.
     ///
     /// # Example
     ///
@@ -762,69 +3445,252 @@ impl PosterGenerator {
     ///     color: "#f0f0f0".to_string(),
     ///     image: None,
     ///     radius: Some(Radius::Single(20.0)),
+    ///     ..Default::default()
     /// };
     /// generator.add_background(bg);
     /// ```
     pub fn add_background(&mut self, background: BackgroundElement) -> &mut Self {
-        self.elements.push(Box::new(background));
+        self.push_element(Box::new(background));
+        self
+    }
+
+    /// Adds an image element to the poster.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poster_generator::{PosterGenerator, ImageElement, ObjectFit, Radius};
+    ///
+    /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+    /// let img = ImageElement {
+    ///     src: "photo.jpg".to_string(),
+    ///     x: 50.0,
+    ///     y: 50.0,
+    ///     width: 300.0,
+    ///     height: 200.0,
+    ///     radius: Some(Radius::Single(10.0)),
+    ///     z_index: Some(1),
+    ///     object_fit: ObjectFit::Cover,
+    ///     ..Default::default()
+    /// };
+    /// generator.add_image(img);
+    /// ```
+    pub fn add_image(&mut self, image: ImageElement) -> &mut Self {
+        self.push_element(Box::new(image));
+        self
+    }
+
+    /// Adds a text element to the poster.
+    ///
+    /// Text elements support RTL languages and will be automatically detected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poster_generator::{PosterGenerator, TextElement, TextAlignType, TextDirectionType};
+    ///
+    /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+    /// let text = TextElement {
+    ///     text: "Hello, World!".to_string(),
+    ///     x: 400.0,
+    ///     y: 300.0,
+    ///     font_size: 48.0,
+    ///     color: "#000000".to_string(),
+    ///     align: TextAlignType::Center,
+    ///     ..Default::default()
+    /// };
+    /// generator.add_text(text);
+    /// ```
+    pub fn add_text(&mut self, text: TextElement) -> &mut Self {
+        self.push_element(Box::new(text));
+        self
+    }
+
+    /// Adds a frosted-glass region that blurs the elements beneath it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poster_generator::{PosterGenerator, BackdropBlurElement, Radius};
+    ///
+    /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+    /// let card = BackdropBlurElement {
+    ///     x: 100.0,
+    ///     y: 100.0,
+    ///     width: 600.0,
+    ///     height: 400.0,
+    ///     radius: Some(Radius::Single(24.0)),
+    ///     blur: 20.0,
+    ///     tint: Some("#ffffff40".to_string()),
+    ///     id: None,
+    ///     z_index: Some(1),
+    /// };
+    /// generator.add_backdrop_blur(card);
+    /// ```
+    pub fn add_backdrop_blur(&mut self, blur: BackdropBlurElement) -> &mut Self {
+        self.push_element(Box::new(blur));
+        self
+    }
+
+    /// Adds a pie or donut chart to the poster.
+    pub fn add_pie_chart(&mut self, chart: PieChartElement) -> &mut Self {
+        self.push_element(Box::new(chart));
+        self
+    }
+
+    /// Adds a bar chart to the poster.
+    pub fn add_bar_chart(&mut self, chart: BarChartElement) -> &mut Self {
+        self.push_element(Box::new(chart));
+        self
+    }
+
+    /// Adds a sparkline to the poster.
+    pub fn add_sparkline(&mut self, sparkline: SparklineElement) -> &mut Self {
+        self.push_element(Box::new(sparkline));
+        self
+    }
+
+    /// Adds a badge to the poster.
+    pub fn add_badge(&mut self, badge: BadgeElement) -> &mut Self {
+        self.push_element(Box::new(badge));
+        self
+    }
+
+    /// Adds a named icon to the poster.
+    pub fn add_icon(&mut self, icon: IconElement) -> &mut Self {
+        self.push_element(Box::new(icon));
+        self
+    }
+
+    /// Adds a line to the poster.
+    pub fn add_line(&mut self, line: LineElement) -> &mut Self {
+        self.push_element(Box::new(line));
+        self
+    }
+
+    /// Adds a rectangle to the poster.
+    pub fn add_rect(&mut self, rect: RectElement) -> &mut Self {
+        self.push_element(Box::new(rect));
+        self
+    }
+
+    /// Adds a circle to the poster.
+    pub fn add_circle(&mut self, circle: CircleElement) -> &mut Self {
+        self.push_element(Box::new(circle));
+        self
+    }
+
+    /// Adds an SVG path shape to the poster.
+    pub fn add_path(&mut self, path: PathElement) -> &mut Self {
+        self.push_element(Box::new(path));
+        self
+    }
+
+    /// Adds a film grain overlay to the poster.
+    pub fn add_noise(&mut self, noise: NoiseElement) -> &mut Self {
+        self.push_element(Box::new(noise));
+        self
+    }
+
+    /// Adds a pixelated region to the poster.
+    pub fn add_pixelate(&mut self, pixelate: PixelateElement) -> &mut Self {
+        self.push_element(Box::new(pixelate));
+        self
+    }
+
+    /// Adds procedurally generated artwork to the poster.
+    pub fn add_generative(&mut self, generative: GenerativeElement) -> &mut Self {
+        self.push_element(Box::new(generative));
+        self
+    }
+
+    /// Adds a custom element implementing [`PosterElement`].
+    ///
+    /// Custom elements are layered with the built-in elements according to their z-index.
+    pub fn add_custom(&mut self, element: Box<dyn PosterElement>) -> &mut Self {
+        self.push_element(element);
+        self
+    }
+
+    // Add an element under its own id, or a new one if it has none
+    fn push_element(&mut self, element: Box<dyn PosterElement>) {
+        let id = match element.id() {
+            Some(id) => id.to_string(),
+            None => {
+                self.next_id += 1;
+                format!("element-{}", self.next_id)
+            }
+        };
+        self.ids.push(id);
+        self.elements.push(element);
+    }
+
+    /// Clears all elements from the poster.
+    pub fn clear(&mut self) -> &mut Self {
+        self.elements.clear();
+        self.ids.clear();
         self
     }
 
-    /// Adds an image element to the poster.
-    ///
-    /// # Example
+    /// Ids of the elements, in the order they were added.
     ///
-    /// ```
-    /// use poster_generator::{PosterGenerator, ImageElement, ObjectFit, Radius};
-    ///
-    /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
-    /// let img = ImageElement {
-    ///     src: "photo.jpg".to_string(),
-    ///     x: 50.0,
-    ///     y: 50.0,
-    ///     width: 300.0,
-    ///     height: 200.0,
-    ///     radius: Some(Radius::Single(10.0)),
-    ///     z_index: Some(1),
-    ///     object_fit: ObjectFit::Cover,
-    /// };
-    /// generator.add_image(img);
-    /// ```
-    pub fn add_image(&mut self, image: ImageElement) -> &mut Self {
-        self.elements.push(Box::new(image));
-        self
+    /// Every element gets an id when it is added: its own `id`, or `element-<n>`
+    /// for elements without one. Ids are kept for the element's lifetime, even if
+    /// its `id` field is changed later.
+    pub fn element_ids(&self) -> impl Iterator<Item = &str> {
+        self.ids.iter().map(String::as_str)
     }
 
-    /// Adds a text element to the poster.
-    ///
-    /// Text elements support RTL languages and will be automatically detected.
+    /// Id of the most recently added element.
+    pub fn last_element_id(&self) -> Option<&str> {
+        self.ids.last().map(String::as_str)
+    }
+
+    // Position of the element with the id
+    fn element_index(&self, id: &str) -> Option<usize> {
+        self.ids.iter().position(|element_id| element_id == id)
+    }
+
+    /// The element with the id.
+    pub fn get_element(&self, id: &str) -> Option<&dyn PosterElement> {
+        self.element_index(id).map(|index| &*self.elements[index])
+    }
+
+    /// The element with the id, if it is a `T`, for changing it in place between renders.
     ///
     /// # Example
     ///
     /// ```
-    /// use poster_generator::{PosterGenerator, TextElement, TextAlignType, TextDirectionType};
+    /// use poster_generator::{PosterGenerator, TextElement};
     ///
     /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
-    /// let text = TextElement {
-    ///     text: "Hello, World!".to_string(),
-    ///     x: 400.0,
-    ///     y: 300.0,
-    ///     font_size: 48.0,
-    ///     color: "#000000".to_string(),
-    ///     align: TextAlignType::Center,
-    ///     ..Default::default()
-    /// };
-    /// generator.add_text(text);
+    /// generator.add_text(TextElement { id: Some("price".to_string()), text: "$9".to_string(), ..Default::default() });
+    ///
+    /// for price in ["$8", "$7"] {
+    ///     if let Some(text) = generator.get_element_mut::<TextElement>("price") {
+    ///         text.text = price.to_string();
+    ///     }
+    ///     let png = generator.generate().expect("Failed to generate");
+    /// }
     /// ```
-    pub fn add_text(&mut self, text: TextElement) -> &mut Self {
-        self.elements.push(Box::new(text));
-        self
+    pub fn get_element_mut<T: PosterElement>(&mut self, id: &str) -> Option<&mut T> {
+        let index = self.element_index(id)?;
+        self.elements[index].as_mut().as_any_mut().downcast_mut::<T>()
     }
 
-    /// Clears all elements from the poster.
-    pub fn clear(&mut self) -> &mut Self {
-        self.elements.clear();
-        self
+    /// Removes the element with the id, returning it.
+    pub fn remove(&mut self, id: &str) -> Option<Box<dyn PosterElement>> {
+        let index = self.element_index(id)?;
+        self.ids.remove(index);
+        Some(self.elements.remove(index))
+    }
+
+    /// Replaces the element with the id, keeping its place and id. Returns the
+    /// previous element, or `None` (leaving the poster unchanged) if there is no
+    /// element with the id.
+    pub fn replace(&mut self, id: &str, element: Element) -> Option<Box<dyn PosterElement>> {
+        let index = self.element_index(id)?;
+        Some(std::mem::replace(&mut self.elements[index], element.into_boxed()))
     }
 
     /// Sets all elements at once, replacing any existing elements.
@@ -852,11 +3718,7 @@ impl PosterGenerator {
         self.clear();
         
         for element in elements {
-            match element {
-                Element::Background(bg) => self.add_background(bg),
-                Element::Image(img) => self.add_image(img),
-                Element::Text(txt) => self.add_text(txt),
-            };
+            self.push_element(element.into_boxed());
         }
         
         self
@@ -880,36 +3742,561 @@ impl PosterGenerator {
     /// std::fs::write("output.png", png_data).expect("Failed to write file");
     /// ```
     pub fn generate(&self) -> Result<Vec<u8>> {
-        // Create surface
-        let mut surface = skia_safe::surfaces::raster_n32_premul((self.width as i32, self.height as i32)).ok_or_else(|| {
-            PosterError::RenderError("Failed to create surface".to_string())
-        })?;
-        
-        {
-            // Get canvas
-            let canvas = surface.canvas();
-            
-            // Fill with background color
-            let bg_color = parse_color(&self.background_color);
-            canvas.clear(bg_color);
-            
-            // Sort elements by z-index
-            let mut sorted_elements = self.elements.iter().collect::<Vec<_>>();
-            sorted_elements.sort_by_key(|e| e.z_index());
-            
-            // Render each element
-            for element in sorted_elements {
-                element.render(canvas)?;
+        self.render(false).map(|poster| poster.png)
+    }
+
+    /// Generates the poster along with the id, bounds and text of every element,
+    /// and the [warnings](Self::generate_with_warnings) found while rendering.
+    ///
+    /// Client apps can use the metadata to place clickable hotspots over the
+    /// rendered image. Set `id` on elements to tell them apart. The text of the
+    /// elements gives alt text for the poster, see [`RenderedPoster::alt_text`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering fails or PNG encoding fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poster_generator::{PosterGenerator, TextElement};
+    ///
+    /// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+    /// generator.add_text(TextElement {
+    ///     id: Some("title".to_string()),
+    ///     text: "Hello".to_string(),
+    ///     x: 400.0,
+    ///     y: 300.0,
+    ///     ..Default::default()
+    /// });
+    ///
+    /// let poster = generator.generate_with_metadata().expect("Failed to generate");
+    /// let json = serde_json::to_string(&poster.elements).unwrap();
+    /// ```
+    pub fn generate_with_metadata(&self) -> Result<RenderedPoster> {
+        self.render(true)
+    }
+
+    /// Generates the poster along with the quality problems found while rendering it:
+    /// elements outside the canvas, text that didn't fit, missing fonts and images
+    /// upscaled more than [`MAX_UPSCALE`](warnings::MAX_UPSCALE) times. See [`warnings`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering fails or PNG encoding fails. Warnings don't fail the render.
+    pub fn generate_with_warnings(&self) -> Result<(Vec<u8>, Vec<RenderWarning>)> {
+        self.render(true).map(|poster| (poster.png, poster.warnings))
+    }
+
+    // Render and encode the poster, collecting element metadata if asked to
+    fn render(&self, with_metadata: bool) -> Result<RenderedPoster> {
+        let _span = info_span!("generate", width = self.width, height = self.height, elements = self.elements.len()).entered();
+        // Clocks are only read when profiling, as some targets (wasm32) have none
+        let start = self.profiling.then(Instant::now);
+
+        let sheet = self.sheet();
+        let mut surface = self.raster_surface(sheet.width, sheet.height)?;
+        let Drawn { metadata, mut profile, warnings } =
+            self.draw_supersampled(&mut surface, |canvas| self.draw_sheet(canvas, &sheet, with_metadata))?;
+
+        let encode_start = self.profiling.then(Instant::now);
+        let png = self.encode(&mut surface)?;
+        if let (Some(profile), Some(start), Some(encode_start)) = (&mut profile, start, encode_start) {
+            profile.encode_ms = profile::ms(encode_start.elapsed());
+            profile.total_ms = profile::ms(start.elapsed());
+        }
+
+        Ok(RenderedPoster {
+            png,
+            elements: metadata,
+            profile,
+            warnings,
+        })
+    }
+
+    // Encode the finished poster, after its post-processing steps
+    fn encode(&self, surface: &mut Surface) -> Result<Vec<u8>> {
+        match self.post.as_ref().filter(|post| !post.is_noop()) {
+            Some(post) => encode_image(&post.apply(surface.image_snapshot())?),
+            None => encode_png(surface),
+        }
+    }
+
+    // Resolution that print lengths are converted at
+    fn print_dpi(&self) -> f32 {
+        self.print.as_ref().map_or(print::DEFAULT_DPI, |print| print.dpi)
+    }
+
+    // Bleed in pixels
+    fn bleed_px(&self) -> f32 {
+        self.bleed.map_or(0.0, |bleed| bleed.to_px(self.print_dpi()).max(0.0))
+    }
+
+    // Layout of the poster on a sheet with room for its bleed and crop marks
+    fn sheet(&self) -> Sheet {
+        let bleed = self.bleed_px();
+        let mm = |mm: f32| mm * self.print_dpi() / 25.4;
+        // Marks start outside the bleed, so none of them is left after trimming
+        let marks = self.crop_marks.then(|| CropMarks {
+            offset: bleed.max(mm(3.0)),
+            length: mm(5.0),
+            // 0.25 pt hairlines
+            width: (0.25 * self.print_dpi() / 72.0).max(1.0),
+        });
+        let margin = match &marks {
+            Some(marks) => marks.offset + marks.length + mm(1.0),
+            None => bleed,
+        }
+        .ceil();
+        let trim = Rect::from_xywh(margin, margin, self.width as f32, self.height as f32);
+        Sheet {
+            width: (self.width as f32 + 2.0 * margin) as i32,
+            height: (self.height as f32 + 2.0 * margin) as i32,
+            trim,
+            bleed: trim.with_outset((bleed, bleed)),
+            marks,
+        }
+    }
+
+    // Draw the poster onto a print sheet: on white paper, clipped to its bleed, with
+    // crop marks around it. Without bleed or marks, this is the poster itself.
+    fn draw_sheet(&self, canvas: &Canvas, sheet: &Sheet, with_metadata: bool) -> Result<Drawn> {
+        if sheet.trim.left == 0.0 {
+            return self.draw(canvas, None, |_| false, with_metadata);
+        }
+        canvas.clear(Color::WHITE);
+        canvas.save();
+        canvas.translate((sheet.trim.left, sheet.trim.top));
+        let bleed = sheet.bleed.with_offset((-sheet.trim.left, -sheet.trim.top));
+        let drawn = self.draw(canvas, Some(bleed), |_| false, with_metadata);
+        canvas.restore();
+        if let Some(marks) = &sheet.marks {
+            draw_crop_marks(canvas, sheet.trim, marks);
+        }
+        drawn
+    }
+
+    // Create a surface the size of the poster
+    fn create_surface(&self) -> Result<Surface> {
+        self.raster_surface(self.width as i32, self.height as i32)
+    }
+
+    // Create a surface to draw the poster or part of it on. LCD text needs the
+    // surface's subpixel layout, which Skia otherwise treats as unknown.
+    fn raster_surface(&self, width: i32, height: i32) -> Result<Surface> {
+        let props = (self.font_rendering.edging == FontEdgingType::Subpixel)
+            .then(|| SurfaceProps::new(SurfacePropsFlags::empty(), PixelGeometry::RGBH));
+        skia_safe::surfaces::raster(&ImageInfo::new_n32_premul((width, height), None), None, props.as_ref())
+            .ok_or_else(|| PosterError::RenderError("Failed to create surface".to_string()))
+    }
+
+    // Run `draw` on the surface, or on a larger one scaled back down onto it when
+    // supersampling. The surface's previous contents are replaced.
+    fn draw_supersampled(&self, surface: &mut Surface, draw: impl FnOnce(&Canvas) -> Result<Drawn>) -> Result<Drawn> {
+        let factor = self.quality.supersample.clamp(1, MAX_SUPERSAMPLE);
+        if factor == 1 {
+            return draw(surface.canvas());
+        }
+
+        let _span = debug_span!("supersample", factor).entered();
+        let (width, height) = (surface.width(), surface.height());
+        let mut large = self.raster_surface(width * factor as i32, height * factor as i32)?;
+        large.canvas().scale((factor as f32, factor as f32));
+        let drawn = draw(large.canvas())?;
+
+        // Mipmaps average each block of pixels rather than picking a few of them
+        let mut paint = Paint::default();
+        paint.set_blend_mode(BlendMode::Src);
+        let sampling = SamplingOptions::new(FilterMode::Linear, MipmapMode::Linear);
+        let image = large.image_snapshot();
+        surface.canvas().draw_image_rect_with_sampling_options(image, None, Rect::from_iwh(width, height), sampling, &paint);
+        Ok(drawn)
+    }
+
+    // Dominant color of the largest image element (or else the background image),
+    // white if there is none or it can't be loaded
+    fn auto_background_color(&self, elements: &ThemedElements<'_>, ctx: &RenderContext) -> Color {
+        let elements: Vec<Element> = elements.iter().filter_map(|element| element.to_element()).collect();
+        let largest_image = elements
+            .iter()
+            .filter_map(|element| match element {
+                Element::Image(image) => Some(image),
+                _ => None,
+            })
+            .max_by(|a, b| (a.width * a.height).total_cmp(&(b.width * b.height)))
+            .map(|image| image.src.as_str());
+        let background_image = elements.iter().find_map(|element| match element {
+            Element::Background(background) => background.image.as_deref(),
+            _ => None,
+        });
+        let Some(src) = largest_image.or(background_image) else {
+            warn!("background_color is \"auto\" but the poster has no image, using white");
+            return Color::WHITE;
+        };
+
+        match load_image(src, ctx) {
+            Ok(image) => palette::image_palette(&image, 1).first().copied().unwrap_or(Color::WHITE),
+            Err(err) => {
+                warn!("Can't sample {} for the background color, using white: {}", assets::describe_source(src), err);
+                Color::WHITE
             }
         }
+    }
+
+    // The elements with the theme's tokens replaced by their values
+    fn themed_elements(&self) -> Result<ThemedElements<'_>> {
+        let themed = self
+            .elements
+            .iter()
+            .map(|element| {
+                let Some(element) = element.to_element().filter(|_| !self.theme.is_empty()) else {
+                    return Ok(None);
+                };
+                let mut value = serde_json::to_value(element)?;
+                if !theme::apply(&mut value, &self.theme) {
+                    return Ok(None);
+                }
+                Ok(Some(serde_json::from_value::<Element>(value)?.into_boxed()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(ThemedElements { elements: &self.elements, themed })
+    }
+
+    // Settings shared by the elements during one render
+    fn render_context(&self) -> RenderContext {
+        RenderContext {
+            width: self.width as f32,
+            height: self.height as f32,
+            canvas: self.canvas.unwrap_or_else(|| Rect::from_wh(self.width as f32, self.height as f32)),
+            bleed: self.bleed_px(),
+            on_missing_asset: self.on_missing_asset,
+            placeholder: self.placeholder.clone(),
+            assets: self.assets.clone(),
+            resolver: self.resolver.clone(),
+            base_path: self.base_path.clone(),
+            font_fallback: self.font_fallback.clone(),
+            typefaces: self.typefaces.clone(),
+            safe_area: self.safe_area.clone(),
+            quality: self.quality,
+            font_rendering: self.font_rendering,
+            dither: self.dither,
+            images: self.images.clone(),
+            timings: self.profiling.then(RefCell::default),
+            warnings: None,
+            background_drawn: Cell::new(false),
+        }
+    }
+
+    // Draw the poster onto the canvas. With a clip rect, only that region is redrawn;
+    // elements for which `skip` returns true are left out. Warnings are collected
+    // along with the metadata.
+    fn draw(
+        &self,
+        canvas: &Canvas,
+        clip: Option<Rect>,
+        skip: impl Fn(usize) -> bool,
+        with_metadata: bool,
+    ) -> Result<Drawn> {
+        let mut metadata = Vec::new();
+        let mut profile = self.profiling.then(RenderProfile::default);
+        let mut warnings = Vec::new();
+
+        canvas.save();
+        if let Some(clip) = clip {
+            canvas.clip_rect(clip, None, None);
+        }
+
+        let ctx = RenderContext { warnings: with_metadata.then(RefCell::default), ..self.render_context() };
+        let elements = self.themed_elements()?;
+
+        // Fill with background color
+        let background_color = theme::resolve(&self.background_color, &self.theme);
+        let bg_color = if background_color == AUTO_COLOR {
+            self.auto_background_color(&elements, &ctx)
+        } else {
+            parse_color(background_color, "background_color")?
+        };
+        canvas.clear(bg_color);
         
-        // Encode as PNG
-        let image = surface.image_snapshot();
-        let data = image.encode_to_data(EncodedImageFormat::PNG).ok_or_else(|| {
-            PosterError::OutputError("Failed to encode image as PNG".to_string())
-        })?;
+        // Sort elements by z-index
+        let mut sorted_elements = elements.iter().enumerate().collect::<Vec<_>>();
+        sorted_elements.sort_by_key(|(_, e)| e.z_index());
         
-        Ok(data.as_bytes().to_vec())
+        // Render each element
+        for (index, element) in sorted_elements {
+            if skip(index) {
+                continue;
+            }
+
+            let _span = debug_span!("render_element", index, z_index = element.z_index()).entered();
+            let start = self.profiling.then(Instant::now);
+            let animated = match (self.time_ms, &self.animation, element.id()) {
+                (Some(time_ms), Some(animation), Some(id)) => animation.state_at(id, time_ms),
+                _ => None,
+            };
+            match animated {
+                Some(state) => state.render(canvas, element.bounds(&ctx), || element.render(canvas, &ctx)),
+                None => element.render(canvas, &ctx),
+            }
+            .map_err(|source| PosterError::ElementRender {
+                index,
+                source: Box::new(source),
+            })?;
+            if let (Some(profile), Some(timings), Some(start)) = (&mut profile, &ctx.timings, start) {
+                let timings = timings.borrow_mut().take();
+                profile.elements.push(ElementProfile::new(index, element.id(), timings, start.elapsed()));
+            }
+
+            if with_metadata {
+                let bounds = element.bounds(&ctx);
+                let overflowed = element.overflows(&ctx);
+                if bounds.is_some_and(|bounds| !bounds.intersects(ctx.canvas_rect())) {
+                    ctx.add_warning(WarningKind::OffCanvas);
+                }
+                if overflowed {
+                    ctx.add_warning(WarningKind::TextOverflow);
+                }
+                if let Some(element_warnings) = &ctx.warnings {
+                    warnings.extend(element_warnings.take().into_iter().map(|kind| RenderWarning {
+                        index,
+                        id: element.id().map(str::to_string),
+                        kind,
+                    }));
+                }
+                metadata.push(ElementMetadata {
+                    index,
+                    id: element.id().map(str::to_string),
+                    z_index: element.z_index(),
+                    bounds: bounds.map(ElementBounds::from),
+                    text: element.text_content(),
+                    baseline: element.baseline(&ctx),
+                    overflowed,
+                });
+            }
+        }
+
+        if let Some(watermark) = &self.watermark {
+            let _span = debug_span!("watermark", tile = watermark.tile).entered();
+            let start = self.profiling.then(Instant::now);
+            watermark.render(canvas, &ctx)?;
+            if let (Some(profile), Some(start)) = (&mut profile, start) {
+                profile.watermark_ms = profile::ms(start.elapsed());
+            }
+        }
+
+        if self.safe_area.as_ref().is_some_and(|area| area.show_guides) {
+            draw_safe_area_guides(canvas, &ctx);
+        }
+
+        canvas.restore();
+        Ok(Drawn { metadata, profile, warnings })
+    }
+
+    /// Renders the poster with its theme, then again with the dark theme's tokens
+    /// replacing those of the theme. Images are decoded once for both renders.
+    ///
+    /// Returns the light and the dark PNG data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no dark theme or rendering fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use poster_generator::theme::Theme;
+    /// use poster_generator::PosterGenerator;
+    ///
+    /// let mut generator = PosterGenerator::new(800, 600, "$surface".to_string());
+    /// generator.set_theme(Theme::from([("surface".to_string(), "#ffffff".to_string())]));
+    /// generator.set_dark_theme(Theme::from([("surface".to_string(), "#121212".to_string())]));
+    /// let (light, dark) = generator.generate_light_and_dark().expect("Failed to generate");
+    /// ```
+    pub fn generate_light_and_dark(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+        if self.dark_theme.is_empty() {
+            return Err(PosterError::RenderError("No dark_theme to render the dark variant with".to_string()));
+        }
+        let light_theme = self.theme.clone();
+        let mut dark_theme = light_theme.clone();
+        dark_theme.extend(self.dark_theme.clone());
+
+        self.images = Some(ImageCache::default());
+        let result = self.generate().and_then(|light| {
+            self.theme = dark_theme;
+            Ok((light, self.generate()?))
+        });
+        self.theme = light_theme;
+        self.images = None;
+        result
+    }
+
+    /// Renders the poster at its own size, then at each of its [`outputs`](PosterConfig::outputs)
+    /// sizes. Images are decoded once for all sizes.
+    ///
+    /// The poster's own size comes first, with an empty suffix. [`post`](PosterConfig::post)
+    /// steps only apply to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an output has a zero width or height, or rendering fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use poster_generator::{OutputSize, PosterGenerator};
+    ///
+    /// let mut generator = PosterGenerator::new(1080, 1350, "#ffffff".to_string());
+    /// generator.set_outputs(vec![OutputSize { width: 1200, height: 630, suffix: "-og".to_string() }]);
+    /// for output in generator.generate_outputs().expect("Failed to generate") {
+    ///     std::fs::write(format!("poster{}.png", output.suffix), output.png).unwrap();
+    /// }
+    /// ```
+    pub fn generate_outputs(&mut self) -> Result<Vec<RenderedOutput>> {
+        self.images = Some(ImageCache::default());
+        let result = self.generate().and_then(|png| {
+            let mut rendered = vec![RenderedOutput { suffix: String::new(), width: self.width, height: self.height, png }];
+            for output in self.outputs.clone() {
+                let png = self.generate_size(output.width, output.height)?;
+                rendered.push(RenderedOutput { suffix: output.suffix, width: output.width, height: output.height, png });
+            }
+            Ok(rendered)
+        });
+        self.images = None;
+        result
+    }
+
+    // Render the poster scaled to fit the size, centered, with the rest of the output
+    // as an extension of the canvas
+    fn generate_size(&mut self, width: u32, height: u32) -> Result<Vec<u8>> {
+        if width == 0 || height == 0 || self.width == 0 || self.height == 0 {
+            return Err(PosterError::OutputError(format!("Can't render the poster at {}x{}", width, height)));
+        }
+        let _span = info_span!("generate_size", width, height).entered();
+        let scale = (width as f32 / self.width as f32).min(height as f32 / self.height as f32);
+        let (canvas_width, canvas_height) = (width as f32 / scale, height as f32 / scale);
+        let area = Rect::from_xywh(
+            (self.width as f32 - canvas_width) / 2.0,
+            (self.height as f32 - canvas_height) / 2.0,
+            canvas_width,
+            canvas_height,
+        );
+
+        self.canvas = Some(area);
+        let mut surface = self.raster_surface(width as i32, height as i32)?;
+        let drawn = self.draw_supersampled(&mut surface, |canvas| {
+            canvas.scale((scale, scale));
+            canvas.translate((-area.left, -area.top));
+            self.draw(canvas, None, |_| false, false)
+        });
+        self.canvas = None;
+        drawn?;
+        encode_png(&mut surface)
+    }
+
+    /// Renders the poster for print: converted from sRGB to the [`print`](PosterConfig::print)
+    /// profile's color space and encoded as a TIFF or PDF with the profile embedded.
+    /// See [`print`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the poster has no print profile, the profile can't be loaded
+    /// or converted to, the `print` feature is disabled, or rendering fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use poster_generator::print::{PrintFormat, PrintProfile, RenderingIntent};
+    /// use poster_generator::PosterGenerator;
+    ///
+    /// let mut generator = PosterGenerator::new(2480, 3508, "#ffffff".to_string());
+    /// generator.set_print_profile(PrintProfile {
+    ///     icc_profile: "ISOcoated_v2_300_eci.icc".to_string(),
+    ///     intent: RenderingIntent::RelativeColorimetric,
+    ///     black_point_compensation: true,
+    ///     dpi: 300.0,
+    /// });
+    /// let pdf = generator.generate_print(PrintFormat::Pdf).expect("Failed to render");
+    /// std::fs::write("poster.pdf", pdf).unwrap();
+    /// ```
+    pub fn generate_print(&self, format: PrintFormat) -> Result<Vec<u8>> {
+        let Some(profile) = &self.print else {
+            return Err(PosterError::OutputError("No print profile to convert to".to_string()));
+        };
+        let _span = info_span!("generate_print", width = self.width, height = self.height, ?format).entered();
+        let icc = self.render_context().load_asset(&profile.icc_profile).map_err(|e| {
+            let source = assets::describe_source(&profile.icc_profile);
+            PosterError::OutputError(format!("Failed to read ICC profile {}: {}", source, e))
+        })?;
+
+        let sheet = self.sheet();
+        let mut surface = self.raster_surface(sheet.width, sheet.height)?;
+        self.draw_supersampled(&mut surface, |canvas| self.draw_sheet(canvas, &sheet, false))?;
+        let (image, boxes) = match self.post.as_ref().filter(|post| !post.is_noop()) {
+            // Cropping and resizing move the poster's edges, so the whole page is trimmed to
+            Some(post) => {
+                let image = post.apply(surface.image_snapshot())?;
+                let page = Rect::from_iwh(image.width(), image.height());
+                (image, (page, page))
+            }
+            None => (surface.image_snapshot(), (sheet.trim, sheet.bleed)),
+        };
+        print::encode(&image, profile, &icc, format, boxes)
+    }
+
+    /// Renders the poster's [`animation`](PosterConfig::animation) frame by frame and
+    /// encodes it as an animated GIF or PNG. Images are decoded once for all frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the poster has no animation, or rendering or encoding fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use poster_generator::animation::AnimationFormat;
+    /// use poster_generator::{PosterConfig, PosterGenerator};
+    ///
+    /// # let config: PosterConfig = unimplemented!();
+    /// let mut generator = PosterGenerator::from_config(config);
+    /// let gif = generator.generate_animation(AnimationFormat::Gif).expect("Failed to animate");
+    /// std::fs::write("poster.gif", gif).unwrap();
+    /// ```
+    pub fn generate_animation(&mut self, format: AnimationFormat) -> Result<Vec<u8>> {
+        let Some(animation) = self.animation.clone() else {
+            return Err(PosterError::RenderError("No animation to render".to_string()));
+        };
+        let _span = info_span!("generate_animation", frames = animation.frame_count()).entered();
+        let pixels = self
+            .generate_frames(animation.fps(), animation.duration_ms, FrameFormat::Rgba)
+            .collect::<Result<Vec<_>>>()?;
+        Frames::new(&animation, self.width, self.height, pixels).encode(format)
+    }
+
+    /// Renders `duration_ms` of the poster's [`animation`](PosterConfig::animation) at `fps`
+    /// frames per second, one frame at a time, e.g. to pipe into ffmpeg for a video.
+    ///
+    /// Frames are rendered as the iterator is advanced, so only one is held in memory.
+    /// Raw frames are unpremultiplied RGBA, row by row, the size of the poster. Without
+    /// an animation, every frame is the still poster. Images are decoded once for all frames.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use poster_generator::animation::FrameFormat;
+    /// use poster_generator::{PosterConfig, PosterGenerator};
+    /// use std::io::Write;
+    ///
+    /// # let config: PosterConfig = unimplemented!();
+    /// // ffmpeg -f rawvideo -pix_fmt rgba -s 1080x1920 -r 30 -i - poster.mp4
+    /// let mut generator = PosterGenerator::from_config(config);
+    /// let mut stdout = std::io::stdout().lock();
+    /// for frame in generator.generate_frames(30, 3000, FrameFormat::Rgba) {
+    ///     stdout.write_all(&frame.expect("Failed to render frame")).unwrap();
+    /// }
+    /// ```
+    pub fn generate_frames(&mut self, fps: u32, duration_ms: u32, format: FrameFormat) -> FrameSequence<'_> {
+        self.images = Some(ImageCache::default());
+        FrameSequence::new(self, fps, duration_ms, format)
     }
 
     /// Generates the poster and saves it to a file.
@@ -939,6 +4326,95 @@ impl PosterGenerator {
         Ok(())
     }
 
+    /// Renders the poster in horizontal strips and streams them into a PNG.
+    ///
+    /// A single surface for a billboard-size poster (20000×10000 needs 800 MB) may not
+    /// fit in memory. Here only one strip of `strip_height` rows is held at a time, plus
+    /// a margin above and below it. The margin is as wide as the furthest reaching
+    /// backdrop blur, box shadow or pixelated block among the elements (and at least
+    /// 64 rows), so those effects match a single render where they cross a strip edge.
+    /// Elements are redrawn for each strip they cross, so this is slower than
+    /// [`generate`](Self::generate) for posters that fit in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the poster has [`post`](PosterConfig::post) steps, which need the
+    /// whole poster, or if rendering, encoding or writing fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use poster_generator::PosterGenerator;
+    /// use std::io::BufWriter;
+    ///
+    /// let generator = PosterGenerator::new(20000, 10000, "#ffffff".to_string());
+    /// let file = BufWriter::new(std::fs::File::create("billboard.png").unwrap());
+    /// generator.write_png_tiled(file, 1024).expect("Failed to render");
+    /// ```
+    pub fn write_png_tiled<W: Write>(&self, writer: W, strip_height: u32) -> Result<()> {
+        let _span = info_span!("render_tiled", width = self.width, height = self.height, strip_height).entered();
+        if self.post.as_ref().is_some_and(|post| !post.is_noop()) {
+            return Err(PosterError::OutputError("Post-processing needs the whole poster and can't be rendered in strips".to_string()));
+        }
+        let strip_height = strip_height.clamp(1, self.height.max(1));
+        let overlap = self.tile_overlap();
+        let surface_height = strip_height + 2 * overlap;
+        let mut surface = self.raster_surface(self.width as i32, surface_height as i32)?;
+
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut png_writer = encoder.write_header().map_err(png_error)?;
+        let mut stream = png_writer.stream_writer().map_err(png_error)?;
+
+        // Elements outside a strip (and its margin) are skipped
+        let ctx = self.render_context();
+        let areas: Vec<Option<Rect>> = self.themed_elements()?.iter().map(|element| element.bounds(&ctx)).collect();
+
+        let row_bytes = self.width as usize * 4;
+        let mut rows = vec![0u8; row_bytes * strip_height as usize];
+        let mut top = 0;
+        while top < self.height {
+            let strip_rows = strip_height.min(self.height - top);
+            // Surface row 0 is poster row `origin`, so the strip starts at surface row `overlap`
+            let origin = top as f32 - overlap as f32;
+            let region = Rect::from_xywh(0.0, origin, self.width as f32, surface_height as f32);
+            debug!(top, rows = strip_rows, "Rendering strip");
+
+            let outside = |index: usize| areas[index].is_some_and(|area| !area.intersects(region));
+            self.draw_supersampled(&mut surface, |canvas| {
+                canvas.save();
+                canvas.translate((0.0, -origin));
+                let drawn = self.draw(canvas, Some(region), outside, false);
+                canvas.restore();
+                drawn
+            })?;
+
+            let size = (self.width as i32, strip_rows as i32);
+            let info = ImageInfo::new(size, ColorType::RGBA8888, AlphaType::Unpremul, None);
+            let len = row_bytes * strip_rows as usize;
+            if !surface.read_pixels(&info, &mut rows[..len], row_bytes, (0, overlap as i32)) {
+                return Err(PosterError::RenderError("Failed to read strip pixels".to_string()));
+            }
+            stream.write_all(&rows[..len])?;
+            top += strip_rows;
+        }
+
+        stream.finish().map_err(png_error)?;
+        Ok(())
+    }
+
+    // Rows drawn above and below each strip of a tiled render
+    fn tile_overlap(&self) -> u32 {
+        let reach = self
+            .elements
+            .iter()
+            .filter_map(|element| element.to_element())
+            .map(|element| effect_reach(&element))
+            .fold(0.0, f32::max);
+        TILE_OVERLAP.max(reach.ceil() as u32)
+    }
+
     /// Generates the poster as a base64 encoded data URL.
     ///
     /// Returns a string in the format: `data:image/png;base64,<encoded_data>`
@@ -966,8 +4442,141 @@ impl PosterGenerator {
     }
 }
 
+/// Optional capabilities of this build, for checking at runtime.
+///
+/// Features that depend on cargo features can be checked here before a config
+/// relying on them is rendered.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// AVIF images can be decoded (`avif` feature).
+    pub avif: bool,
+    /// HEIC images can be decoded (`heic` feature).
+    pub heic: bool,
+    /// Images and fonts can be loaded from http(s) URLs (`http` feature).
+    pub http: bool,
+    /// Text can be shaped with rustybuzz (`rustybuzz` feature).
+    pub rustybuzz: bool,
+    /// Frames of Lottie animations can be drawn as images (`lottie` feature).
+    pub lottie: bool,
+    /// Posters can be converted to ICC print profiles (`print` feature).
+    pub print: bool,
+}
+
+/// Returns the capabilities of this build.
+///
+/// # Example
+///
+/// ```
+/// let capabilities = poster_generator::capabilities();
+/// if !capabilities.avif {
+///     println!("AVIF images are not supported by this build");
+/// }
+/// ```
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        avif: cfg!(feature = "avif"),
+        heic: cfg!(feature = "heic"),
+        http: cfg!(feature = "http"),
+        rustybuzz: cfg!(feature = "rustybuzz"),
+        lottie: cfg!(feature = "lottie"),
+        print: cfg!(feature = "print"),
+    }
+}
+
+/// Renders a poster from its JSON config, returning PNG data.
+///
+/// This is the single entry point needed by bindings that embed the renderer,
+/// such as a browser build exposing `render_config(json) -> Uint8Array`.
+///
+/// # Errors
+///
+/// Returns an error if the config is invalid or rendering fails.
+///
+/// Fields may be [bound](binding) to the config's `data`, texts may be
+/// [translated](locale), and numeric fields may hold [expressions](expr).
+///
+/// # Example
+///
+/// ```
+/// let png = poster_generator::render_config(r##"{
+///     "width": 400,
+///     "height": 300,
+///     "background_color": "#ffffff",
+///     "elements": []
+/// }"##).expect("Failed to render");
+/// ```
+pub fn render_config(json: &str) -> Result<Vec<u8>> {
+    let mut config: serde_json::Value = serde_json::from_str(json)?;
+    preprocess(&mut config, &template::Variables::new())?;
+    let config: PosterConfig = serde_json::from_value(config)?;
+    PosterGenerator::from_config(config).generate()
+}
+
+/// Resolves a JSON config in place before it is deserialized: fields [bound](binding)
+/// to the config's `data` are filled in, then texts are [translated](locale), then
+/// [expressions](expr) in numeric fields are evaluated with `vars`.
+///
+/// [`render_config`] and [`template::render_config`] call this; callers deserializing
+/// configs themselves should too.
+///
+/// # Errors
+///
+/// Returns an error if a binding, translation or expression is invalid.
+pub fn preprocess(config: &mut serde_json::Value, vars: &template::Variables) -> serde_json::Result<()> {
+    binding::apply(config)?;
+    locale::apply(config)?;
+    expr::apply(config, vars)
+}
+
 // Utility functions
-fn parse_color(color_str: &str) -> Color {
+fn encode_png(surface: &mut Surface) -> Result<Vec<u8>> {
+    encode_image(&surface.image_snapshot())
+}
+
+fn encode_image(image: &Image) -> Result<Vec<u8>> {
+    let _span = debug_span!("encode", format = "png").entered();
+    let data = image.encode_to_data(EncodedImageFormat::PNG).ok_or_else(|| {
+        PosterError::Encode("Failed to encode image as PNG".to_string())
+    })?;
+    Ok(data.as_bytes().to_vec())
+}
+
+// Largest supported supersampling factor; a 4x render already needs 16 times the memory
+const MAX_SUPERSAMPLE: u32 = 4;
+
+// Fewest rows drawn above and below each strip of a tiled render, so effects that
+// spread across the strip edges (blurs, shadows) come out the same as in one render
+const TILE_OVERLAP: u32 = 64;
+
+// How far an element's effects reach past its bounds or into pixels outside a strip:
+// blurs read about three sigmas around them, shadows are offset and blurred beyond
+// the box, highlight stripes are padded past the words they mark, and pixelated
+// blocks average all of their pixels
+pub(crate) fn effect_reach(element: &Element) -> f32 {
+    let shadow = match element {
+        Element::Text(text) => text.box_shadow.as_ref(),
+        Element::Badge(badge) => badge.box_shadow.as_ref(),
+        _ => None,
+    };
+    // The shadow blur radius is twice the Gaussian sigma
+    let shadow_reach = shadow.map_or(0.0, |shadow| {
+        shadow.offset_x.abs().max(shadow.offset_y.abs()) + shadow.spread.max(0.0) + shadow.blur * 1.5
+    });
+    let effect_reach = match element {
+        Element::Text(text) => text.highlight.as_ref().map_or(0.0, |highlight| highlight.padding),
+        Element::BackdropBlur(blur) => blur.blur * 3.0,
+        Element::Pixelate(pixelate) => pixelate.block_size,
+        Element::Image(image) => image.pixelate.unwrap_or_default(),
+        _ => 0.0,
+    };
+    shadow_reach.max(effect_reach).max(0.0)
+}
+
+fn png_error(err: png::EncodingError) -> PosterError {
+    PosterError::Encode(format!("Failed to encode PNG: {}", err))
+}
+
+fn parse_color(color_str: &str, field: &str) -> Result<Color> {
     if color_str.starts_with('#') {
         // Parse hex color
         let hex = &color_str[1..];
@@ -977,7 +4586,7 @@ fn parse_color(color_str: &str) -> Color {
                 u8::from_str_radix(&hex[2..4], 16),
                 u8::from_str_radix(&hex[4..6], 16),
             ) {
-                return Color::from_rgb(r, g, b);
+                return Ok(Color::from_rgb(r, g, b));
             }
         } else if hex.len() == 8 {
             if let (Ok(r), Ok(g), Ok(b), Ok(a)) = (
@@ -986,44 +4595,196 @@ fn parse_color(color_str: &str) -> Color {
                 u8::from_str_radix(&hex[4..6], 16),
                 u8::from_str_radix(&hex[6..8], 16),
             ) {
-                return Color::from_argb(a, r, g, b);
+                return Ok(Color::from_argb(a, r, g, b));
             }
         }
     }
     
-    // Default to black if parsing fails
-    Color::BLACK
+    Err(PosterError::InvalidColor {
+        value: color_str.to_string(),
+        field: field.to_string(),
+    })
 }
 
-fn load_image(path: &str) -> Result<Image> {
-    // Check if path is a base64 string
-    if path.starts_with("data:image/") {
-        let base64_data = path.split(',').nth(1).ok_or_else(|| {
-            PosterError::ImageLoadError("Invalid base64 image format".to_string())
-        })?;
-        
-        let bytes = general_purpose::STANDARD.decode(base64_data)?;
-        let data = Data::new_copy(&bytes);
-        
-        let image = Image::from_encoded(data).ok_or_else(|| {
-            PosterError::ImageLoadError("Failed to decode base64 image".to_string())
-        })?;
-        
+fn load_image(path: &str, ctx: &RenderContext) -> Result<Image> {
+    load_image_with_options(path, &DecodeOptions::default(), ctx)
+}
+
+/// Prefix of image sources that refer to assets registered with [`PosterGenerator::register_asset`].
+pub const ASSET_SCHEME: &str = "asset://";
+
+/// Prefix of image sources that are a [BlurHash](crate::blurhash) to decode, e.g.
+/// `blurhash:LEHV6nWB2yk8pyo0adR*.7kCMdnj`.
+pub const BLURHASH_SCHEME: &str = "blurhash:";
+
+/// `background_color` value that picks the dominant color of the poster's largest image.
+pub const AUTO_COLOR: &str = "auto";
+
+// How an image source is decoded
+struct DecodeOptions {
+    // Turn photos upright according to their EXIF orientation
+    respect_orientation: bool,
+    // Frame of an animated image to decode
+    frame: Option<FrameSelector>,
+    // Size the image is drawn at, for sources that have none of their own
+    size: Option<(f32, f32)>,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            respect_orientation: true,
+            frame: None,
+            size: None,
+        }
+    }
+}
+
+// Frame of an animated image, by index, by playback time or by position in the animation
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum FrameSelector {
+    Index(usize),
+    TimeMs(u32),
+    // In steps of 1 / PROGRESS_SCALE, so selectors stay usable as cache keys
+    Progress(u32),
+}
+
+impl FrameSelector {
+    const PROGRESS_SCALE: f32 = 10000.0;
+
+    fn progress(progress: f32) -> Self {
+        Self::Progress((progress.clamp(0.0, 1.0) * Self::PROGRESS_SCALE).round() as u32)
+    }
+}
+
+// Decoded images shared by several renders of a poster, by source and decode options
+type ImageCache = Rc<RefCell<HashMap<(String, bool, Option<FrameSelector>), Image>>>;
+
+fn load_image_with_options(path: &str, options: &DecodeOptions, ctx: &RenderContext) -> Result<Image> {
+    // Placeholders are cheap to decode, and square unless drawn at a known size
+    if let Some(hash) = path.strip_prefix(BLURHASH_SCHEME) {
+        let (width, height) = options.size.unwrap_or((1.0, 1.0));
+        return blurhash::image(hash, width, height);
+    }
+
+    let key = (path.to_string(), options.respect_orientation, options.frame);
+    if let Some(image) = ctx.images.as_ref().and_then(|images| images.borrow().get(&key).cloned()) {
         return Ok(image);
     }
-    
-    // Otherwise load from file
-    let bytes = std::fs::read(path)?;
+
+    let _timer = ctx.timer(Phase::Decode);
+    let source = assets::describe_source(path);
+    let bytes = ctx.load_asset(path).map_err(|e| {
+        PosterError::ImageLoadError(format!("Failed to read {}: {}", source, e))
+    })?;
     let data = Data::new_copy(&bytes);
-    
-    let image = Image::from_encoded(data).ok_or_else(|| {
-        PosterError::ImageLoadError(format!("Failed to load image from: {}", path))
+
+    let image = decode_image(data, options).ok_or_else(|| {
+        PosterError::ImageLoadError(format!("Failed to load image from: {}", source))
     })?;
-    
+
+    if let Some(images) = &ctx.images {
+        images.borrow_mut().insert(key, image.clone());
+    }
     Ok(image)
 }
 
-fn scale_image(img: Image, width: f32, height: f32, object_fit: &ObjectFit) -> Result<Image> {
+// Decode an encoded image. Phone photos are often stored sideways with an EXIF
+// orientation tag, which is applied here unless `respect_orientation` is false.
+fn decode_image(data: Data, options: &DecodeOptions) -> Option<Image> {
+    let Some(mut codec) = Codec::from_data(data.clone()) else {
+        return Image::from_encoded(data.clone()).or_else(|| decoders::decode(data.as_bytes(), options.frame));
+    };
+
+    let frame_index = match options.frame {
+        Some(frame) => select_frame(&mut codec, frame),
+        None => 0,
+    };
+    // Earlier frames this one builds on are decoded by the codec itself
+    let codec_options = codec::Options {
+        zero_initialized: codec::ZeroInitialized::No,
+        subset: None,
+        frame_index,
+        prior_frame: None,
+    };
+
+    if options.respect_orientation {
+        // The codec rotates and flips the pixels into the oriented size
+        let mut info = codec.info();
+        if codec.origin().swaps_width_height() {
+            info = codec::pixmap_utils::swap_width_height(&info);
+        }
+        return codec.get_image(info, &codec_options).ok();
+    }
+
+    // Decode the pixels as stored, ignoring the orientation
+    let info = codec.info();
+    let row_bytes = info.min_row_bytes();
+    let mut pixels = vec![0u8; info.compute_byte_size(row_bytes)];
+    if codec.get_pixels_with_options(&info, &mut pixels, row_bytes, Some(&codec_options)) != codec::Result::Success {
+        return None;
+    }
+    images::raster_from_data(&info, Data::new_copy(&pixels), row_bytes)
+}
+
+// Index of the frame to decode from an animated image. Frames past the end use the
+// last frame, and playback times loop over the animation.
+fn select_frame(codec: &mut Codec, frame: FrameSelector) -> usize {
+    let count = codec.get_frame_count();
+    if count <= 1 {
+        return 0;
+    }
+
+    match frame {
+        FrameSelector::Index(index) => index.min(count - 1),
+        FrameSelector::TimeMs(time_ms) => frame_at_time(codec, count, |total| u64::from(time_ms) % total),
+        FrameSelector::Progress(progress) => frame_at_time(codec, count, |total| {
+            (total * u64::from(progress) / FrameSelector::PROGRESS_SCALE as u64).min(total - 1)
+        }),
+    }
+}
+
+// Index of the frame showing at the playback time `time` picks from the total duration
+fn frame_at_time(codec: &mut Codec, count: usize, time: impl FnOnce(u64) -> u64) -> usize {
+    let durations: Vec<u64> = (0..count)
+        .map(|i| codec.get_frame_info(i).map_or(0, |info| info.duration.max(0) as u64))
+        .collect();
+    let total: u64 = durations.iter().sum();
+    if total == 0 {
+        return 0;
+    }
+
+    let mut time = time(total);
+    for (index, duration) in durations.iter().enumerate() {
+        if time < *duration {
+            return index;
+        }
+        time -= duration;
+    }
+    count - 1
+}
+
+// Cut a region out of an image, limited to the image bounds
+fn crop_image(img: &Image, crop: &CropRect) -> Result<Image> {
+    let region = IRect::from_xywh(
+        crop.x.round() as i32,
+        crop.y.round() as i32,
+        crop.width.round() as i32,
+        crop.height.round() as i32,
+    );
+    let Some(region) = IRect::intersect(&img.bounds(), &region) else {
+        return Err(PosterError::RenderError(format!(
+            "Crop {:?} is outside the {}x{} image",
+            crop,
+            img.width(),
+            img.height()
+        )));
+    };
+    img.make_subset(None, region)
+        .ok_or_else(|| PosterError::RenderError("Failed to crop image".to_string()))
+}
+
+fn scale_image(img: Image, width: f32, height: f32, object_fit: &ObjectFit, insets: Option<&Insets>) -> Result<Image> {
     let src_width = img.width() as f32;
     let src_height = img.height() as f32;
     
@@ -1101,6 +4862,38 @@ fn scale_image(img: Image, width: f32, height: f32, object_fit: &ObjectFit) -> R
             paint.set_anti_alias(true);
             canvas.draw_image_rect(img, Some((&src_rect, skia_safe::canvas::SrcRectConstraint::Fast)), dest_rect, &paint);
             
+            surface
+        },
+        ObjectFit::NinePatch => {
+            // Create a surface for the nine-patch image
+            let mut surface = skia_safe::surfaces::raster_n32_premul((width as i32, height as i32)).ok_or_else(|| {
+                PosterError::RenderError("Failed to create surface for nine-patch image".to_string())
+            })?;
+
+            let canvas = surface.canvas();
+
+            // The center rect is the part of the source that stretches in both directions
+            let [top, right, bottom, left] = match insets {
+                Some(Insets::Single(inset)) => [*inset; 4],
+                Some(Insets::Multiple(sides)) => *sides,
+                None => {
+                    let (x, y) = (src_width / 3.0, src_height / 3.0);
+                    [y, x, y, x]
+                }
+            };
+            let center_left = left.clamp(0.0, src_width);
+            let center_top = top.clamp(0.0, src_height);
+            let center = IRect::new(
+                center_left as i32,
+                center_top as i32,
+                (src_width - right).clamp(center_left, src_width) as i32,
+                (src_height - bottom).clamp(center_top, src_height) as i32,
+            );
+
+            let mut paint = Paint::default();
+            paint.set_anti_alias(true);
+            canvas.draw_image_nine(img, center, Rect::new(0.0, 0.0, width, height), FilterMode::Linear, Some(&paint));
+
             surface
         }
     };
@@ -1151,10 +4944,318 @@ fn create_rounded_rect_path(x: f32, y: f32, width: f32, height: f32, radius: &Ra
     path
 }
 
+// Build a rounded rectangle whose corners are smoothed into the sides by `smoothing`
+// (0 to 1), as continuous-curvature corners in design tools. Each corner starts
+// further along the side, at up to twice the radius, and eases into a shorter
+// circular arc through cubic curves.
+fn create_smooth_rect_path(x: f32, y: f32, width: f32, height: f32, radius: &Radius, smoothing: f32) -> SkPath {
+    let smoothing = smoothing.clamp(0.0, 1.0);
+    if smoothing <= 0.0 {
+        return create_rounded_rect_path(x, y, width, height, radius);
+    }
+    let [tl, tr, br, bl] = match radius {
+        Radius::Single(r) => [*r; 4],
+        Radius::Multiple(corners) => *corners,
+    };
+    let max_length = width.min(height) / 2.0;
+
+    // Corners clockwise from the top-right: the corner point, the direction of the side
+    // leading into it and the direction of the side leading away from it
+    let corners = [
+        (tr, Point::new(x + width, y), Vector::new(1.0, 0.0), Vector::new(0.0, 1.0)),
+        (br, Point::new(x + width, y + height), Vector::new(0.0, 1.0), Vector::new(-1.0, 0.0)),
+        (bl, Point::new(x, y + height), Vector::new(-1.0, 0.0), Vector::new(0.0, -1.0)),
+        (tl, Point::new(x, y), Vector::new(0.0, -1.0), Vector::new(1.0, 0.0)),
+    ];
+
+    let mut path = SkPath::new();
+    for (index, (r, corner, incoming, outgoing)) in corners.into_iter().enumerate() {
+        // A point `back` from the corner along the incoming side and `along` the outgoing one
+        let at = |back: f32, along: f32| corner - incoming * back + outgoing * along;
+        let r = r.clamp(0.0, max_length);
+        if r <= 0.0 {
+            if index == 0 {
+                path.move_to(corner);
+            } else {
+                path.line_to(corner);
+            }
+            continue;
+        }
+
+        // Length of side the corner takes; less smoothing fits when the box is small
+        let length = ((1.0 + smoothing) * r).min(max_length);
+        let smoothing = (length / r - 1.0).clamp(0.0, smoothing);
+
+        let arc_degrees = 90.0 * (1.0 - smoothing);
+        let arc_chord = (arc_degrees / 2.0).to_radians().sin() * r * std::f32::consts::SQRT_2;
+        let curve_degrees = (90.0 - arc_degrees) / 2.0;
+        let arc_offset = r * (curve_degrees / 2.0).to_radians().tan();
+        let beta = (45.0 * smoothing).to_radians();
+        let c = arc_offset * beta.cos();
+        let d = c * beta.tan();
+        let b = (length - arc_chord - c - d) / 3.0;
+        let a = 2.0 * b;
+
+        if index == 0 {
+            path.move_to(at(length, 0.0));
+        } else {
+            path.line_to(at(length, 0.0));
+        }
+        path.cubic_to(at(length - a, 0.0), at(length - a - b, 0.0), at(length - a - b - c, d));
+        path.arc_to_rotated((r, r), 0.0, ArcSize::Small, PathDirection::CW, at(d, length - a - b - c));
+        path.cubic_to(at(0.0, length - a - b), at(0.0, length - a), at(0.0, length));
+    }
+    path.close();
+    path
+}
+
+// Build a clip path for an element box from a preset shape name or SVG path data
+fn create_clip_path(spec: &str, x: f32, y: f32, width: f32, height: f32) -> Result<SkPath> {
+    // Polygon presets as fractions of the box
+    let polygon: &[(f32, f32)] = match spec.trim() {
+        "hexagon" => &[(0.5, 0.0), (1.0, 0.25), (1.0, 0.75), (0.5, 1.0), (0.0, 0.75), (0.0, 0.25)],
+        "diamond" => &[(0.5, 0.0), (1.0, 0.5), (0.5, 1.0), (0.0, 0.5)],
+        "triangle" => &[(0.5, 0.0), (1.0, 1.0), (0.0, 1.0)],
+        _ => &[],
+    };
+    let to_box = |(px, py): (f32, f32)| Point::new(x + px * width, y + py * height);
+
+    let mut path = SkPath::new();
+    if !polygon.is_empty() {
+        path.add_poly(&polygon.iter().copied().map(to_box).collect::<Vec<_>>(), true);
+        return Ok(path);
+    }
+
+    match spec.trim() {
+        "circle" => {
+            path.add_oval(Rect::from_xywh(x, y, width, height), None);
+        }
+        "star" => {
+            // Five-pointed star, inner radius at 40% of the outer one
+            let points: Vec<Point> = (0..10)
+                .map(|i| {
+                    let r = if i % 2 == 0 { 0.5 } else { 0.2 };
+                    let angle = (-90.0 + 36.0 * i as f32).to_radians();
+                    to_box((0.5 + r * angle.cos(), 0.5 + r * angle.sin()))
+                })
+                .collect();
+            path.add_poly(&points, true);
+        }
+        "blob" => {
+            // Closed smooth curve through points at uneven distances from the center
+            let radii = [0.5, 0.42, 0.48, 0.4, 0.5, 0.44];
+            let points: Vec<Point> = radii
+                .iter()
+                .enumerate()
+                .map(|(i, r)| {
+                    let angle = (-90.0 + 60.0 * i as f32).to_radians();
+                    to_box((0.5 + r * angle.cos(), 0.5 + r * angle.sin()))
+                })
+                .collect();
+            let n = points.len();
+            path.move_to(points[0]);
+            for i in 0..n {
+                let (before, previous) = (points[(i + n - 1) % n], points[i]);
+                let (point, next) = (points[(i + 1) % n], points[(i + 2) % n]);
+                let c1 = previous + (point - before) * (1.0 / 6.0);
+                let c2 = point - (next - previous) * (1.0 / 6.0);
+                path.cubic_to(c1, c2, point);
+            }
+            path.close();
+        }
+        svg => {
+            path = SkPath::from_svg(svg)
+                .ok_or_else(|| PosterError::RenderError(format!("Invalid clip_path '{}'", svg)))?;
+            path.offset((x, y));
+        }
+    }
+    Ok(path)
+}
+
+// Draw a box shadow for the box at (x, y, width, height) with optional rounded corners
+fn draw_box_shadow(
+    canvas: &Canvas,
+    shadow: &BoxShadow,
+    (x, y, width, height): (f32, f32, f32, f32),
+    radius: Option<&Radius>,
+    corner_smoothing: f32,
+) -> Result<()> {
+    let mut paint = Paint::default();
+    paint.set_color(parse_color(&shadow.color, "box_shadow.color")?);
+    paint.set_anti_alias(true);
+    if shadow.blur > 0.0 {
+        // Like CSS, the blur radius is twice the Gaussian sigma
+        paint.set_mask_filter(MaskFilter::blur(BlurStyle::Normal, shadow.blur / 2.0, false));
+    }
+
+    let x = x + shadow.offset_x - shadow.spread;
+    let y = y + shadow.offset_y - shadow.spread;
+    let width = (width + shadow.spread * 2.0).max(0.0);
+    let height = (height + shadow.spread * 2.0).max(0.0);
+
+    // Grow the corner radii with the spread so the shadow follows the box outline
+    let radius = radius.map(|radius| match radius {
+        Radius::Single(r) => Radius::Single((r + shadow.spread).max(0.0)),
+        Radius::Multiple(corners) => Radius::Multiple(corners.map(|r| (r + shadow.spread).max(0.0))),
+    });
+    match &radius {
+        Some(radius) => {
+            canvas.draw_path(&create_smooth_rect_path(x, y, width, height, radius, corner_smoothing), &paint);
+        }
+        None => {
+            canvas.draw_rect(Rect::from_xywh(x, y, width, height), &paint);
+        }
+    }
+    Ok(())
+}
+
+// Split text into runs drawn with one font each. Characters the font has no glyph
+// for get a fallback typeface from the system, so mixed-script text doesn't show
+// missing glyphs as boxes. A new run starts where the script changes; characters
+// common to all scripts (spaces, digits, punctuation) stay in the run they are in.
+fn font_runs<'a>(text: &'a str, font: &Font) -> Vec<(&'a str, Font)> {
+    struct Run {
+        start: usize,
+        script: Option<Script>,
+        // `None` for the element's own font
+        fallback: Option<Typeface>,
+    }
+
+    let primary = font.typeface();
+    let has_glyph = |typeface: &Typeface, c: char| c.is_whitespace() || c.is_control() || typeface.unichar_to_glyph(c as i32) != 0;
+    let font_mgr = FontMgr::default();
+    let family = primary.family_name();
+
+    let mut runs: Vec<Run> = Vec::new();
+    for (i, c) in text.char_indices() {
+        let script = Script::of(c);
+        if let Some(run) = runs.last_mut() {
+            let same_script = script.is_none() || run.script.is_none_or(|run_script| Some(run_script) == script);
+            if same_script && has_glyph(run.fallback.as_ref().unwrap_or(&primary), c) {
+                run.script = run.script.or(script);
+                continue;
+            }
+        }
+
+        let fallback = if has_glyph(&primary, c) {
+            None
+        } else {
+            font_mgr.match_family_style_character(&family, primary.font_style(), &[], c as i32)
+        };
+        if let Some(run) = runs.last_mut() {
+            let same_font = match (&run.fallback, &fallback) {
+                (None, None) => true,
+                (Some(a), Some(b)) => a.unique_id() == b.unique_id(),
+                _ => false,
+            };
+            if same_font {
+                run.script = script.or(run.script);
+                continue;
+            }
+        }
+        runs.push(Run { start: i, script, fallback });
+    }
+
+    let ends = runs.iter().skip(1).map(|run| run.start).chain([text.len()]);
+    runs.iter()
+        .zip(ends)
+        .map(|(run, end)| {
+            let mut run_font = font.clone();
+            if let Some(fallback) = &run.fallback {
+                run_font.set_typeface(fallback.clone());
+            }
+            (&text[run.start..end], run_font)
+        })
+        .collect()
+}
+
+// Text blob of a line, with fallback fonts for characters the font doesn't have
+fn text_blob(text: &str, font: &Font) -> Option<TextBlob> {
+    let runs = font_runs(text, font);
+    if runs.len() <= 1 {
+        return TextBlob::new(text, font);
+    }
+
+    let mut builder = TextBlobBuilder::new();
+    let mut x = 0.0;
+    for (run_text, run_font) in &runs {
+        let glyphs = run_font.str_to_glyphs_vec(run_text);
+        builder.alloc_run(run_font, glyphs.len(), Point::new(x, 0.0), None).copy_from_slice(&glyphs);
+        x += run_font.measure_str(run_text, None).0;
+    }
+    builder.make()
+}
+
+// The poster on a print sheet
+struct Sheet {
+    width: i32,
+    height: i32,
+    // Edges of the poster without and with its bleed
+    trim: Rect,
+    bleed: Rect,
+    marks: Option<CropMarks>,
+}
+
+// Trim marks drawn at the corners of a poster, in line with its edges
+struct CropMarks {
+    // Distance of the marks from the trim edges
+    offset: f32,
+    length: f32,
+    width: f32,
+}
+
+fn draw_crop_marks(canvas: &Canvas, trim: Rect, marks: &CropMarks) {
+    let mut paint = Paint::default();
+    paint.set_color(Color::BLACK);
+    paint.set_anti_alias(true);
+    paint.set_style(PaintStyle::Stroke);
+    paint.set_stroke_width(marks.width);
+
+    // Each corner, with the directions pointing away from the poster
+    let corners = [
+        (trim.left, trim.top, -1.0, -1.0),
+        (trim.right, trim.top, 1.0, -1.0),
+        (trim.right, trim.bottom, 1.0, 1.0),
+        (trim.left, trim.bottom, -1.0, 1.0),
+    ];
+    let (start, end) = (marks.offset, marks.offset + marks.length);
+    for (x, y, dx, dy) in corners {
+        canvas.draw_line((x + dx * start, y), (x + dx * end, y), &paint);
+        canvas.draw_line((x, y + dy * start), (x, y + dy * end), &paint);
+    }
+}
+
+// Shade the margins outside the safe area and outline it with a dashed line
+fn draw_safe_area_guides(canvas: &Canvas, ctx: &RenderContext) {
+    let area = ctx.safe_area();
+
+    let mut shade = Paint::default();
+    shade.set_color(Color::from_argb(0x33, 0xff, 0x00, 0x00));
+    let mut margins = SkPath::new();
+    margins.add_rect(ctx.canvas_rect(), None);
+    margins.add_rect(area, None);
+    margins.set_fill_type(skia_safe::PathFillType::EvenOdd);
+    canvas.draw_path(&margins, &shade);
+
+    let mut outline = Paint::default();
+    outline.set_color(Color::from_argb(0xcc, 0xff, 0x00, 0x00));
+    outline.set_style(PaintStyle::Stroke);
+    outline.set_stroke_width(2.0);
+    outline.set_anti_alias(true);
+    outline.set_path_effect(skia_safe::PathEffect::dash(&[12.0, 8.0], 0.0));
+    canvas.draw_rect(area, &outline);
+}
+
+// Smallest font size `overflow: shrink` goes down to
+const MIN_SHRINK_FONT_SIZE: f32 = 6.0;
+
+// Width single lines of RTL text are laid out in, wide enough for any line
+const PARAGRAPH_LAYOUT_WIDTH: f32 = 1000.0;
+
 // Improved text measurement with better font support
 fn measure_text_with_font(text: &str, font: &Font) -> (f32, f32) {
     // Use Skia's text measurement
-    let blob = TextBlob::new(text, font).unwrap_or_else(|| {
+    let blob = text_blob(text, font).unwrap_or_else(|| {
         TextBlob::new(" ", font).unwrap() // Fallback to a space if there's an issue
     });
     
@@ -1163,7 +5264,7 @@ fn measure_text_with_font(text: &str, font: &Font) -> (f32, f32) {
 }
 
 // RTL-aware text breaking
-fn break_text_rtl(text: &str, max_width: f32, font: &Font, max_lines: Option<u32>) -> Vec<String> {
+fn break_text_rtl(text: &str, max_width: f32, font: &Font) -> Vec<String> {
     let mut lines = Vec::new();
     let mut current_line = String::new();
     
@@ -1184,56 +5285,27 @@ fn break_text_rtl(text: &str, max_width: f32, font: &Font, max_lines: Option<u32
         } else {
             lines.push(current_line);
             current_line = word.to_string();
-            
-            if let Some(max) = max_lines {
-                if lines.len() >= max as usize - 1 {
-                    break;
-                }
-            }
         }
     }
     
     if !current_line.is_empty() {
-        if let Some(max) = max_lines {
-            if lines.len() >= max as usize {
-                // Truncate last line with ellipsis
-                let last_line = lines.last_mut().unwrap();
-                *last_line = truncate_with_ellipsis_rtl(last_line, max_width, font);
-            } else {
-                lines.push(current_line);
-            }
-        } else {
-            lines.push(current_line);
-        }
+        lines.push(current_line);
     }
     
     lines
 }
 
-fn truncate_with_ellipsis_rtl(text: &str, max_width: f32, font: &Font) -> String {
-    let ellipsis = if is_rtl_text(text) { "..." } else { "..." }; // Could use RTL ellipsis: "…"
-    let (ellipsis_width, _) = measure_text_with_font(ellipsis, font);
-    
-    let (text_width, _) = measure_text_with_font(text, font);
-    if text_width <= max_width {
-        return text.to_string();
-    }
-    
-    let available_width = max_width - ellipsis_width;
-    let mut result = String::new();
-    
-    for ch in text.chars() {
-        let test_text = format!("{}{}", result, ch);
-        let (test_width, _) = measure_text_with_font(&test_text, font);
-        
-        if test_width <= available_width {
-            result.push(ch);
-        } else {
-            break;
+// End a truncated line with the suffix, dropping characters until both fit in max_width
+fn truncate_with_suffix(text: &str, max_width: f32, font: &Font, suffix: &str) -> String {
+    let mut kept = text.trim_end().to_string();
+    loop {
+        let line = format!("{}{}", kept, suffix);
+        if kept.is_empty() || measure_text_with_font(&line, font).0 <= max_width {
+            return line;
         }
+        kept.pop();
+        kept.truncate(kept.trim_end().len());
     }
-    
-    format!("{}{}", result, ellipsis)
 }
 
 // Improved text drawing with RTL support
@@ -1249,58 +5321,10 @@ fn draw_text_line_improved(
 ) {
     // For RTL text (Arabic/Hebrew/Uyghur), use Skia's textlayout for proper shaping and direction
     if matches!(direction, TextDirectionType::Rtl) && is_rtl_text(text) {
-        // Create paragraph style with RTL direction
-        let mut paragraph_style = ParagraphStyle::new();
-        paragraph_style.set_text_direction(TextDirection::RTL);
-
-        // Set text alignment
-        let text_align = match align {
-            TextAlignType::Left => TextAlign::Left,
-            TextAlignType::Right => TextAlign::Right,
-            TextAlignType::Center => TextAlign::Center,
-        };
-        paragraph_style.set_text_align(text_align);
-
-        // Use system font manager for font collection
-        let font_mgr = FontMgr::default();
-        let mut font_collection = FontCollection::new();
-        font_collection.set_default_font_manager(font_mgr, None);
-
-        let mut paragraph_builder = ParagraphBuilder::new(&paragraph_style, font_collection);
-
-        // Create text style using the font that was already selected by get_font_for_text_with_family
-        let mut text_style = TextStyle::new();
-        text_style.set_font_size(font.size());
-        text_style.set_color(paint.color());
-
-        // Extract font family name from the font
-        let family_name = font.typeface().family_name();
-        text_style.set_font_families(&[family_name.as_str()]);
-
-        // Add styled text
-        paragraph_builder.push_style(&text_style);
-        paragraph_builder.add_text(text);
-
-        // Build and layout paragraph
-        let mut paragraph = paragraph_builder.build();
-        paragraph.layout(1000.0); // Wide layout for proper text measurement
-
-        // Adjust Y position for baseline
-        let draw_y = y - font.size();
-
-        // For center alignment, adjust X position
-        let draw_x = if matches!(align, TextAlignType::Center) {
-            x - paragraph.max_width() / 2.0
-        } else {
-            x
-        };
-
-        // Draw the paragraph
-        paragraph.paint(canvas, Point::new(draw_x, draw_y));
-
+        draw_rtl_paragraph(canvas, text, x, y, font, paint, align, &[]);
     } else {
         // For LTR text, use standard TextBlob approach
-        if let Some(blob) = TextBlob::new(text, font) {
+        if let Some(blob) = text_blob(text, font) {
             let (text_width, _) = measure_text_with_font(text, font);
             
             let draw_x = match align {
@@ -1312,4 +5336,203 @@ fn draw_text_line_improved(
             canvas.draw_text_blob(blob, Point::new(draw_x, y), paint);
         }
     }
+}
+
+// Left edge and width of a line as draw_text_line_improved places it
+fn line_extent(text: &str, x: f32, font: &Font, direction: &TextDirectionType, align: &TextAlignType) -> (f32, f32) {
+    let (width, _) = measure_text_with_font(text, font);
+    let left = if matches!(direction, TextDirectionType::Rtl) && is_rtl_text(text) {
+        // Aligned inside the paragraph, which starts at x (or is centered on it)
+        match align {
+            TextAlignType::Left => x,
+            TextAlignType::Right => x + PARAGRAPH_LAYOUT_WIDTH - width,
+            TextAlignType::Center => x - width / 2.0,
+        }
+    } else {
+        match align {
+            TextAlignType::Left => x,
+            TextAlignType::Right => x - width,
+            TextAlignType::Center => x - width / 2.0,
+        }
+    };
+    (left, width)
+}
+
+// Paint the marker stripe behind the highlighted words of one line
+fn draw_highlight(
+    canvas: &Canvas,
+    highlight: &Highlight,
+    line: &str,
+    x: f32,
+    y: f32,
+    font: &Font,
+    direction: &TextDirectionType,
+    align: &TextAlignType,
+) -> Result<()> {
+    let mut ranges: Vec<(usize, usize)> = if highlight.words.is_empty() {
+        vec![(0, line.len())]
+    } else {
+        highlight
+            .words
+            .iter()
+            .filter(|word| !word.is_empty())
+            .flat_map(|word| line.match_indices(word.as_str()).map(|(start, word)| (start, start + word.len())))
+            .collect()
+    };
+    if ranges.is_empty() {
+        return Ok(());
+    }
+    ranges.sort_unstable();
+
+    let mut paint = Paint::default();
+    paint.set_color(parse_color(&highlight.color, "highlight.color")?);
+    paint.set_anti_alias(true);
+
+    let (left, width) = line_extent(line, x, font, direction, align);
+    let rtl = matches!(direction, TextDirectionType::Rtl) && is_rtl_text(line);
+    let bottom = y + highlight.offset * font.size();
+    let top = bottom - highlight.height * font.size();
+    for (start, end) in ranges {
+        let before = font.measure_str(&line[..start], None).0;
+        let word = font.measure_str(&line[start..end], None).0;
+        // RTL lines run from the right edge
+        let word_left = if rtl { left + width - before - word } else { left + before };
+        let rect = Rect::new(word_left - highlight.padding, top, word_left + word + highlight.padding, bottom);
+        canvas.draw_round_rect(rect, highlight.radius, highlight.radius, &paint);
+    }
+    Ok(())
+}
+
+// Draw a line with OpenType features, which only the paragraph layout applies
+fn draw_text_line_with_features(
+    canvas: &Canvas,
+    text: &str,
+    x: f32,
+    y: f32,
+    font: &Font,
+    paint: &Paint,
+    direction: &TextDirectionType,
+    align: &TextAlignType,
+    features: &[String],
+) {
+    if features.is_empty() {
+        draw_text_line_improved(canvas, text, x, y, font, paint, direction, align);
+    } else if matches!(direction, TextDirectionType::Rtl) && is_rtl_text(text) {
+        draw_rtl_paragraph(canvas, text, x, y, font, paint, align, features);
+    } else {
+        let paragraph = build_paragraph(text, font, paint, TextDirection::LTR, TextAlign::Left, features);
+        let width = paragraph.longest_line();
+        let draw_x = match align {
+            TextAlignType::Left => x,
+            TextAlignType::Right => x - width,
+            TextAlignType::Center => x - width / 2.0,
+        };
+        paragraph.paint(canvas, Point::new(draw_x, y - paragraph.alphabetic_baseline()));
+    }
+}
+
+fn draw_rtl_paragraph(
+    canvas: &Canvas,
+    text: &str,
+    x: f32,
+    y: f32,
+    font: &Font,
+    paint: &Paint,
+    align: &TextAlignType,
+    features: &[String],
+) {
+    // Set text alignment
+    let text_align = match align {
+        TextAlignType::Left => TextAlign::Left,
+        TextAlignType::Right => TextAlign::Right,
+        TextAlignType::Center => TextAlign::Center,
+    };
+    let paragraph = build_paragraph(text, font, paint, TextDirection::RTL, text_align, features);
+
+    // Adjust Y position for baseline
+    let draw_y = y - font.size();
+
+    // For center alignment, adjust X position
+    let draw_x = if matches!(align, TextAlignType::Center) {
+        x - paragraph.max_width() / 2.0
+    } else {
+        x
+    };
+
+    // Draw the paragraph
+    paragraph.paint(canvas, Point::new(draw_x, draw_y));
+}
+
+// Lay out a single line as a paragraph, using the font that was already selected
+// by get_font_for_text_with_family
+fn build_paragraph(
+    text: &str,
+    font: &Font,
+    paint: &Paint,
+    text_direction: TextDirection,
+    text_align: TextAlign,
+    features: &[String],
+) -> Paragraph {
+    let mut paragraph_style = ParagraphStyle::new();
+    paragraph_style.set_text_direction(text_direction);
+    paragraph_style.set_text_align(text_align);
+    // Paragraphs pick their own edging, but hinting can at least be turned off
+    if font.hinting() == FontHinting::None {
+        paragraph_style.turn_hinting_off();
+    }
+
+    // Register the fonts of each run, so fonts loaded from files are found by family
+    // name; anything else falls back to the system font manager
+    let mut provider = TypefaceFontProvider::new();
+    let mut families: Vec<String> = Vec::new();
+    let run_typefaces = font_runs(text, font).into_iter().map(|(_, run_font)| run_font.typeface());
+    for typeface in std::iter::once(font.typeface()).chain(run_typefaces) {
+        let family_name = typeface.family_name();
+        if !families.contains(&family_name) {
+            provider.register_typeface(typeface, None);
+            families.push(family_name);
+        }
+    }
+    let mut font_collection = FontCollection::new();
+    font_collection.set_asset_font_manager(Some(FontMgr::from(provider)));
+    font_collection.set_default_font_manager(FontMgr::default(), None);
+
+    let mut paragraph_builder = ParagraphBuilder::new(&paragraph_style, font_collection);
+
+    let mut text_style = TextStyle::new();
+    text_style.set_font_size(font.size());
+    text_style.set_color(paint.color());
+
+    // The element's font first, then the fallbacks of runs it doesn't cover
+    text_style.set_font_families(&families);
+
+    for feature in features {
+        match parse_font_feature(feature) {
+            Some((tag, value)) => text_style.add_font_feature(tag, value),
+            None => warn!("Ignoring invalid font feature '{}'", feature),
+        }
+    }
+
+    // Add styled text
+    paragraph_builder.push_style(&text_style);
+    paragraph_builder.add_text(text);
+
+    // Build and layout paragraph
+    let mut paragraph = paragraph_builder.build();
+    paragraph.layout(PARAGRAPH_LAYOUT_WIDTH); // Wide layout for proper text measurement
+    paragraph
+}
+
+// Parse an OpenType feature setting: "tnum" turns a feature on, "-liga" turns it
+// off, and "salt=2" picks an alternate
+fn parse_font_feature(feature: &str) -> Option<(&str, i32)> {
+    let (tag, value) = match feature.split_once('=') {
+        Some((tag, value)) => (tag, value.trim().parse().ok()?),
+        None => match feature.strip_prefix('-') {
+            Some(tag) => (tag, 0),
+            None => (feature.strip_prefix('+').unwrap_or(feature), 1),
+        },
+    };
+    let tag = tag.trim();
+    (tag.len() == 4 && tag.is_ascii()).then_some((tag, value))
 } 
\ No newline at end of file
@@ -0,0 +1,131 @@
+//! Translated text, so one template renders every language variant.
+//!
+//! The `text` of an element can be a map from locale to string. The config's
+//! `locale` picks the translation, either as one locale or as a list tried in
+//! order:
+//!
+//! ```json
+//! "locale": ["ug", "zh"],
+//! "elements": [
+//!     { "type": "text", "text": { "zh": "欢迎", "ug": "خۇش كەلدىڭىز", "en": "Welcome" }, "x": 400, "y": 300, "align": "center" }
+//! ]
+//! ```
+//!
+//! Each locale falls back to its more general forms, so `zh-Hant-TW` tries
+//! `zh-Hant-TW`, `zh-Hant` and then `zh`. Locales match regardless of case and of
+//! `-` or `_` separators. When no locale matches, the map's `default` entry is
+//! used, if it has one.
+//!
+//! Translations are picked on the JSON config before it is deserialized.
+//! [`template::render_config`](crate::template::render_config), [`render_config`](crate::render_config)
+//! and the CLI do this through [`preprocess`](crate::preprocess); with templates, `"locale": "{{lang}}"` renders each row in
+//! its own language. [`localize`] takes the locales separately:
+//!
+//! ```
+//! use poster_generator::locale;
+//! use serde_json::json;
+//!
+//! let mut element = json!({ "type": "text", "text": { "en": "Welcome", "zh": "欢迎" }, "x": 40, "y": 80 });
+//! locale::localize(&mut element, &["zh-CN"]).unwrap();
+//! assert_eq!(element["text"], "欢迎");
+//! ```
+
+use serde_json::{Map, Value};
+
+/// Picks the translations of a JSON config for the config's `locale`, which is
+/// removed from the config.
+///
+/// # Errors
+///
+/// Returns an error if `locale` is neither a string nor a list of strings, or if
+/// a text has no translation for the locales and no `default`.
+pub fn apply(config: &mut Value) -> serde_json::Result<()> {
+    let locales = match config.as_object_mut().and_then(|config| config.remove("locale")) {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::String(locale)) => vec![locale],
+        Some(locales) => serde_json::from_value(locales)?,
+    };
+    localize(config, &locales)
+}
+
+/// Replaces every `text` map within a JSON value by its translation for the first
+/// of `locales` it has, in place.
+///
+/// # Errors
+///
+/// Returns an error if a text has no translation for the locales and no `default`.
+pub fn localize<S: AsRef<str>>(value: &mut Value, locales: &[S]) -> serde_json::Result<()> {
+    let chain = fallback_chain(locales);
+    localize_fields(value, &chain)
+}
+
+/// The locales tried for a list of preferred locales: each one followed by its more
+/// general forms, without repeats.
+///
+/// ```
+/// use poster_generator::locale::fallback_chain;
+///
+/// assert_eq!(fallback_chain(&["zh-Hant-TW", "en-US"]), ["zh-Hant-TW", "zh-Hant", "zh", "en-US", "en"]);
+/// ```
+pub fn fallback_chain<S: AsRef<str>>(locales: &[S]) -> Vec<String> {
+    let mut chain: Vec<String> = Vec::new();
+    for locale in locales {
+        let mut locale = locale.as_ref().trim().replace('_', "-");
+        while !locale.is_empty() {
+            if !chain.iter().any(|known| known.eq_ignore_ascii_case(&locale)) {
+                chain.push(locale.clone());
+            }
+            let general = locale.rfind('-').unwrap_or(0);
+            locale.truncate(general);
+        }
+    }
+    chain
+}
+
+fn localize_fields(value: &mut Value, chain: &[String]) -> serde_json::Result<()> {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                match item {
+                    Value::Object(translations) if key == "text" && is_translations(translations) => {
+                        *item = translate(translations, chain)?;
+                    }
+                    _ => localize_fields(item, chain)?,
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                localize_fields(item, chain)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// Whether a text object maps locales to strings, rather than being a data binding
+fn is_translations(map: &Map<String, Value>) -> bool {
+    !map.contains_key("$") && map.values().all(Value::is_string)
+}
+
+fn translate(translations: &Map<String, Value>, chain: &[String]) -> serde_json::Result<Value> {
+    let find = |locale: &str| {
+        translations
+            .iter()
+            .find(|(key, _)| key.replace('_', "-").eq_ignore_ascii_case(locale))
+            .map(|(_, text)| text.clone())
+    };
+    chain
+        .iter()
+        .find_map(|locale| find(locale))
+        .or_else(|| translations.get("default").cloned())
+        .ok_or_else(|| {
+            let available = translations.keys().map(String::as_str).collect::<Vec<_>>().join(", ");
+            let message = match chain {
+                [] => format!("no locale is set for the text in '{}', which has no \"default\"", available),
+                _ => format!("no translation for '{}' among '{}', and no \"default\"", chain.join(", "), available),
+            };
+            serde::de::Error::custom(message)
+        })
+}
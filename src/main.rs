@@ -1,47 +1,747 @@
-use clap::Parser;
-use poster_generator::PosterGenerator;
-use std::path::PathBuf;
+use base64::{engine::general_purpose, Engine};
+use clap::{Parser, Subcommand};
+use notify::{RecursiveMode, Watcher};
+use poster_generator::animation::AnimationFormat;
+use poster_generator::print::PrintFormat;
+use poster_generator::fonts::{self, Script};
+use poster_generator::icons::{self, IconSource};
+use poster_generator::incremental::IncrementalRenderer;
+use poster_generator::template::{self, Variables};
+use poster_generator::{
+    preprocess, Element, PosterConfig, PosterGenerator, RenderProfile, RenderWarning, ASSET_SCHEME, BLURHASH_SCHEME,
+};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::io::{BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+#[cfg(feature = "server")]
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info, info_span, warn};
+use tracing_subscriber::EnvFilter;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
-    #[arg(short, long, help = "JSON config file for the poster")]
-    config: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
 
-    #[arg(short, long, help = "Output file path")]
-    output: PathBuf,
+    #[arg(short, long, required = true, help = "JSON config file for the poster (\"-\" reads from stdin, a glob such as 'templates/*.json' needs --out-dir)")]
+    config: Option<PathBuf>,
+
+    #[arg(short, long, required_unless_present = "out_dir", help = "Output file path (\"-\" writes PNG bytes to stdout)")]
+    output: Option<PathBuf>,
+
+    #[arg(long, conflicts_with_all = ["output", "base64", "watch"], help = "Render every config matched by --config into this directory")]
+    out_dir: Option<PathBuf>,
 
     #[arg(long, help = "Return base64 encoded image instead of file")]
     base64: bool,
+
+    #[arg(short, long, help = "Watch the config file and referenced assets, re-rendering on change")]
+    watch: bool,
+
+    #[arg(long, help = "Resolve relative image and font paths against this directory instead of the config file's")]
+    base_path: Option<PathBuf>,
+
+    #[arg(long, help = "Log how long each element took to decode, lay out and draw")]
+    profile: bool,
+
+    #[arg(long, value_name = "ROWS", conflicts_with_all = ["out_dir", "base64", "watch", "profile"], help = "Render in strips of this many rows, for posters too large to render at once")]
+    strip_height: Option<u32>,
+
+    #[arg(long, conflicts_with_all = ["base64", "watch", "profile", "strip_height"], help = "Also render the config's dark_theme variant, saved next to the output as <name>.dark.png")]
+    dark: bool,
+
+    #[arg(long, value_name = "FORMAT", value_parser = ["gif", "apng"], conflicts_with_all = ["out_dir", "base64", "watch", "profile", "strip_height", "dark"], help = "Render the config's animation as an animated GIF or PNG")]
+    animate: Option<String>,
+
+    #[arg(long, value_name = "FORMAT", value_parser = ["tiff", "pdf"], conflicts_with_all = ["out_dir", "base64", "watch", "profile", "strip_height", "dark", "animate"], help = "Convert to the config's print profile and save as a TIFF or PDF")]
+    print: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Render one poster per row of a CSV or JSONL data file
+    Batch {
+        #[arg(short, long, help = "JSON config template with {{column}} placeholders")]
+        template: PathBuf,
+
+        #[arg(short, long, help = "CSV (with header row) or JSONL data file; CSV cells are strings, use JSONL for typed values")]
+        data: PathBuf,
+
+        #[arg(short, long, help = "Output path pattern, e.g. \"out/{{id}}.png\" ({{_index}} is the row number)")]
+        output: String,
+
+        #[arg(short, long, help = "Number of posters rendered in parallel (defaults to the CPU count)")]
+        jobs: Option<usize>,
+    },
+
+    /// Serve a live-reloading preview of a config in the browser
+    #[cfg(feature = "server")]
+    Preview {
+        #[arg(short, long, help = "JSON config file for the poster")]
+        config: PathBuf,
+
+        #[arg(short, long, default_value_t = 8000, help = "Port to listen on")]
+        port: u16,
+    },
+
+    /// List the font families available to the renderer
+    Fonts {
+        #[arg(long = "font-dir", help = "Also list fonts found in this directory (repeatable)")]
+        font_dirs: Vec<PathBuf>,
+
+        #[arg(short, long, help = "Only show fonts covering this script (e.g. arabic, han, latin)")]
+        script: Option<Script>,
+
+        #[arg(long, help = "Print the list as JSON")]
+        json: bool,
+    },
 }
 
 fn main() -> anyhow::Result<()> {
+    // Logs go to stderr so that base64 output on stdout stays clean
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with_writer(std::io::stderr)
+        .init();
+
     let cli = Cli::parse();
-    
-    // Read config file
-    let config = std::fs::read_to_string(&cli.config)?;
-    let config: poster_generator::PosterConfig = serde_json::from_str(&config)?;
-    
-    // Create poster generator
-    let mut generator = PosterGenerator::new(config.width, config.height, config.background_color.clone());
-    
-    // Add elements from config
-    for element in config.elements {
-        match element {
-            poster_generator::Element::Background(bg) => { generator.add_background(bg); },
-            poster_generator::Element::Image(img) => { generator.add_image(img); },
-            poster_generator::Element::Text(txt) => { generator.add_text(txt); },
-        }
-    }
-    
-    // Generate the poster
-    if cli.base64 {
-        let base64 = generator.generate_base64()?;
-        println!("{}", base64);
+
+    match &cli.command {
+        Some(Command::Batch { template, data, output, jobs }) => return batch(template, data, output, *jobs),
+        #[cfg(feature = "server")]
+        Some(Command::Preview { config, port }) => return preview(config, *port),
+        Some(Command::Fonts { font_dirs, script, json }) => return list_fonts(font_dirs, *script, *json),
+        None => {}
+    }
+
+    // clap enforces --config, and --output unless --out-dir is given
+    let config = cli.config.as_deref().expect("--config is required");
+    let base_path = cli.base_path.as_deref();
+    if let Some(out_dir) = &cli.out_dir {
+        return render_glob(config, out_dir, base_path, cli.profile, cli.dark);
+    }
+    let output = cli.output.as_deref().expect("--output is required");
+
+    if let Some(strip_height) = cli.strip_height {
+        return render_tiled(config, output, base_path, strip_height);
+    }
+    if cli.dark {
+        return render_light_and_dark(config, output, base_path);
+    }
+    if let Some(format) = &cli.animate {
+        let format = if format == "gif" { AnimationFormat::Gif } else { AnimationFormat::Apng };
+        return render_animation(config, output, base_path, format);
+    }
+    if let Some(format) = &cli.print {
+        let format = if format == "tiff" { PrintFormat::Tiff } else { PrintFormat::Pdf };
+        return render_print(config, output, base_path, format);
+    }
+
+    if cli.watch {
+        if is_stdio(config) {
+            anyhow::bail!("--watch cannot be used when reading the config from stdin");
+        }
+        let mut renderer = IncrementalRenderer::new();
+        return watch(config, |assets_changed| {
+            if assets_changed {
+                renderer.reset();
+            }
+            render(config, output, cli.base64, base_path, cli.profile, &mut renderer)
+        });
+    }
+
+    render(config, output, cli.base64, base_path, cli.profile, &mut IncrementalRenderer::new())?;
+    Ok(())
+}
+
+// "-" stands for stdin/stdout
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+// Read config file, resolving relative asset paths against `base_path` if given,
+// otherwise against the config file's directory (or the current directory for stdin)
+fn load_config(path: &Path, base_path: Option<&Path>) -> anyhow::Result<PosterConfig> {
+    let _span = info_span!("parse", config = %path.display()).entered();
+    let config = if is_stdio(path) {
+        let mut config = String::new();
+        std::io::stdin().read_to_string(&mut config)?;
+        config
     } else {
-        generator.generate_file(&cli.output)?;
-        println!("Poster saved to: {}", cli.output.display());
+        std::fs::read_to_string(path)?
+    };
+    let mut config: Value = serde_json::from_str(&config)?;
+    let base_path = match base_path {
+        Some(base_path) => Some(base_path.to_path_buf()),
+        None if is_stdio(path) => None,
+        None => Some(config_dir(path).join(config.get("base_path").and_then(Value::as_str).unwrap_or_default())),
+    };
+    if let Some(base_path) = base_path {
+        set_base_path(&mut config, &base_path)?;
     }
-    
+    preprocess(&mut config, &Variables::new())?;
+    Ok(serde_json::from_value(config)?)
+}
+
+// Set the base path in the JSON config. This comes before expressions are evaluated,
+// as they measure elements with their fonts and images.
+fn set_base_path(config: &mut Value, base_path: &Path) -> anyhow::Result<()> {
+    if let Some(config) = config.as_object_mut() {
+        config.insert("base_path".to_string(), serde_json::to_value(base_path)?);
+    }
+    Ok(())
+}
+
+// Directory containing a config or template file
+fn config_dir(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+// Render the poster once, returning the config so watch mode can find its assets.
+// The renderer only redraws what changed since its previous render; profiling
+// always renders the whole poster so every element is timed.
+fn render(
+    config_path: &Path,
+    output: &Path,
+    base64: bool,
+    base_path: Option<&Path>,
+    profile: bool,
+    renderer: &mut IncrementalRenderer,
+) -> anyhow::Result<PosterConfig> {
+    let config = load_config(config_path, base_path)?;
+    if !config.outputs.is_empty() {
+        if base64 || is_stdio(output) {
+            warn!("Only the poster's own size is written: `outputs` need an output file to name the other sizes after");
+        } else if profile {
+            warn!("Only the poster's own size is written: `outputs` aren't rendered with --profile");
+        } else {
+            render_outputs(&config, output)?;
+            return Ok(config);
+        }
+    }
+    let png_data = if profile {
+        let mut generator = PosterGenerator::from_config(config.clone());
+        generator.set_profiling(true);
+        let poster = generator.generate_with_metadata()?;
+        if let Some(profile) = &poster.profile {
+            log_profile(profile);
+        }
+        log_warnings(&poster.warnings);
+        poster.png
+    } else {
+        let png = renderer.render(&config)?;
+        log_warnings(renderer.warnings());
+        png
+    };
+
+    // Write the poster
+    if base64 {
+        println!("data:image/png;base64,{}", general_purpose::STANDARD.encode(&png_data));
+    } else if is_stdio(output) {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&png_data)?;
+        stdout.flush()?;
+    } else {
+        std::fs::write(output, &png_data)?;
+        info!("Poster saved to: {}", output.display());
+    }
+
+    Ok(config)
+}
+
+// Render the poster at its own size and each of its `outputs`, naming them <name><suffix>.png
+fn render_outputs(config: &PosterConfig, output: &Path) -> anyhow::Result<()> {
+    let rendered = PosterGenerator::from_config(config.clone()).generate_outputs()?;
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = output.extension().map(|extension| extension.to_string_lossy()).unwrap_or("png".into());
+    for size in rendered {
+        let path = output.with_file_name(format!("{}{}.{}", stem, size.suffix, extension));
+        std::fs::write(&path, size.png)?;
+        info!("Poster saved to: {} ({}x{})", path.display(), size.width, size.height);
+    }
+    Ok(())
+}
+
+// Render the poster strip by strip, streaming the PNG to the output
+fn render_tiled(config_path: &Path, output: &Path, base_path: Option<&Path>, strip_height: u32) -> anyhow::Result<()> {
+    let generator = PosterGenerator::from_config(load_config(config_path, base_path)?);
+    if is_stdio(output) {
+        let mut stdout = std::io::stdout().lock();
+        generator.write_png_tiled(&mut stdout, strip_height)?;
+        stdout.flush()?;
+    } else {
+        let file = std::fs::File::create(output)?;
+        generator.write_png_tiled(std::io::BufWriter::new(file), strip_height)?;
+        info!("Poster saved to: {}", output.display());
+    }
+    Ok(())
+}
+
+// Render the light and dark variants, saving the dark one as <name>.dark.png next to the output
+fn render_light_and_dark(config_path: &Path, output: &Path, base_path: Option<&Path>) -> anyhow::Result<()> {
+    if is_stdio(output) {
+        anyhow::bail!("--dark needs an output file to name the dark variant after");
+    }
+    let mut generator = PosterGenerator::from_config(load_config(config_path, base_path)?);
+    let (light, dark) = generator.generate_light_and_dark()?;
+    let dark_output = output.with_extension("dark.png");
+    std::fs::write(output, light)?;
+    std::fs::write(&dark_output, dark)?;
+    info!("Posters saved to: {} and {}", output.display(), dark_output.display());
+    Ok(())
+}
+
+// Render the config's animation frame by frame into an animated image
+fn render_animation(config_path: &Path, output: &Path, base_path: Option<&Path>, format: AnimationFormat) -> anyhow::Result<()> {
+    let mut generator = PosterGenerator::from_config(load_config(config_path, base_path)?);
+    let data = generator.generate_animation(format)?;
+    if is_stdio(output) {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&data)?;
+        stdout.flush()?;
+    } else {
+        std::fs::write(output, &data)?;
+        info!("Animation saved to: {}", output.display());
+    }
+    Ok(())
+}
+
+// Render the config converted to its print profile
+fn render_print(config_path: &Path, output: &Path, base_path: Option<&Path>, format: PrintFormat) -> anyhow::Result<()> {
+    let generator = PosterGenerator::from_config(load_config(config_path, base_path)?);
+    let data = generator.generate_print(format)?;
+    if is_stdio(output) {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&data)?;
+        stdout.flush()?;
+    } else {
+        std::fs::write(output, &data)?;
+        info!("Print file saved to: {}", output.display());
+    }
+    Ok(())
+}
+
+// Log the quality problems found while rendering, one line each
+fn log_warnings(warnings: &[RenderWarning]) {
+    for warning in warnings {
+        warn!("{}", warning);
+    }
+}
+
+// Log a render profile, one line per element
+fn log_profile(profile: &RenderProfile) {
+    for element in &profile.elements {
+        info!(
+            "Element {}{}: {:.1} ms (decode {:.1}, layout {:.1}, draw {:.1})",
+            element.index,
+            element.id.as_deref().map(|id| format!(" ({})", id)).unwrap_or_default(),
+            element.total_ms,
+            element.decode_ms,
+            element.layout_ms,
+            element.draw_ms,
+        );
+    }
+    info!(
+        "Rendered in {:.1} ms (watermark {:.1}, encode {:.1})",
+        profile.total_ms, profile.watermark_ms, profile.encode_ms
+    );
+}
+
+// Render every config matching a glob pattern into a directory, named after the config
+fn render_glob(pattern: &Path, out_dir: &Path, base_path: Option<&Path>, profile: bool, dark: bool) -> anyhow::Result<()> {
+    let pattern = pattern.to_str().ok_or_else(|| anyhow::anyhow!("config pattern is not valid UTF-8"))?;
+    let configs = glob::glob(pattern)?.collect::<Result<Vec<_>, _>>()?;
+    if configs.is_empty() {
+        anyhow::bail!("no config files match '{}'", pattern);
+    }
+
+    std::fs::create_dir_all(out_dir)?;
+
+    let mut failed = Vec::new();
+    for config in &configs {
+        let name = config.file_stem().unwrap_or(config.as_os_str());
+        let output = out_dir.join(name).with_extension("png");
+        let result = if dark {
+            render_light_and_dark(config, &output, base_path)
+        } else {
+            render(config, &output, false, base_path, profile, &mut IncrementalRenderer::new()).map(|_| ())
+        };
+        if let Err(err) = result {
+            error!("{}: {:#}", config.display(), err);
+            failed.push(config);
+        }
+    }
+
+    info!("Rendered {} of {} config(s)", configs.len() - failed.len(), configs.len());
+    if !failed.is_empty() {
+        for config in &failed {
+            error!("Failed: {}", config.display());
+        }
+        anyhow::bail!("{} of {} config(s) failed to render", failed.len(), configs.len());
+    }
+
+    Ok(())
+}
+
+// Local files referenced by the config (images and fonts), excluding data URLs
+fn asset_paths(config: &PosterConfig) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let sources = config.elements.iter().flat_map(|element| match element {
+        Element::Background(bg) => vec![bg.image.as_deref().map(Cow::Borrowed)],
+        Element::Image(img) => vec![Some(Cow::Borrowed(img.src.as_str()))],
+        Element::Text(txt) => {
+            vec![txt.font_file.as_deref().map(Cow::Borrowed), txt.fill_image.as_deref().map(Cow::Borrowed)]
+        }
+        Element::Badge(badge) => {
+            vec![badge.icon.as_deref().map(Cow::Borrowed), badge.font_file.as_deref().map(Cow::Borrowed)]
+        }
+        // Icons drawn from an icon font read its file
+        Element::Icon(icon) => match icons::lookup(&icon.name) {
+            Some(IconSource::FontGlyph { font_file, .. }) => vec![Some(Cow::Owned(font_file))],
+            _ => vec![],
+        },
+        Element::BackdropBlur(_)
+        | Element::PieChart(_)
+        | Element::BarChart(_)
+        | Element::Sparkline(_)
+        | Element::Line(_)
+        | Element::Rect(_)
+        | Element::Circle(_)
+        | Element::Path(_)
+        | Element::Noise(_)
+        | Element::Pixelate(_)
+        | Element::Generative(_)
+        | Element::Custom(_) => vec![],
+    });
+    let watermark = config.watermark.as_ref().and_then(|watermark| watermark.image.as_deref()).map(Cow::Borrowed);
+    for src in sources.chain(std::iter::once(watermark)).flatten() {
+        let embedded = [ASSET_SCHEME, BLURHASH_SCHEME, "data:"].iter().any(|scheme| src.starts_with(scheme));
+        if !embedded && !src.contains("://") {
+            let base_path = config.base_path.as_deref().unwrap_or(Path::new(""));
+            paths.push(base_path.join(&*src));
+        }
+    }
+    paths
+}
+
+// Re-render whenever the config or one of its assets changes. `render` is told
+// whether an asset changed: the config is then the same, but its render isn't.
+fn watch(config_path: &Path, mut render: impl FnMut(bool) -> anyhow::Result<PosterConfig>) -> anyhow::Result<()> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    let mut watched_dirs: HashSet<PathBuf> = HashSet::new();
+    let mut assets_changed = false;
+
+    loop {
+        let mut files = vec![config_path.to_path_buf()];
+        match render(assets_changed) {
+            Ok(config) => files.extend(asset_paths(&config)),
+            Err(err) => error!("Render failed: {:#}", err),
+        }
+
+        // Editors often replace files on save, so watch the parent directories
+        // and filter events down to the files we care about
+        let files: HashSet<PathBuf> = files.iter().filter_map(|f| watched_path(f)).collect();
+        for file in &files {
+            if let Some(dir) = file.parent() {
+                if watched_dirs.insert(dir.to_path_buf()) {
+                    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+                }
+            }
+        }
+        info!("Watching {} file(s) for changes", files.len());
+
+        // Wait for a relevant change
+        let changed_files = |event: &notify::Event| -> Vec<PathBuf> {
+            event.paths.iter().filter_map(|p| watched_path(p)).filter(|p| files.contains(p)).collect()
+        };
+        let mut changed = Vec::new();
+        while changed.is_empty() {
+            changed = changed_files(&rx.recv()??);
+        }
+
+        // Debounce bursts of events from a single save
+        std::thread::sleep(Duration::from_millis(100));
+        while let Ok(event) = rx.try_recv() {
+            if let Ok(event) = event {
+                changed.extend(changed_files(&event));
+            }
+        }
+        let config_file = watched_path(config_path);
+        assets_changed = changed.iter().any(|file| Some(file) != config_file.as_ref());
+    }
+}
+
+// Absolute path of a watched file, to compare with the paths of watcher events. A file
+// that is briefly missing while an editor deletes and recreates it is resolved through
+// its directory, so it stays watched.
+fn watched_path(path: &Path) -> Option<PathBuf> {
+    path.canonicalize().ok().or_else(|| {
+        let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        Some(dir.canonicalize().ok()?.join(path.file_name()?))
+    })
+}
+
+// Read batch rows from a CSV file (header row gives the variable names) or JSONL file
+fn load_rows(path: &Path) -> anyhow::Result<Vec<Variables>> {
+    let is_csv = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let mut rows = Vec::new();
+
+    if is_csv {
+        let mut reader = csv::Reader::from_path(path)?;
+        let headers = reader.headers()?.clone();
+        for record in reader.records() {
+            let record = record?;
+            // CSV has no types, so every cell stays a string
+            let row = headers.iter()
+                .zip(record.iter())
+                .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+                .collect();
+            rows.push(row);
+        }
+    } else {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        for (line_no, line) in file.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line)? {
+                Value::Object(row) => rows.push(row),
+                _ => anyhow::bail!("line {} of {} is not a JSON object", line_no + 1, path.display()),
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+fn render_row(config_template: &Value, vars: &Variables, output: &Path, base_path: &Path) -> anyhow::Result<()> {
+    let mut config_template = config_template.clone();
+    // A placeholder in the template's own base path is still substituted after the join
+    let base_path = base_path.join(config_template.get("base_path").and_then(Value::as_str).unwrap_or_default());
+    set_base_path(&mut config_template, &base_path)?;
+    let config = template::render_config(&config_template, vars)?;
+    if let Some(dir) = output.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    PosterGenerator::from_config(config).generate_file(output)?;
+    Ok(())
+}
+
+// Render the template once per data row, spreading rows over worker threads
+fn batch(template_path: &Path, data_path: &Path, output_pattern: &str, jobs: Option<usize>) -> anyhow::Result<()> {
+    let config_template: Value = serde_json::from_str(&std::fs::read_to_string(template_path)?)?;
+    let rows = load_rows(data_path)?;
+    let base_path = config_dir(template_path);
+    let jobs = jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+        .clamp(1, rows.len().max(1));
+
+    info!("Rendering {} poster(s) with {} worker(s)", rows.len(), jobs);
+
+    let next_row = AtomicUsize::new(0);
+    let failures = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let index = next_row.fetch_add(1, Ordering::Relaxed);
+                let Some(row) = rows.get(index) else {
+                    break;
+                };
+
+                let mut vars = row.clone();
+                vars.insert("_index".to_string(), Value::from(index));
+                let output = PathBuf::from(template::render_str(output_pattern, &vars));
+
+                match render_row(&config_template, &vars, &output, &base_path) {
+                    Ok(()) => info!("Row {}: saved to {}", index, output.display()),
+                    Err(err) => {
+                        failures.fetch_add(1, Ordering::Relaxed);
+                        error!("Row {}: {:#}", index, err);
+                    }
+                }
+            });
+        }
+    });
+
+    let failures = failures.into_inner();
+    info!("Batch finished: {} succeeded, {} failed", rows.len() - failures, failures);
+    if failures > 0 {
+        anyhow::bail!("{} of {} poster(s) failed to render", failures, rows.len());
+    }
+
+    Ok(())
+}
+
+// Latest preview render, shared between the watcher thread and the HTTP server
+#[cfg(feature = "server")]
+#[derive(Default)]
+struct PreviewState {
+    version: u64,
+    png: Option<Vec<u8>>,
+    blurhash: Option<String>,
+    error: Option<String>,
+    warnings: Vec<String>,
+}
+
+#[cfg(feature = "server")]
+const PREVIEW_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Poster preview</title>
+<style>
+  body { margin: 0; padding: 24px; background: #2b2b2b; font-family: sans-serif; text-align: center; }
+  img { max-width: 100%; box-shadow: 0 4px 24px rgba(0, 0, 0, 0.5); background: repeating-conic-gradient(#ccc 0 25%, #fff 0 50%) 0 0 / 16px 16px; }
+  #error { display: none; margin-bottom: 16px; padding: 12px; color: #fff; background: #c0392b; text-align: left; white-space: pre-wrap; }
+  #warnings { display: none; margin-bottom: 16px; padding: 12px; color: #000; background: #f1c40f; text-align: left; white-space: pre-wrap; }
+</style>
+</head>
+<body>
+<pre id="error"></pre>
+<pre id="warnings"></pre>
+<img id="poster" alt="poster">
+<script>
+  let version = null;
+  async function poll() {
+    try {
+      const status = await (await fetch('/status')).json();
+      if (status.version !== version) {
+        version = status.version;
+        document.getElementById('poster').src = '/poster.png?v=' + version;
+        const error = document.getElementById('error');
+        error.textContent = status.error || '';
+        error.style.display = status.error ? 'block' : 'none';
+        const warnings = document.getElementById('warnings');
+        warnings.textContent = status.warnings.join('\n');
+        warnings.style.display = status.warnings.length ? 'block' : 'none';
+      }
+    } catch (e) {}
+    setTimeout(poll, 500);
+  }
+  poll();
+</script>
+</body>
+</html>
+"#;
+
+// Render in a watcher thread and serve the latest result over HTTP
+#[cfg(feature = "server")]
+fn preview(config_path: &Path, port: u16) -> anyhow::Result<()> {
+    let state = Arc::new(Mutex::new(PreviewState::default()));
+
+    let watch_state = Arc::clone(&state);
+    let watch_path = config_path.to_path_buf();
+    std::thread::spawn(move || {
+        let mut renderer = IncrementalRenderer::new();
+        let result = watch(&watch_path, |assets_changed| {
+            if assets_changed {
+                renderer.reset();
+            }
+            let rendered = load_config(&watch_path, None).and_then(|config| {
+                let png = renderer.render(&config)?;
+                Ok((config, png, renderer.warnings().iter().map(ToString::to_string).collect()))
+            });
+
+            let mut state = watch_state.lock().unwrap();
+            state.version += 1;
+            match rendered {
+                Ok((config, png, warnings)) => {
+                    state.blurhash = poster_generator::blurhash::from_png(&png)
+                        .map_err(|err| tracing::warn!("Failed to compute BlurHash: {}", err))
+                        .ok();
+                    state.png = Some(png);
+                    state.error = None;
+                    state.warnings = warnings;
+                    info!("Preview updated (version {})", state.version);
+                    Ok(config)
+                }
+                Err(err) => {
+                    // Keep showing the last good render alongside the error
+                    state.error = Some(format!("{:#}", err));
+                    Err(err)
+                }
+            }
+        });
+        if let Err(err) = result {
+            error!("Watcher stopped: {:#}", err);
+        }
+    });
+
+    let server = tiny_http::Server::http(("127.0.0.1", port))
+        .map_err(|err| anyhow::anyhow!("failed to listen on port {}: {}", port, err))?;
+    info!("Preview available at http://localhost:{}", port);
+
+    for request in server.incoming_requests() {
+        let path = request.url().split('?').next().unwrap_or("/").to_string();
+        let response = match path.as_str() {
+            "/" => tiny_http::Response::from_string(PREVIEW_PAGE)
+                .with_header(content_type("text/html; charset=utf-8")),
+            "/status" => {
+                let state = state.lock().unwrap();
+                let status = serde_json::json!({
+                    "version": state.version,
+                    "error": state.error,
+                    "blurhash": state.blurhash,
+                    "warnings": state.warnings,
+                });
+                tiny_http::Response::from_string(status.to_string())
+                    .with_header(content_type("application/json"))
+            }
+            "/poster.png" => match &state.lock().unwrap().png {
+                Some(png) => tiny_http::Response::from_data(png.clone())
+                    .with_header(content_type("image/png")),
+                None => tiny_http::Response::from_string("not rendered yet").with_status_code(404),
+            },
+            _ => tiny_http::Response::from_string("not found").with_status_code(404),
+        };
+
+        if let Err(err) = request.respond(response) {
+            error!("Failed to send preview response: {}", err);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+fn content_type(value: &str) -> tiny_http::Header {
+    tiny_http::Header::from_bytes(&b"Content-Type"[..], value.as_bytes()).expect("valid header")
+}
+
+// Print system fonts plus fonts from the given directories
+fn list_fonts(font_dirs: &[PathBuf], script: Option<Script>, json: bool) -> anyhow::Result<()> {
+    let mut families = fonts::system_fonts();
+    for dir in font_dirs {
+        families.extend(fonts::fonts_in_dir(dir)?);
+    }
+    if let Some(script) = script {
+        families.retain(|family| family.scripts.contains(&script));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&families)?);
+        return Ok(());
+    }
+
+    for family in &families {
+        let scripts: Vec<&str> = family.scripts.iter().map(|script| script.name()).collect();
+        let source = match &family.path {
+            Some(path) => format!(" ({})", path.display()),
+            None => String::new(),
+        };
+        println!("{}{}: {} [{}]", family.family, source, family.styles.join(", "), scripts.join(", "));
+    }
+    info!("{} font famil{} found", families.len(), if families.len() == 1 { "y" } else { "ies" });
+
     Ok(())
 }
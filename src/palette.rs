@@ -0,0 +1,130 @@
+//! Dominant color extraction, for tinting a template to match its artwork.
+//!
+//! ```no_run
+//! use poster_generator::palette::extract_palette;
+//!
+//! let colors = extract_palette("cover.jpg", 3).expect("Failed to read image");
+//! ```
+//!
+//! A poster whose `background_color` is `"auto"` is filled with the dominant
+//! color of its largest image.
+
+use crate::assets::{AssetResolver, DefaultResolver};
+use crate::{decode_image, DecodeOptions, PosterError, Result};
+use skia_safe::{
+    AlphaType, BlendMode, Color, ColorType, Data, FilterMode, Image, ImageInfo, MipmapMode, Paint, Rect, SamplingOptions,
+};
+use std::collections::HashMap;
+
+// Longest side of the thumbnail colors are counted on; plenty for dominant colors
+const SAMPLE_SIZE: f32 = 64.0;
+
+// Smallest RGB distance between palette colors, so shades of the top color aren't repeated
+const MIN_DISTANCE: f32 = 48.0;
+
+/// Loads an image (file path, base64 data URL or HTTP(S) URL) and returns up to
+/// `n` of its most common colors, most common first.
+///
+/// Use [`PosterGenerator::extract_palette`](crate::PosterGenerator::extract_palette)
+/// to load the image with a poster's registered assets and resolver instead.
+///
+/// # Errors
+///
+/// Returns an error if the image can't be read or decoded.
+pub fn extract_palette(image_source: &str, n: usize) -> Result<Vec<Color>> {
+    let source = crate::assets::describe_source(image_source);
+    let bytes = DefaultResolver::default()
+        .resolve(image_source)
+        .map_err(|e| PosterError::ImageLoadError(format!("Failed to read {}: {}", source, e)))?;
+    let image = decode_image(Data::new_copy(&bytes), &DecodeOptions::default())
+        .ok_or_else(|| PosterError::ImageLoadError(format!("Failed to load image from: {}", source)))?;
+    Ok(image_palette(&image, n))
+}
+
+/// Returns up to `n` of the most common colors of a decoded image, most common first.
+///
+/// Colors are grouped into buckets of similar colors, and each palette color is
+/// the average of a bucket. Mostly transparent pixels are ignored.
+pub fn image_palette(image: &Image, n: usize) -> Vec<Color> {
+    if n == 0 || image.width() == 0 || image.height() == 0 {
+        return Vec::new();
+    }
+
+    // Count colors on a thumbnail
+    let Some((pixels, _, _)) = thumbnail_rgba(image, SAMPLE_SIZE) else {
+        return Vec::new();
+    };
+
+    // Buckets of 16 levels per channel
+    let mut buckets: HashMap<u16, Bucket> = HashMap::new();
+    for pixel in pixels.chunks_exact(4) {
+        let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+        if a < 128 {
+            continue;
+        }
+        let key = (u16::from(r >> 4) << 8) | (u16::from(g >> 4) << 4) | u16::from(b >> 4);
+        let bucket = buckets.entry(key).or_default();
+        bucket.count += 1;
+        bucket.sum[0] += u32::from(r);
+        bucket.sum[1] += u32::from(g);
+        bucket.sum[2] += u32::from(b);
+    }
+
+    let mut buckets: Vec<(u16, Bucket)> = buckets.into_iter().collect();
+    // Ties are broken by the bucket key so the palette doesn't depend on hash order
+    buckets.sort_by(|(a_key, a), (b_key, b)| b.count.cmp(&a.count).then(a_key.cmp(b_key)));
+
+    let mut palette: Vec<Color> = Vec::with_capacity(n);
+    for (_, bucket) in &buckets {
+        let color = bucket.average();
+        if palette.iter().all(|&chosen| distance(chosen, color) >= MIN_DISTANCE) {
+            palette.push(color);
+            if palette.len() == n {
+                break;
+            }
+        }
+    }
+    palette
+}
+
+/// Unpremultiplied RGBA pixels of the image scaled down to at most `max_size` pixels
+/// on its longest side, with the thumbnail's width and height.
+pub(crate) fn thumbnail_rgba(image: &Image, max_size: f32) -> Option<(Vec<u8>, u32, u32)> {
+    // Mipmapped scaling averages the pixels it drops
+    let scale = (max_size / image.width().max(image.height()) as f32).min(1.0);
+    let width = ((image.width() as f32 * scale).round() as i32).max(1);
+    let height = ((image.height() as f32 * scale).round() as i32).max(1);
+    let mut surface = skia_safe::surfaces::raster_n32_premul((width, height))?;
+    let mut paint = Paint::default();
+    paint.set_blend_mode(BlendMode::Src);
+    let sampling = SamplingOptions::new(FilterMode::Linear, MipmapMode::Linear);
+    let dest = Rect::from_iwh(width, height);
+    surface.canvas().draw_image_rect_with_sampling_options(image, None, dest, sampling, &paint);
+
+    let info = ImageInfo::new((width, height), ColorType::RGBA8888, AlphaType::Unpremul, None);
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    if !surface.read_pixels(&info, &mut pixels, width as usize * 4, (0, 0)) {
+        return None;
+    }
+    Some((pixels, width as u32, height as u32))
+}
+
+// Pixels of similar color
+#[derive(Default)]
+struct Bucket {
+    count: u32,
+    sum: [u32; 3],
+}
+
+impl Bucket {
+    fn average(&self) -> Color {
+        let channel = |sum: u32| (sum / self.count.max(1)) as u8;
+        Color::from_rgb(channel(self.sum[0]), channel(self.sum[1]), channel(self.sum[2]))
+    }
+}
+
+fn distance(a: Color, b: Color) -> f32 {
+    let delta = |x: u8, y: u8| x as f32 - y as f32;
+    let (dr, dg, db) = (delta(a.r(), b.r()), delta(a.g(), b.g()), delta(a.b(), b.b()));
+    (dr * dr + dg * dg + db * db).sqrt()
+}
@@ -0,0 +1,95 @@
+//! Procedural pattern fills, for retro aesthetics without tiling image assets.
+//!
+//! A [`Pattern`] repeats stripes, dots or a checkerboard over a shape or the
+//! background, at any spacing and angle:
+//!
+//! ```json
+//! { "type": "rect", "x": 0, "y": 0, "width": 800, "height": 200,
+//!   "fill_pattern": { "type": "stripes", "color": "#e63946", "background": "#f1faee", "spacing": 24, "angle": 45 } }
+//! ```
+
+use crate::{parse_color, PosterError, Result};
+use serde::{Deserialize, Serialize};
+use skia_safe::{FilterMode, Matrix, Paint, PictureRecorder, Point, Rect, Shader, TileMode};
+
+/// Kind of mark repeated by a [`Pattern`].
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternKind {
+    /// Parallel bands, horizontal at angle 0.
+    Stripes,
+    /// Dots on a square grid.
+    Dots,
+    /// A checkerboard of squares half the spacing wide.
+    Checker,
+}
+
+/// Repeating pattern used in place of a solid fill color.
+///
+/// The pattern starts at the top-left corner of the shape it paints.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Pattern {
+    /// Kind of mark.
+    #[serde(rename = "type")]
+    pub kind: PatternKind,
+    /// Color of the stripes, dots or dark squares in hex format.
+    pub color: String,
+    /// Color between the marks in hex format. Transparent if not set.
+    #[serde(default)]
+    pub background: Option<String>,
+    /// Distance in pixels after which the pattern repeats.
+    #[serde(default = "default_pattern_spacing")]
+    pub spacing: f32,
+    /// Thickness of the stripes or diameter of the dots. Defaults to half the spacing.
+    #[serde(default)]
+    pub size: Option<f32>,
+    /// Rotation of the pattern in degrees, clockwise.
+    #[serde(default)]
+    pub angle: f32,
+}
+
+fn default_pattern_spacing() -> f32 {
+    20.0
+}
+
+impl Pattern {
+    /// Creates a shader that paints this pattern over `rect`.
+    pub(crate) fn shader(&self, rect: Rect) -> Result<Shader> {
+        if self.spacing < 1.0 {
+            return Err(PosterError::RenderError(format!("Pattern spacing {} is below 1 pixel", self.spacing)));
+        }
+        let spacing = self.spacing;
+        let size = self.size.unwrap_or(spacing / 2.0).clamp(0.0, spacing);
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(parse_color(&self.color, "pattern.color")?);
+
+        // Record one tile of the pattern; as a picture it stays sharp at any angle
+        let tile = Rect::from_wh(spacing, spacing);
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(tile, None);
+        if let Some(background) = &self.background {
+            canvas.clear(parse_color(background, "pattern.background")?);
+        }
+        match self.kind {
+            PatternKind::Stripes => {
+                canvas.draw_rect(Rect::from_xywh(0.0, (spacing - size) / 2.0, spacing, size), &paint);
+            }
+            PatternKind::Dots => {
+                canvas.draw_circle(Point::new(spacing / 2.0, spacing / 2.0), size / 2.0, &paint);
+            }
+            PatternKind::Checker => {
+                let half = spacing / 2.0;
+                canvas.draw_rect(Rect::from_xywh(0.0, 0.0, half, half), &paint);
+                canvas.draw_rect(Rect::from_xywh(half, half, half, half), &paint);
+            }
+        }
+        let picture = recorder
+            .finish_recording_as_picture(None)
+            .ok_or_else(|| PosterError::RenderError("Failed to record pattern tile".to_string()))?;
+
+        let mut matrix = Matrix::translate((rect.left, rect.top));
+        matrix.pre_rotate(self.angle, None);
+        Ok(picture.to_shader((TileMode::Repeat, TileMode::Repeat), FilterMode::Linear, &matrix, &tile))
+    }
+}
@@ -0,0 +1,183 @@
+//! Post-processing of the finished poster: crop, resize and sharpen.
+//!
+//! Applied to the rendered image just before it is encoded, so producing a
+//! thumbnail doesn't need a second image library:
+//!
+//! ```json
+//! "post": {
+//!     "crop": { "x": 0, "y": 135, "width": 1080, "height": 1080 },
+//!     "resize": { "width": 320, "filter": "mitchell" },
+//!     "sharpen": 0.3
+//! }
+//! ```
+//!
+//! The steps run in that order. Element metadata stays in canvas coordinates.
+
+use crate::{PosterError, Result};
+use serde::{Deserialize, Serialize};
+use skia_safe::image_filters::{self, CropRect};
+use skia_safe::{
+    surfaces, BlendMode, CubicResampler, FilterMode, IRect, Image, MipmapMode, Paint, Rect, SamplingOptions, TileMode,
+};
+
+/// Steps applied to the finished poster before it is encoded.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct PostProcess {
+    /// Region of the poster to keep, in canvas pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crop: Option<Crop>,
+    /// New size of the (cropped) poster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resize: Option<Resize>,
+    /// Strength of the sharpening applied last, from 0 (off) to about 1.
+    /// Restores some crispness to text after shrinking.
+    #[serde(default)]
+    pub sharpen: f32,
+}
+
+/// Region of the poster kept by [`PostProcess::crop`]. Parts outside the canvas are dropped.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct Crop {
+    /// X-coordinate of the top-left corner.
+    pub x: u32,
+    /// Y-coordinate of the top-left corner.
+    pub y: u32,
+    /// Width of the region.
+    pub width: u32,
+    /// Height of the region.
+    pub height: u32,
+}
+
+/// New size of the poster. With only one dimension set, the other keeps the aspect ratio.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct Resize {
+    /// Width in pixels.
+    pub width: Option<u32>,
+    /// Height in pixels.
+    pub height: Option<u32>,
+    /// How pixels are sampled.
+    #[serde(default)]
+    pub filter: ResizeFilter,
+}
+
+/// How pixels are sampled when resizing.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeFilter {
+    /// Nearest pixel, for pixel art.
+    Nearest,
+    /// Bilinear, averaging the pixels dropped when shrinking (default).
+    #[default]
+    Linear,
+    /// Mitchell cubic, soft; best for enlarging or shrinking by less than half.
+    Mitchell,
+    /// Catmull-Rom cubic, sharper than Mitchell; best for enlarging or shrinking by less than half.
+    CatmullRom,
+}
+
+impl From<ResizeFilter> for SamplingOptions {
+    fn from(filter: ResizeFilter) -> Self {
+        match filter {
+            ResizeFilter::Nearest => SamplingOptions::new(FilterMode::Nearest, MipmapMode::None),
+            ResizeFilter::Linear => SamplingOptions::new(FilterMode::Linear, MipmapMode::Linear),
+            ResizeFilter::Mitchell => CubicResampler::mitchell().into(),
+            ResizeFilter::CatmullRom => CubicResampler::catmull_rom().into(),
+        }
+    }
+}
+
+impl PostProcess {
+    /// Whether any step changes the image.
+    pub fn is_noop(&self) -> bool {
+        self.crop.is_none() && self.resize.is_none() && self.sharpen <= 0.0
+    }
+
+    // Run the steps on the rendered poster
+    pub(crate) fn apply(&self, mut image: Image) -> Result<Image> {
+        let _span = tracing::debug_span!("post_process").entered();
+        if let Some(crop) = &self.crop {
+            image = crop.apply(&image)?;
+        }
+        if let Some(resize) = &self.resize {
+            image = resize.apply(&image)?;
+        }
+        if self.sharpen > 0.0 {
+            image = sharpen(&image, self.sharpen)?;
+        }
+        Ok(image)
+    }
+}
+
+impl Crop {
+    fn apply(&self, image: &Image) -> Result<Image> {
+        let region = IRect::from_xywh(self.x as i32, self.y as i32, self.width as i32, self.height as i32);
+        let area = IRect::intersect(&region, &IRect::from_wh(image.width(), image.height())).ok_or_else(|| {
+            PosterError::OutputError(format!(
+                "Crop {}x{} at ({}, {}) is outside the {}x{} poster",
+                self.width,
+                self.height,
+                self.x,
+                self.y,
+                image.width(),
+                image.height()
+            ))
+        })?;
+        image
+            .make_subset(None, area)
+            .ok_or_else(|| PosterError::OutputError("Failed to crop the poster".to_string()))
+    }
+}
+
+impl Resize {
+    fn apply(&self, image: &Image) -> Result<Image> {
+        let aspect = image.width() as f32 / image.height() as f32;
+        let (width, height) = match (self.width, self.height) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (width, (width as f32 / aspect).round() as u32),
+            (None, Some(height)) => ((height as f32 * aspect).round() as u32, height),
+            (None, None) => return Ok(image.clone()),
+        };
+        let (width, height) = (width.max(1) as i32, height.max(1) as i32);
+        if (width, height) == (image.width(), image.height()) {
+            return Ok(image.clone());
+        }
+
+        let mut surface = surfaces::raster_n32_premul((width, height))
+            .ok_or_else(|| PosterError::OutputError(format!("Failed to create {}x{} surface", width, height)))?;
+        let mut paint = Paint::default();
+        paint.set_blend_mode(BlendMode::Src);
+        surface.canvas().draw_image_rect_with_sampling_options(
+            image,
+            None,
+            Rect::from_iwh(width, height),
+            SamplingOptions::from(self.filter),
+            &paint,
+        );
+        Ok(surface.image_snapshot())
+    }
+}
+
+// Boost each pixel's difference from its neighbours with a 3x3 convolution
+fn sharpen(image: &Image, amount: f32) -> Result<Image> {
+    let a = amount.min(4.0);
+    let kernel = [0.0, -a, 0.0, -a, 1.0 + 4.0 * a, -a, 0.0, -a, 0.0];
+    let filter = image_filters::matrix_convolution(
+        (3, 3),
+        &kernel,
+        1.0,
+        0.0,
+        (1, 1),
+        TileMode::Clamp,
+        false,
+        None,
+        CropRect::NO_CROP_RECT,
+    )
+    .ok_or_else(|| PosterError::OutputError("Failed to create sharpen filter".to_string()))?;
+
+    let mut surface = surfaces::raster_n32_premul((image.width(), image.height()))
+        .ok_or_else(|| PosterError::OutputError("Failed to create surface".to_string()))?;
+    let mut paint = Paint::default();
+    paint.set_image_filter(filter);
+    surface.canvas().draw_image(image, (0.0, 0.0), Some(&paint));
+    Ok(surface.image_snapshot())
+}
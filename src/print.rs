@@ -0,0 +1,397 @@
+//! Print output in the color space of a printing condition.
+//!
+//! Posters are drawn in sRGB, which presses can't reproduce as is: their inks
+//! have a different gamut, described by an ICC profile from the print shop.
+//! With a `print` profile in the config, [`PosterGenerator::generate_print`]
+//! converts the poster into the profile's color space and writes it as a TIFF
+//! or PDF with the profile embedded, so colors don't shift at the printer:
+//!
+//! ```json
+//! "print": { "icc_profile": "ISOcoated_v2_300_eci.icc", "intent": "relative_colorimetric", "dpi": 300 }
+//! ```
+//!
+//! CMYK profiles give CMYK output; RGB profiles, such as Adobe RGB, give RGB
+//! output. Transparent areas are printed on white paper. The profile is loaded
+//! like an image, so it can be a file, a URL or a registered asset.
+//!
+//! Color conversion uses LittleCMS and needs the `print` feature.
+//!
+//! For trimming, `bleed` extends the poster's backgrounds past its edges and
+//! `crop_marks` draws trim marks around it, on a white sheet enlarged to fit
+//! them. Both also apply to [`PosterGenerator::generate`]; animations, extra
+//! output sizes and strip renders stay the poster's own size:
+//!
+//! ```json
+//! "bleed": "3mm",
+//! "crop_marks": true
+//! ```
+//!
+//! [`PosterGenerator::generate_print`]: crate::PosterGenerator::generate_print
+//! [`PosterGenerator::generate`]: crate::PosterGenerator::generate
+
+use crate::{PosterError, Result};
+use serde::{Deserialize, Serialize};
+use skia_safe::{surfaces, AlphaType, Color, ColorType, Image, ImageInfo, Rect};
+use std::fmt::Write as _;
+
+/// Printing condition the poster is converted for.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PrintProfile {
+    /// ICC profile of the printing condition: file path, URL, data URL or `asset://` name.
+    pub icc_profile: String,
+    /// How colors outside the profile's gamut are mapped into it.
+    #[serde(default)]
+    pub intent: RenderingIntent,
+    /// Map the darkest sRGB black to the darkest black the press can print, keeping
+    /// detail in the shadows.
+    #[serde(default = "default_black_point_compensation")]
+    pub black_point_compensation: bool,
+    /// Resolution the poster is printed at, which sets the page size of PDFs and is
+    /// recorded in TIFFs.
+    #[serde(default = "default_dpi")]
+    pub dpi: f32,
+}
+
+fn default_black_point_compensation() -> bool {
+    true
+}
+
+// Resolution that millimeters are converted at without a print profile
+pub(crate) const DEFAULT_DPI: f32 = 300.0;
+
+fn default_dpi() -> f32 {
+    DEFAULT_DPI
+}
+
+/// A print measurement: pixels as a number, or a string with a unit such as `"3mm"`,
+/// `"0.125in"` or `"36px"`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(try_from = "LengthValue", into = "LengthValue")]
+pub enum Length {
+    /// Pixels.
+    Px(f32),
+    /// Millimeters.
+    Mm(f32),
+    /// Inches.
+    In(f32),
+}
+
+impl Length {
+    /// The length in pixels at `dpi` pixels per inch.
+    pub fn to_px(self, dpi: f32) -> f32 {
+        match self {
+            Length::Px(px) => px,
+            Length::Mm(mm) => mm * dpi / 25.4,
+            Length::In(inches) => inches * dpi,
+        }
+    }
+}
+
+// How a length is written in JSON
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum LengthValue {
+    Number(f32),
+    Text(String),
+}
+
+impl TryFrom<LengthValue> for Length {
+    type Error = String;
+
+    fn try_from(value: LengthValue) -> std::result::Result<Self, Self::Error> {
+        let text = match value {
+            LengthValue::Number(px) => return Ok(Length::Px(px)),
+            LengthValue::Text(text) => text,
+        };
+        let number = |unit: &str| text.trim().strip_suffix(unit)?.trim().parse::<f32>().ok();
+        number("mm")
+            .map(Length::Mm)
+            .or_else(|| number("in").map(Length::In))
+            .or_else(|| number("px").map(Length::Px))
+            .ok_or_else(|| format!("invalid length '{}', expected a number of px, mm or in", text))
+    }
+}
+
+impl From<Length> for LengthValue {
+    fn from(length: Length) -> Self {
+        match length {
+            Length::Px(px) => LengthValue::Number(px),
+            Length::Mm(mm) => LengthValue::Text(format!("{}mm", mm)),
+            Length::In(inches) => LengthValue::Text(format!("{}in", inches)),
+        }
+    }
+}
+
+/// ICC rendering intent of the conversion to the print profile.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderingIntent {
+    /// Compress the whole gamut smoothly, keeping the relations between colors. Suits photos.
+    #[default]
+    Perceptual,
+    /// Keep colors inside the gamut exact and clip the others, relative to the paper white.
+    /// Suits brand colors.
+    RelativeColorimetric,
+    /// Keep colors vivid at the expense of accuracy. Suits charts and graphics.
+    Saturation,
+    /// Like relative colorimetric, but simulating the sRGB white point on the paper.
+    AbsoluteColorimetric,
+}
+
+/// File format of print output.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrintFormat {
+    /// Deflate-compressed TIFF with the profile embedded.
+    Tiff,
+    /// Single page PDF holding the poster as an image, with the profile as its output intent.
+    Pdf,
+}
+
+// Poster pixels in the color space of the print profile
+struct Separation {
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
+    // 4 for CMYK, 3 for RGB
+    channels: usize,
+}
+
+// Convert the poster sheet to the print profile and encode it. `trim` and `bleed` are
+// the poster's edges on the sheet without and with its bleed, in pixels.
+pub(crate) fn encode(
+    image: &Image,
+    profile: &PrintProfile,
+    icc: &[u8],
+    format: PrintFormat,
+    (trim, bleed): (Rect, Rect),
+) -> Result<Vec<u8>> {
+    if !profile.dpi.is_finite() || profile.dpi <= 0.0 {
+        return Err(PosterError::OutputError(format!("Print resolution {} dpi is not positive", profile.dpi)));
+    }
+    let (pixels, channels) = convert(&flatten(image)?, profile, icc)?;
+    let separation = Separation { pixels, width: image.width() as u32, height: image.height() as u32, channels };
+    match format {
+        PrintFormat::Tiff => encode_tiff(&separation, icc, profile.dpi),
+        PrintFormat::Pdf => encode_pdf(&separation, icc, profile.dpi, (trim, bleed)),
+    }
+}
+
+// RGB pixels of the poster on white paper
+fn flatten(image: &Image) -> Result<Vec<u8>> {
+    let info = ImageInfo::new(image.dimensions(), ColorType::RGBA8888, AlphaType::Premul, None);
+    let mut surface = surfaces::raster(&info, None, None)
+        .ok_or_else(|| PosterError::OutputError("Failed to create print surface".to_string()))?;
+    surface.canvas().clear(Color::WHITE);
+    surface.canvas().draw_image(image, (0.0, 0.0), None);
+
+    let row_bytes = info.min_row_bytes();
+    let mut rgba = vec![0u8; row_bytes * info.height() as usize];
+    if !surface.read_pixels(&info, &mut rgba, row_bytes, (0, 0)) {
+        return Err(PosterError::OutputError("Failed to read the poster pixels".to_string()));
+    }
+    Ok(rgba.chunks_exact(4).flat_map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect())
+}
+
+// Convert RGB pixels to the print profile, returning the pixels and their number of channels
+#[cfg(feature = "print")]
+fn convert(rgb: &[u8], profile: &PrintProfile, icc: &[u8]) -> Result<(Vec<u8>, usize)> {
+    use lcms2::{ColorSpaceSignature, Flags, Intent, PixelFormat, Profile, Transform};
+
+    let target = Profile::new_icc(icc).map_err(|e| PosterError::OutputError(format!("Invalid ICC profile: {}", e)))?;
+    let intent = match profile.intent {
+        RenderingIntent::Perceptual => Intent::Perceptual,
+        RenderingIntent::RelativeColorimetric => Intent::RelativeColorimetric,
+        RenderingIntent::Saturation => Intent::Saturation,
+        RenderingIntent::AbsoluteColorimetric => Intent::AbsoluteColorimetric,
+    };
+    let flags = if profile.black_point_compensation { Flags::BLACKPOINT_COMPENSATION } else { Flags::default() };
+    let transform_error =
+        |e: lcms2::Error| PosterError::OutputError(format!("Failed to convert to the print profile: {}", e));
+
+    let source: Vec<[u8; 3]> = rgb.chunks_exact(3).map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect();
+    let srgb = Profile::new_srgb();
+    match target.color_space() {
+        ColorSpaceSignature::CmykData => {
+            let transform = Transform::new_flags(&srgb, PixelFormat::RGB_8, &target, PixelFormat::CMYK_8, intent, flags)
+                .map_err(transform_error)?;
+            let mut cmyk = vec![[0u8; 4]; source.len()];
+            transform.transform_pixels(&source, &mut cmyk);
+            Ok((cmyk.into_iter().flatten().collect(), 4))
+        }
+        ColorSpaceSignature::RgbData => {
+            let transform = Transform::new_flags(&srgb, PixelFormat::RGB_8, &target, PixelFormat::RGB_8, intent, flags)
+                .map_err(transform_error)?;
+            let mut converted = vec![[0u8; 3]; source.len()];
+            transform.transform_pixels(&source, &mut converted);
+            Ok((converted.into_iter().flatten().collect(), 3))
+        }
+        other => Err(PosterError::OutputError(format!("Print profiles must be CMYK or RGB, not {:?}", other))),
+    }
+}
+
+#[cfg(not(feature = "print"))]
+fn convert(_rgb: &[u8], _profile: &PrintProfile, _icc: &[u8]) -> Result<(Vec<u8>, usize)> {
+    Err(PosterError::OutputError("Print output needs the `print` feature".to_string()))
+}
+
+#[cfg(feature = "print")]
+fn deflate(bytes: &[u8]) -> Result<Vec<u8>> {
+    use flate2::{write::ZlibEncoder, Compression};
+    use std::io::Write;
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    Ok(encoder.finish()?)
+}
+
+#[cfg(not(feature = "print"))]
+fn deflate(_bytes: &[u8]) -> Result<Vec<u8>> {
+    Err(PosterError::OutputError("Print output needs the `print` feature".to_string()))
+}
+
+// TIFF field types
+const SHORT: u16 = 3;
+const LONG: u16 = 4;
+const RATIONAL: u16 = 5;
+const UNDEFINED: u16 = 7;
+
+// A little-endian TIFF with one Deflate-compressed strip
+fn encode_tiff(separation: &Separation, icc: &[u8], dpi: f32) -> Result<Vec<u8>> {
+    let strip = deflate(&separation.pixels)?;
+    let channels = separation.channels as u32;
+
+    // Header, with the offset of the directory filled in once the values before it are written
+    let mut tiff = b"II*\0\0\0\0\0".to_vec();
+    // Values larger than 4 bytes are stored outside the directory, at even offsets
+    let append = |tiff: &mut Vec<u8>, bytes: &[u8]| {
+        if tiff.len() % 2 == 1 {
+            tiff.push(0);
+        }
+        let offset = tiff.len() as u32;
+        tiff.extend_from_slice(bytes);
+        offset
+    };
+    let strip_offset = append(&mut tiff, &strip);
+    let bits: Vec<u8> = (0..channels).flat_map(|_| 8u16.to_le_bytes()).collect();
+    let bits_offset = append(&mut tiff, &bits);
+    let resolution: Vec<u8> = [(dpi * 100.0).round() as u32, 100].into_iter().flat_map(u32::to_le_bytes).collect();
+    let resolution_offset = append(&mut tiff, &resolution);
+    let icc_offset = append(&mut tiff, icc);
+
+    // (tag, type, count, value or offset), in tag order. Short values sit in the low bytes.
+    let entries: [(u16, u16, u32, u32); 14] = [
+        (256, LONG, 1, separation.width),
+        (257, LONG, 1, separation.height),
+        (258, SHORT, channels, bits_offset),
+        // Adobe Deflate
+        (259, SHORT, 1, 8),
+        // RGB or separated (CMYK)
+        (262, SHORT, 1, if channels == 4 { 5 } else { 2 }),
+        (273, LONG, 1, strip_offset),
+        (277, SHORT, 1, channels),
+        (278, LONG, 1, separation.height),
+        (279, LONG, 1, strip.len() as u32),
+        (282, RATIONAL, 1, resolution_offset),
+        (283, RATIONAL, 1, resolution_offset),
+        (284, SHORT, 1, 1),
+        // Inches
+        (296, SHORT, 1, 2),
+        (34675, UNDEFINED, icc.len() as u32, icc_offset),
+    ];
+
+    if tiff.len() % 2 == 1 {
+        tiff.push(0);
+    }
+    let directory_offset = tiff.len() as u32;
+    tiff[4..8].copy_from_slice(&directory_offset.to_le_bytes());
+    tiff.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for (tag, kind, count, value) in entries {
+        tiff.extend_from_slice(&tag.to_le_bytes());
+        tiff.extend_from_slice(&kind.to_le_bytes());
+        tiff.extend_from_slice(&count.to_le_bytes());
+        tiff.extend_from_slice(&value.to_le_bytes());
+    }
+    // No further directories
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    Ok(tiff)
+}
+
+// A single page PDF showing the poster as an image in the profile's color space,
+// with the profile as the document's output intent
+fn encode_pdf(separation: &Separation, icc: &[u8], dpi: f32, (trim, bleed): (Rect, Rect)) -> Result<Vec<u8>> {
+    let image = deflate(&separation.pixels)?;
+    let profile = deflate(icc)?;
+    // Page size in points
+    let (width, height) = (separation.width as f32 * 72.0 / dpi, separation.height as f32 * 72.0 / dpi);
+    // Boxes in points, from the bottom-left corner of the page
+    let page_box = |rect: Rect| {
+        let scale = 72.0 / dpi;
+        format!(
+            "[{:.3} {:.3} {:.3} {:.3}]",
+            rect.left * scale,
+            height - rect.bottom * scale,
+            rect.right * scale,
+            height - rect.top * scale
+        )
+    };
+    let content = format!("q {:.3} 0 0 {:.3} 0 0 cm /Poster Do Q", width, height);
+
+    let objects: Vec<(String, Option<&[u8]>)> = vec![
+        (
+            "<< /Type /Catalog /Pages 2 0 R /OutputIntents [<< /Type /OutputIntent /S /GTS_PDFX \
+             /OutputConditionIdentifier (Custom) /DestOutputProfile 5 0 R >>] >>"
+                .to_string(),
+            None,
+        ),
+        ("<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(), None),
+        (
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.3} {:.3}] /BleedBox {} /TrimBox {} \
+                 /Resources << /XObject << /Poster 4 0 R >> >> /Contents 6 0 R >>",
+                width,
+                height,
+                page_box(bleed),
+                page_box(trim)
+            ),
+            None,
+        ),
+        (
+            format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace [/ICCBased 5 0 R] \
+                 /BitsPerComponent 8 /Filter /FlateDecode /Length {} >>",
+                separation.width,
+                separation.height,
+                image.len()
+            ),
+            Some(image.as_slice()),
+        ),
+        (
+            format!("<< /N {} /Filter /FlateDecode /Length {} >>", separation.channels, profile.len()),
+            Some(profile.as_slice()),
+        ),
+        (format!("<< /Length {} >>", content.len()), Some(content.as_bytes())),
+    ];
+
+    let mut pdf = b"%PDF-1.6\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (number, (dictionary, stream)) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\n", number + 1, dictionary).as_bytes());
+        if let Some(stream) = stream {
+            pdf.extend_from_slice(b"stream\n");
+            pdf.extend_from_slice(stream);
+            pdf.extend_from_slice(b"\nendstream\n");
+        }
+        pdf.extend_from_slice(b"endobj\n");
+    }
+
+    let xref_offset = pdf.len();
+    let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(xref, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(xref, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref_offset);
+    pdf.extend_from_slice(xref.as_bytes());
+    Ok(pdf)
+}
@@ -0,0 +1,123 @@
+//! Render timing, enabled with [`PosterGenerator::set_profiling`](crate::PosterGenerator::set_profiling).
+//!
+//! Each element's render time is split into image decoding, layout (font
+//! loading and line breaking) and drawing, which is whatever remains.
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// Time spent rendering one poster, in milliseconds.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct RenderProfile {
+    /// Whole render, including encoding.
+    pub total_ms: f64,
+    /// Drawing the watermark.
+    pub watermark_ms: f64,
+    /// Encoding the PNG.
+    pub encode_ms: f64,
+    /// Per-element times, in the order the elements were rendered.
+    pub elements: Vec<ElementProfile>,
+}
+
+impl RenderProfile {
+    /// The element that took longest to render.
+    pub fn slowest(&self) -> Option<&ElementProfile> {
+        self.elements.iter().max_by(|a, b| a.total_ms.total_cmp(&b.total_ms))
+    }
+}
+
+/// Time spent rendering one element, in milliseconds.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct ElementProfile {
+    /// Position of the element in the order it was added.
+    pub index: usize,
+    /// The element's `id`, if it has one.
+    pub id: Option<String>,
+    /// Loading and decoding images.
+    pub decode_ms: f64,
+    /// Loading fonts and breaking text into lines.
+    pub layout_ms: f64,
+    /// Everything else, mostly drawing.
+    pub draw_ms: f64,
+    /// Whole element.
+    pub total_ms: f64,
+}
+
+impl ElementProfile {
+    pub(crate) fn new(index: usize, id: Option<&str>, timings: PhaseTimings, total: Duration) -> Self {
+        Self {
+            index,
+            id: id.map(str::to_string),
+            decode_ms: ms(timings.decode),
+            layout_ms: ms(timings.layout),
+            draw_ms: ms(total.saturating_sub(timings.decode + timings.layout)),
+            total_ms: ms(total),
+        }
+    }
+}
+
+/// Converts a duration to fractional milliseconds.
+pub(crate) fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// Phases timed inside an element's render
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Phase {
+    Decode,
+    Layout,
+}
+
+// Time spent in each phase by the element being rendered
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PhaseTimings {
+    decode: Duration,
+    layout: Duration,
+    // Number of timers running; nested timers are not counted twice
+    depth: u32,
+}
+
+impl PhaseTimings {
+    // Clear the times before the next element
+    pub(crate) fn take(&mut self) -> PhaseTimings {
+        std::mem::take(self)
+    }
+}
+
+/// Adds the time until it is dropped to a phase, unless profiling is off.
+pub(crate) struct PhaseTimer<'a> {
+    timings: Option<&'a RefCell<PhaseTimings>>,
+    phase: Phase,
+    // Only set when profiling, so no clock is read otherwise
+    start: Option<Instant>,
+    outermost: bool,
+}
+
+impl<'a> PhaseTimer<'a> {
+    pub(crate) fn start(timings: Option<&'a RefCell<PhaseTimings>>, phase: Phase) -> Self {
+        let outermost = timings.is_some_and(|timings| {
+            let mut timings = timings.borrow_mut();
+            timings.depth += 1;
+            timings.depth == 1
+        });
+        Self { timings, phase, start: timings.map(|_| Instant::now()), outermost }
+    }
+}
+
+impl Drop for PhaseTimer<'_> {
+    fn drop(&mut self) {
+        let (Some(timings), Some(start)) = (self.timings, self.start) else {
+            return;
+        };
+        let mut timings = timings.borrow_mut();
+        timings.depth = timings.depth.saturating_sub(1);
+        if self.outermost {
+            let elapsed = start.elapsed();
+            match self.phase {
+                Phase::Decode => timings.decode += elapsed,
+                Phase::Layout => timings.layout += elapsed,
+            }
+        }
+    }
+}
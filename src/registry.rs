@@ -0,0 +1,230 @@
+//! Registry for custom element types in JSON configs.
+//!
+//! Config elements whose `"type"` is not one of the built-in kinds are parsed as
+//! [`CustomElement`]s and resolved through this registry when they are added to a
+//! [`PosterGenerator`](crate::PosterGenerator). Register a type once at startup, before
+//! loading posters:
+//!
+//! ```
+//! use poster_generator::{registry, PosterConfig, PosterElement, PosterError, PosterGenerator, RenderContext};
+//! use poster_generator::skia_safe::{Canvas, Color, Paint};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Dot {
+//!     x: f32,
+//!     y: f32,
+//!     radius: f32,
+//! }
+//!
+//! impl PosterElement for Dot {
+//!     fn z_index(&self) -> i32 {
+//!         1
+//!     }
+//!
+//!     fn render(&self, canvas: &Canvas, _ctx: &RenderContext) -> Result<(), PosterError> {
+//!         let mut paint = Paint::default();
+//!         paint.set_color(Color::RED);
+//!         canvas.draw_circle((self.x, self.y), self.radius, &paint);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! registry::register_element::<Dot>("dot");
+//!
+//! let config: PosterConfig = serde_json::from_str(r##"{
+//!     "width": 200, "height": 200, "background_color": "#ffffff",
+//!     "elements": [{ "type": "dot", "x": 100, "y": 100, "radius": 10 }]
+//! }"##).unwrap();
+//!
+//! let generator = PosterGenerator::from_config(config);
+//! generator.generate().unwrap();
+//! ```
+
+use crate::{
+    BackdropBlurElement, BackgroundElement, BadgeElement, BarChartElement, CircleElement, GenerativeElement, IconElement,
+    ImageElement, LineElement, NoiseElement, PathElement, PieChartElement, PixelateElement, PosterElement, PosterError,
+    RectElement, Element, RenderContext, Result, SparklineElement, TextContent, TextElement,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use skia_safe::{Canvas, Rect};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Builds a renderable element from the JSON object of a custom config element.
+pub type ElementFactory = dyn Fn(&Value) -> Result<Box<dyn PosterElement>> + Send + Sync;
+
+// Element types that are handled by the `Element` enum itself
+const BUILTIN_TYPES: &[&str] = &[
+    "background", "image", "text", "backdrop_blur", "pie_chart", "bar_chart", "sparkline", "badge", "icon",
+    "line", "rect", "circle", "path", "noise", "pixelate", "generative",
+];
+
+fn registry() -> &'static RwLock<HashMap<String, Arc<ElementFactory>>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, Arc<ElementFactory>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Registers a custom element type that is deserialized directly from its config object.
+///
+/// The `"type"` key is included in the object passed to the deserializer, so types
+/// should not deny unknown fields.
+pub fn register_element<T>(type_name: &str)
+where
+    T: DeserializeOwned + PosterElement + 'static,
+{
+    register_element_factory(type_name, |value| {
+        let element: T = serde_json::from_value(value.clone())?;
+        Ok(Box::new(element))
+    });
+}
+
+/// Registers a factory for a custom element type.
+///
+/// Registering the same type name again replaces the previous factory.
+///
+/// # Panics
+///
+/// Panics if `type_name` is one of the built-in element types.
+pub fn register_element_factory<F>(type_name: &str, factory: F)
+where
+    F: Fn(&Value) -> Result<Box<dyn PosterElement>> + Send + Sync + 'static,
+{
+    assert!(
+        !BUILTIN_TYPES.contains(&type_name),
+        "cannot register built-in element type '{}'",
+        type_name
+    );
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(type_name.to_string(), Arc::new(factory));
+}
+
+/// Returns `true` if a custom element type is registered under this name.
+pub fn is_registered(type_name: &str) -> bool {
+    registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains_key(type_name)
+}
+
+/// A config element whose type is resolved through the registry.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CustomElement {
+    /// The element's `"type"` string.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// All other properties of the element.
+    #[serde(flatten)]
+    pub properties: Map<String, Value>,
+}
+
+impl CustomElement {
+    /// Builds the renderable element using the registered factory for its type.
+    pub fn resolve(&self) -> Result<Box<dyn PosterElement>> {
+        let mut value = Value::Object(self.properties.clone());
+        value["type"] = Value::String(self.kind.clone());
+
+        // Built-in elements only end up here when they failed to parse, so
+        // parse them again to report the real error
+        // (parsing as `Element` would just fall back to the custom variant again)
+        let builtin_error = match self.kind.as_str() {
+            "background" => Some(serde_json::from_value::<BackgroundElement>(value.clone()).err()),
+            "image" => Some(serde_json::from_value::<ImageElement>(value.clone()).err()),
+            "text" => Some(serde_json::from_value::<TextElement>(value.clone()).err()),
+            "backdrop_blur" => Some(serde_json::from_value::<BackdropBlurElement>(value.clone()).err()),
+            "pie_chart" => Some(serde_json::from_value::<PieChartElement>(value.clone()).err()),
+            "bar_chart" => Some(serde_json::from_value::<BarChartElement>(value.clone()).err()),
+            "sparkline" => Some(serde_json::from_value::<SparklineElement>(value.clone()).err()),
+            "badge" => Some(serde_json::from_value::<BadgeElement>(value.clone()).err()),
+            "icon" => Some(serde_json::from_value::<IconElement>(value.clone()).err()),
+            "line" => Some(serde_json::from_value::<LineElement>(value.clone()).err()),
+            "rect" => Some(serde_json::from_value::<RectElement>(value.clone()).err()),
+            "circle" => Some(serde_json::from_value::<CircleElement>(value.clone()).err()),
+            "path" => Some(serde_json::from_value::<PathElement>(value.clone()).err()),
+            "noise" => Some(serde_json::from_value::<NoiseElement>(value.clone()).err()),
+            "pixelate" => Some(serde_json::from_value::<PixelateElement>(value.clone()).err()),
+            "generative" => Some(serde_json::from_value::<GenerativeElement>(value.clone()).err()),
+            _ => None,
+        };
+        if let Some(error) = builtin_error {
+            return Err(match error {
+                Some(err) => PosterError::InvalidConfig(err),
+                None => PosterError::RenderError(format!("unexpected custom {} element", self.kind)),
+            });
+        }
+
+        let factory = registry()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&self.kind)
+            .cloned()
+            .ok_or_else(|| PosterError::UnknownElementType(self.kind.clone()))?;
+        factory(&value)
+    }
+
+    // The element as a drawable poster element, resolved once rather than on every call
+    pub(crate) fn into_boxed(self) -> Box<dyn PosterElement> {
+        let element = self.resolve();
+        Box::new(ResolvedElement { config: self, element })
+    }
+
+    // The `z_index` property, for elements that failed to resolve
+    fn configured_z_index(&self) -> i32 {
+        self.properties
+            .get("z_index")
+            .and_then(Value::as_i64)
+            .and_then(|z| i32::try_from(z).ok())
+            .unwrap_or(0)
+    }
+}
+
+// A custom element together with the element its type resolved to
+struct ResolvedElement {
+    config: CustomElement,
+    element: Result<Box<dyn PosterElement>>,
+}
+
+impl PosterElement for ResolvedElement {
+    fn z_index(&self) -> i32 {
+        match &self.element {
+            Ok(element) => element.z_index(),
+            Err(_) => self.config.configured_z_index(),
+        }
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        match &self.element {
+            Ok(element) => element.render(canvas, ctx),
+            // `PosterError` isn't `Clone`, so resolve again to report the error
+            Err(_) => self.config.resolve()?.render(canvas, ctx),
+        }
+    }
+
+    fn bounds(&self, ctx: &RenderContext) -> Option<Rect> {
+        self.element.as_ref().ok()?.bounds(ctx)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.config.properties.get("id").and_then(Value::as_str)
+    }
+
+    fn text_content(&self) -> Option<TextContent> {
+        self.element.as_ref().ok()?.text_content()
+    }
+
+    fn baseline(&self, ctx: &RenderContext) -> Option<f32> {
+        self.element.as_ref().ok()?.baseline(ctx)
+    }
+
+    fn overflows(&self, ctx: &RenderContext) -> bool {
+        self.element.as_ref().is_ok_and(|element| element.overflows(ctx))
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Custom(self.config.clone()))
+    }
+}
@@ -0,0 +1,412 @@
+//! Vector shape elements: lines, rectangles, circles and SVG paths.
+//!
+//! All shapes share the fill and stroke options of [`ShapeStyle`], which is
+//! flattened into each element, so JSON configs write them inline:
+//!
+//! ```json
+//! { "type": "rect", "x": 40, "y": 40, "width": 320, "height": 160, "radius": 12,
+//!   "stroke": "#e53935", "stroke_width": 2, "stroke_dash": [8, 4] }
+//! ```
+
+use crate::{
+    create_smooth_rect_path, parse_color, ColorAdjustments, Element, Gradient, Pattern, PosterElement,
+    PosterError, Radius, RenderContext, Result,
+};
+use serde::{Deserialize, Serialize};
+use skia_safe::{
+    paint::{Cap as PaintCap, Join as PaintJoin, Style as PaintStyle},
+    Canvas, Color, Paint, Path as SkPath, PathEffect, Rect,
+};
+
+/// Fill and stroke options shared by all shapes.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ShapeStyle {
+    /// Optional fill color in hex format.
+    pub fill: Option<String>,
+    /// Optional gradient fill. Takes priority over `fill`.
+    #[serde(default)]
+    pub fill_gradient: Option<Gradient>,
+    /// Optional pattern fill (stripes, dots or checker). Takes priority over `fill`
+    /// and `fill_gradient`.
+    #[serde(default)]
+    pub fill_pattern: Option<Pattern>,
+    /// Optional stroke color in hex format.
+    pub stroke: Option<String>,
+    /// Optional gradient stroke. Takes priority over `stroke`.
+    #[serde(default)]
+    pub stroke_gradient: Option<Gradient>,
+    /// Stroke width in pixels.
+    #[serde(default = "default_stroke_width")]
+    pub stroke_width: f32,
+    /// Optional dash pattern as alternating on and off lengths, like SVG `stroke-dasharray`.
+    /// A list with an odd number of entries is repeated to make it even.
+    #[serde(default)]
+    pub stroke_dash: Option<Vec<f32>>,
+    /// Shape of the stroke ends, including the ends of each dash.
+    #[serde(default)]
+    pub stroke_cap: StrokeCap,
+    /// Shape of the stroke corners.
+    #[serde(default)]
+    pub stroke_join: StrokeJoin,
+    /// Color adjustments applied to the fill and stroke, e.g. `hue_rotate` to recolor a gradient.
+    #[serde(flatten)]
+    pub adjustments: ColorAdjustments,
+}
+
+impl Default for ShapeStyle {
+    fn default() -> Self {
+        Self {
+            fill: None,
+            fill_gradient: None,
+            fill_pattern: None,
+            stroke: None,
+            stroke_gradient: None,
+            stroke_width: default_stroke_width(),
+            stroke_dash: None,
+            stroke_cap: StrokeCap::default(),
+            stroke_join: StrokeJoin::default(),
+            adjustments: ColorAdjustments::default(),
+        }
+    }
+}
+
+/// Shape of stroke ends.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StrokeCap {
+    /// The stroke stops exactly at the end point.
+    #[default]
+    Butt,
+    /// The stroke ends in a half circle. Short dashes become dots.
+    Round,
+    /// The stroke extends past the end point by half its width.
+    Square,
+}
+
+/// Shape of stroke corners.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StrokeJoin {
+    /// Sharp corners.
+    #[default]
+    Miter,
+    /// Rounded corners.
+    Round,
+    /// Cut-off corners.
+    Bevel,
+}
+
+/// Straight line configuration.
+///
+/// Lines are drawn with the stroke options only, in black if `stroke` is not set.
+///
+/// # Example
+///
+/// ```
+/// use poster_generator::{PosterGenerator, LineElement, ShapeStyle, StrokeCap};
+///
+/// // A dotted "cut here" line
+/// let mut generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+/// generator.add_line(LineElement {
+///     x1: 40.0,
+///     y1: 300.0,
+///     x2: 760.0,
+///     y2: 300.0,
+///     style: ShapeStyle {
+///         stroke: Some("#999999".to_string()),
+///         stroke_width: 3.0,
+///         stroke_dash: Some(vec![0.0, 8.0]),
+///         stroke_cap: StrokeCap::Round,
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct LineElement {
+    /// X-coordinate of the start point.
+    pub x1: f32,
+    /// Y-coordinate of the start point.
+    pub y1: f32,
+    /// X-coordinate of the end point.
+    pub x2: f32,
+    /// Y-coordinate of the end point.
+    pub y2: f32,
+    /// Stroke options.
+    #[serde(flatten)]
+    pub style: ShapeStyle,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+/// Rectangle configuration.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct RectElement {
+    /// X-coordinate of the rectangle (top-left corner).
+    pub x: f32,
+    /// Y-coordinate of the rectangle (top-left corner).
+    pub y: f32,
+    /// Width of the rectangle.
+    pub width: f32,
+    /// Height of the rectangle.
+    pub height: f32,
+    /// Optional border radius for rounded corners.
+    pub radius: Option<Radius>,
+    /// Smoothing of the rounded corners, from 0 for circular arcs to 1 for corners that
+    /// blend into the sides like iOS app icons (a squircle).
+    #[serde(default)]
+    pub corner_smoothing: f32,
+    /// Fill and stroke options.
+    #[serde(flatten)]
+    pub style: ShapeStyle,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+/// Circle configuration.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct CircleElement {
+    /// X-coordinate of the center.
+    pub x: f32,
+    /// Y-coordinate of the center.
+    pub y: f32,
+    /// Radius of the circle.
+    pub radius: f32,
+    /// Fill and stroke options.
+    #[serde(flatten)]
+    pub style: ShapeStyle,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+/// SVG path configuration.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct PathElement {
+    /// SVG path data (the `d` attribute).
+    pub d: String,
+    /// Horizontal offset applied to the path.
+    #[serde(default)]
+    pub x: f32,
+    /// Vertical offset applied to the path.
+    #[serde(default)]
+    pub y: f32,
+    /// Fill and stroke options.
+    #[serde(flatten)]
+    pub style: ShapeStyle,
+    /// Identifier reported in the render metadata.
+    pub id: Option<String>,
+    /// Z-index for layering.
+    pub z_index: Option<i32>,
+}
+
+fn default_stroke_width() -> f32 {
+    1.0
+}
+
+impl PosterElement for LineElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Line(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let mut path = SkPath::new();
+        path.move_to((self.x1, self.y1));
+        path.line_to((self.x2, self.y2));
+
+        let mut paint = stroke_paint(&self.style, *path.bounds(), ctx)?;
+        if self.style.stroke.is_none() && self.style.stroke_gradient.is_none() {
+            paint.set_color(Color::BLACK);
+        }
+        canvas.draw_path(&path, &paint);
+        Ok(())
+    }
+
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        // Lines are always stroked
+        let outset = self.style.stroke_width / 2.0;
+        Some(Rect::new(self.x1, self.y1, self.x2, self.y2).sorted().with_outset((outset, outset)))
+    }
+}
+
+impl PosterElement for RectElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Rect(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let path = match &self.radius {
+            Some(radius) => create_smooth_rect_path(self.x, self.y, self.width, self.height, radius, self.corner_smoothing),
+            None => SkPath::rect(Rect::from_xywh(self.x, self.y, self.width, self.height), None),
+        };
+        draw_shape(canvas, &path, &self.style, ctx)
+    }
+
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        Some(stroke_bounds(Rect::from_xywh(self.x, self.y, self.width, self.height), &self.style))
+    }
+}
+
+impl PosterElement for CircleElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Circle(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        let mut path = SkPath::new();
+        path.add_circle((self.x, self.y), self.radius, None);
+        draw_shape(canvas, &path, &self.style, ctx)
+    }
+
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        let rect = Rect::from_xywh(self.x - self.radius, self.y - self.radius, self.radius * 2.0, self.radius * 2.0);
+        Some(stroke_bounds(rect, &self.style))
+    }
+}
+
+impl PathElement {
+    fn path(&self) -> Result<SkPath> {
+        let mut path = SkPath::from_svg(&self.d)
+            .ok_or_else(|| PosterError::RenderError(format!("Invalid SVG path '{}'", self.d)))?;
+        path.offset((self.x, self.y));
+        Ok(path)
+    }
+}
+
+impl PosterElement for PathElement {
+    fn z_index(&self) -> i32 {
+        self.z_index.unwrap_or(0)
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn to_element(&self) -> Option<Element> {
+        Some(Element::Path(self.clone()))
+    }
+
+    fn render(&self, canvas: &Canvas, ctx: &RenderContext) -> Result<()> {
+        draw_shape(canvas, &self.path()?, &self.style, ctx)
+    }
+
+    fn bounds(&self, _ctx: &RenderContext) -> Option<Rect> {
+        self.path().ok().map(|path| stroke_bounds(*path.bounds(), &self.style))
+    }
+}
+
+// Fill, then stroke, a shape path
+fn draw_shape(canvas: &Canvas, path: &SkPath, style: &ShapeStyle, ctx: &RenderContext) -> Result<()> {
+    let bounds = *path.bounds();
+
+    let fill = match (&style.fill_pattern, &style.fill_gradient, &style.fill) {
+        (Some(pattern), _, _) => {
+            let mut paint = Paint::default();
+            paint.set_shader(pattern.shader(bounds)?);
+            Some(paint)
+        }
+        (None, Some(gradient), _) => {
+            let mut paint = Paint::default();
+            paint.set_shader(gradient.shader(bounds)?);
+            paint.set_dither(ctx.dither());
+            Some(paint)
+        }
+        (None, None, Some(color)) => {
+            let mut paint = Paint::default();
+            paint.set_color(parse_color(color, "fill")?);
+            Some(paint)
+        }
+        (None, None, None) => None,
+    };
+    if let Some(mut paint) = fill {
+        paint.set_anti_alias(true);
+        paint.set_color_filter(style.adjustments.color_filter());
+        canvas.draw_path(path, &paint);
+    }
+
+    if style.stroke.is_some() || style.stroke_gradient.is_some() {
+        canvas.draw_path(path, &stroke_paint(style, bounds, ctx)?);
+    }
+    Ok(())
+}
+
+// Stroke paint with color, width, dash pattern, caps and joins applied
+fn stroke_paint(style: &ShapeStyle, bounds: Rect, ctx: &RenderContext) -> Result<Paint> {
+    let mut paint = Paint::default();
+    match (&style.stroke_gradient, &style.stroke) {
+        (Some(gradient), _) => {
+            paint.set_shader(gradient.shader(bounds)?);
+            paint.set_dither(ctx.dither());
+        }
+        (None, Some(color)) => {
+            paint.set_color(parse_color(color, "stroke")?);
+        }
+        (None, None) => {}
+    }
+    paint.set_anti_alias(true);
+    paint.set_color_filter(style.adjustments.color_filter());
+    paint.set_style(PaintStyle::Stroke);
+    paint.set_stroke_width(style.stroke_width);
+    paint.set_stroke_cap(match style.stroke_cap {
+        StrokeCap::Butt => PaintCap::Butt,
+        StrokeCap::Round => PaintCap::Round,
+        StrokeCap::Square => PaintCap::Square,
+    });
+    paint.set_stroke_join(match style.stroke_join {
+        StrokeJoin::Miter => PaintJoin::Miter,
+        StrokeJoin::Round => PaintJoin::Round,
+        StrokeJoin::Bevel => PaintJoin::Bevel,
+    });
+
+    if let Some(dash) = &style.stroke_dash {
+        if dash.iter().any(|length| *length < 0.0) || dash.iter().sum::<f32>() <= 0.0 {
+            return Err(PosterError::RenderError(format!("Invalid stroke_dash {:?}", dash)));
+        }
+        // Skia needs an even number of intervals; repeat odd lists like SVG does
+        let mut intervals = dash.clone();
+        if intervals.len() % 2 == 1 {
+            intervals.extend_from_within(..);
+        }
+        paint.set_path_effect(PathEffect::dash(&intervals, 0.0));
+    }
+
+    Ok(paint)
+}
+
+// Grow a shape's bounds by half the stroke width when it is stroked
+fn stroke_bounds(rect: Rect, style: &ShapeStyle) -> Rect {
+    if style.stroke.is_some() || style.stroke_gradient.is_some() {
+        rect.with_outset((style.stroke_width / 2.0, style.stroke_width / 2.0))
+    } else {
+        rect
+    }
+}
@@ -0,0 +1,107 @@
+//! Text shaping with rustybuzz, for fonts Skia's paragraph layout doesn't shape.
+//!
+//! Skia's paragraph module looks fonts up by family name in the system font
+//! manager, so fonts loaded from a `font_file` (such as many custom Uyghur fonts)
+//! are replaced by a system font and lose their ligatures and contextual forms.
+//! Shaping with rustybuzz uses the typeface's own font data instead, and gives
+//! the same glyphs on every platform. Enabled by the `rustybuzz` feature.
+
+use crate::{TextAlignType, TextDirectionType};
+use skia_safe::{Canvas, Font, Paint};
+
+/// Draws a line of text shaped by rustybuzz, with `x` as the alignment anchor and
+/// `y` as the baseline. Returns false if the line couldn't be shaped, in which
+/// case nothing is drawn.
+pub(crate) fn draw_shaped_line(
+    canvas: &Canvas,
+    text: &str,
+    x: f32,
+    y: f32,
+    font: &Font,
+    paint: &Paint,
+    direction: &TextDirectionType,
+    align: &TextAlignType,
+    features: &[String],
+) -> bool {
+    let Some((blob, width)) = shape(text, font, direction, features) else {
+        return false;
+    };
+    let draw_x = match align {
+        TextAlignType::Left => x,
+        TextAlignType::Right => x - width,
+        TextAlignType::Center => x - width / 2.0,
+    };
+    canvas.draw_text_blob(blob, (draw_x, y), paint);
+    true
+}
+
+// Shape the text into a blob of positioned glyphs starting at the origin, with its advance width
+#[cfg(feature = "rustybuzz")]
+fn shape(
+    text: &str,
+    font: &Font,
+    direction: &TextDirectionType,
+    features: &[String],
+) -> Option<(skia_safe::TextBlob, f32)> {
+    use rustybuzz::{Direction, Feature, UnicodeBuffer};
+    use skia_safe::{Point, TextBlobBuilder};
+    use std::str::FromStr;
+
+    let (data, index) = font.typeface().to_font_data()?;
+    let Some(face) = rustybuzz::Face::from_slice(&data, index as u32) else {
+        tracing::warn!("rustybuzz can't read font '{}'", font.typeface().family_name());
+        return None;
+    };
+
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.guess_segment_properties();
+    if matches!(direction, TextDirectionType::Rtl) {
+        buffer.set_direction(Direction::RightToLeft);
+    }
+    // Same syntax as HarfBuzz: "tnum", "-liga", "salt=2"
+    let features: Vec<Feature> = features
+        .iter()
+        .filter_map(|feature| {
+            Feature::from_str(feature)
+                .map_err(|_| tracing::warn!("Ignoring invalid font feature '{}'", feature))
+                .ok()
+        })
+        .collect();
+    let glyphs = rustybuzz::shape(&face, &features, buffer);
+    if glyphs.is_empty() {
+        return None;
+    }
+
+    // Glyphs come out in visual order, left to right, in font units
+    let scale = font.size() / face.units_per_em() as f32;
+    let mut builder = TextBlobBuilder::new();
+    let (ids, points) = builder.alloc_run_pos(font, glyphs.len(), None);
+    let mut pen = Point::new(0.0, 0.0);
+    for ((id, point), (info, position)) in ids
+        .iter_mut()
+        .zip(points.iter_mut())
+        .zip(glyphs.glyph_infos().iter().zip(glyphs.glyph_positions()))
+    {
+        *id = info.glyph_id as u16;
+        // Font units point up, canvas units point down
+        *point = Point::new(
+            pen.x + position.x_offset as f32 * scale,
+            pen.y - position.y_offset as f32 * scale,
+        );
+        pen.x += position.x_advance as f32 * scale;
+        pen.y -= position.y_advance as f32 * scale;
+    }
+    Some((builder.make()?, pen.x))
+}
+
+#[cfg(not(feature = "rustybuzz"))]
+fn shape(
+    _text: &str,
+    _font: &Font,
+    _direction: &TextDirectionType,
+    _features: &[String],
+) -> Option<(skia_safe::TextBlob, f32)> {
+    tracing::warn!("The `rustybuzz` shaper needs the `rustybuzz` feature");
+    None
+}
@@ -0,0 +1,406 @@
+//! Variable substitution for poster config templates.
+//!
+//! Templates are ordinary poster configs whose string values may contain
+//! `{{name}}` placeholders. Substitution happens on the JSON value before it is
+//! deserialized into a [`PosterConfig`], so a placeholder can appear in any string
+//! field (text, image sources, colors, ...).
+//!
+//! When a string consists of a single placeholder and nothing else, it is replaced
+//! by the variable's JSON value as-is, which lets numeric fields be templated too:
+//!
+//! ```
+//! use poster_generator::template;
+//! use serde_json::json;
+//!
+//! let template = json!({
+//!     "width": 800,
+//!     "height": 600,
+//!     "background_color": "#ffffff",
+//!     "elements": [
+//!         { "type": "text", "text": "Hello, {{name}}!", "x": "{{x}}", "y": 300, "font_size": 48, "color": "#000000" }
+//!     ]
+//! });
+//! let vars = json!({ "name": "Ada", "x": 400 });
+//!
+//! let config = template::render_config(&template, vars.as_object().unwrap()).unwrap();
+//! assert_eq!(config.elements.len(), 1);
+//! ```
+//!
+//! CSV cells are always strings, as CSV has no types. Position and size fields
+//! accept numeric strings, since they may hold [expressions](crate::expr), but
+//! other typed fields such as `opacity`, `z_index`, `max_lines` or `bold` don't:
+//! give their values as JSON numbers and booleans, in a JSONL data file. Cells
+//! aren't guessed to be numbers, as `"text": "{{price}}"` must stay a string.
+//!
+//! # Filters
+//!
+//! A placeholder can pipe its value through filters, e.g. `{{price | currency("CNY")}}`.
+//! Numbers may be given as JSON numbers or numeric strings (as read from CSV files).
+//! Filters taking an optional `locale` use its digit grouping and decimal separator
+//! (e.g. "en", "de", "fr", "en-IN"; default "en").
+//!
+//! | Filter | Result |
+//! |--------|--------|
+//! | `thousands(locale?)` | `1234567.5` → `1,234,567.5` |
+//! | `fixed(digits)` | `3.14159` → `3.14` with `fixed(2)` |
+//! | `percent(digits?)` | `0.256` → `26%` (or `25.6%` with `percent(1)`) |
+//! | `currency(code, locale?)` | `1299` → `¥1,299.00` with `currency("CNY")` |
+//! | `format(pattern, timezone?)` | `"2024-05-01T09:30:00Z"` → `2024-05-01` with `format("%Y-%m-%d")` |
+//!
+//! `format` takes a [strftime pattern](https://docs.rs/chrono/latest/chrono/format/strftime/index.html)
+//! and accepts RFC 3339 timestamps, `YYYY-MM-DD[ HH:MM[:SS]]` strings and Unix timestamps
+//! in seconds. Times with an offset (and Unix timestamps, which are UTC) are converted to the
+//! IANA time zone given as second argument, e.g. `format("%H:%M", "Asia/Shanghai")`.
+//! Dates and times without an offset are formatted as written.
+//! The variable `now` holds the current time, unless the template variables define it.
+//!
+//! A placeholder naming an unknown filter, or a value a filter can't handle, is left untouched.
+//!
+//! ```
+//! use poster_generator::template;
+//! use serde_json::json;
+//!
+//! let vars = json!({ "price": "1299", "sold": 1234567 });
+//! let vars = vars.as_object().unwrap();
+//! assert_eq!(template::render_str("{{price | currency(\"CNY\")}}", vars), "¥1,299.00");
+//! assert_eq!(template::render_str("{{sold | thousands(\"de\")}} sold", vars), "1.234.567 sold");
+//! ```
+
+use crate::PosterConfig;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use num_format::{Grouping, Locale};
+use serde_json::{Map, Value};
+use std::fmt::{Display, Write};
+use tracing::warn;
+
+/// Template variables, keyed by name.
+pub type Variables = Map<String, Value>;
+
+/// Substitutes variables into a config template and deserializes the result.
+///
+/// Placeholders naming unknown variables are left untouched. [Bindings](crate::binding)
+/// to the config's `data` are resolved afterwards, then [translations](crate::locale)
+/// are picked and [expressions](crate::expr) in numeric fields are evaluated, so
+/// they can use the variables too.
+pub fn render_config(template: &Value, vars: &Variables) -> serde_json::Result<PosterConfig> {
+    let mut value = template.clone();
+    apply(&mut value, vars);
+    crate::preprocess(&mut value, vars)?;
+    serde_json::from_value(value)
+}
+
+/// Substitutes variables into every string within a JSON value, in place.
+pub fn apply(value: &mut Value, vars: &Variables) {
+    match value {
+        Value::String(s) => {
+            // A lone placeholder without filters takes the variable's value with its JSON type
+            if let Some(name) = sole_placeholder(s) {
+                if let Some(var) = vars.get(name) {
+                    *value = var.clone();
+                    return;
+                }
+            }
+            *s = render_str(s, vars);
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| apply(item, vars)),
+        Value::Object(map) => map.values_mut().for_each(|item| apply(item, vars)),
+        _ => {}
+    }
+}
+
+/// Replaces `{{name}}` placeholders in a string with variable values.
+///
+/// String variables are inserted verbatim and other JSON values use their JSON
+/// representation. Unknown placeholders are left untouched.
+pub fn render_str(template: &str, vars: &Variables) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len;
+        result.push_str(&rest[..start]);
+
+        match eval(&rest[start + 2..end], vars) {
+            Some(value) => result.push_str(&value_to_string(&value)),
+            None => result.push_str(&rest[start..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+// Returns the variable name if the whole string is exactly one placeholder without filters
+fn sole_placeholder(s: &str) -> Option<&str> {
+    let inner = s.trim().strip_prefix("{{")?.strip_suffix("}}")?;
+    if inner.contains("{{") || inner.contains("}}") || inner.contains('|') {
+        return None;
+    }
+    Some(inner.trim())
+}
+
+// Evaluates the inside of a placeholder: a variable name followed by `| filter(args)` steps.
+// Returns `None` if the placeholder should be left untouched.
+fn eval(expr: &str, vars: &Variables) -> Option<Value> {
+    let mut steps = split_outside_quotes(expr, '|').into_iter();
+    let name = steps.next()?.trim();
+    let mut value = match vars.get(name) {
+        Some(value) => value.clone(),
+        None if name == "now" => Value::String(Utc::now().to_rfc3339()),
+        None => return None,
+    };
+
+    for step in steps {
+        let (filter, args) = parse_filter(step.trim())?;
+        value = match apply_filter(filter, &args, &value) {
+            Ok(value) => value,
+            Err(err) => {
+                warn!("Leaving placeholder '{{{{{}}}}}' untouched: {}", expr.trim(), err);
+                return None;
+            }
+        };
+    }
+    Some(value)
+}
+
+// Splits on a separator, ignoring separators inside double-quoted strings
+fn split_outside_quotes(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c == separator && !in_quotes => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+// Parses `name` or `name(arg, "arg", ...)` into the filter name and its arguments
+fn parse_filter(step: &str) -> Option<(&str, Vec<String>)> {
+    let Some(open) = step.find('(') else {
+        return Some((step, Vec::new()));
+    };
+    let name = step[..open].trim();
+    let inner = step[open + 1..].trim_end().strip_suffix(')')?;
+    let args = if inner.trim().is_empty() {
+        Vec::new()
+    } else {
+        split_outside_quotes(inner, ',')
+            .into_iter()
+            .map(|arg| {
+                let arg = arg.trim();
+                arg.strip_prefix('"').and_then(|a| a.strip_suffix('"')).unwrap_or(arg).to_string()
+            })
+            .collect()
+    };
+    Some((name, args))
+}
+
+fn apply_filter(filter: &str, args: &[String], value: &Value) -> Result<Value, String> {
+    let formatted = match filter {
+        "thousands" => {
+            let locale = locale_arg(args.first())?;
+            format_number(&number_text(value)?, None, &locale)
+        }
+        "fixed" => {
+            let digits = digits_arg(args.first())?.ok_or("fixed needs a number of digits")?;
+            format!("{:.*}", digits, number(value)?)
+        }
+        "percent" => {
+            let digits = digits_arg(args.first())?.unwrap_or(0);
+            format!("{:.*}%", digits, number(value)? * 100.0)
+        }
+        "currency" => {
+            let code = args.first().ok_or("currency needs a currency code")?.to_ascii_uppercase();
+            let locale = locale_arg(args.get(1))?;
+            let (symbol, decimals) = currency_symbol(&code);
+            let amount = number(value)?;
+            let digits = format_number(&amount.abs().to_string(), Some(decimals), &locale);
+            let sign = if amount < 0.0 { locale.minus_sign() } else { "" };
+            format!("{}{}{}", sign, symbol, digits)
+        }
+        "format" => {
+            let pattern = args.first().ok_or("format needs a pattern")?;
+            let timezone = args
+                .get(1)
+                .map(|name| name.parse::<Tz>().map_err(|_| format!("unknown time zone '{}'", name)))
+                .transpose()?;
+            format_date(value, pattern, timezone)?
+        }
+        _ => return Err(format!("unknown filter '{}'", filter)),
+    };
+    Ok(Value::String(formatted))
+}
+
+fn format_date(value: &Value, pattern: &str, timezone: Option<Tz>) -> Result<String, String> {
+    // chrono panics when displaying an invalid pattern, so check it first
+    if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("invalid date format '{}'", pattern));
+    }
+
+    let text = match value {
+        Value::Number(_) => None,
+        Value::String(s) => Some(s.trim()),
+        other => return Err(format!("{} is not a date", other)),
+    };
+
+    if let Some(text) = text {
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+            return format_in_zone(datetime, pattern, timezone);
+        }
+        let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+            .or_else(|| {
+                NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            });
+        if let Some(naive) = naive {
+            return write_date(naive.format(pattern), pattern);
+        }
+    }
+
+    // Anything else must be a Unix timestamp in seconds
+    let seconds = number(value).map_err(|_| format!("'{}' is not a date", value_to_string(value)))?;
+    let datetime = DateTime::from_timestamp(seconds.floor() as i64, ((seconds - seconds.floor()) * 1e9) as u32)
+        .ok_or_else(|| format!("timestamp {} is out of range", seconds))?;
+    format_in_zone(datetime, pattern, timezone)
+}
+
+fn format_in_zone<Z: TimeZone>(datetime: DateTime<Z>, pattern: &str, timezone: Option<Tz>) -> Result<String, String>
+where
+    Z::Offset: Display,
+{
+    match timezone {
+        Some(timezone) => write_date(datetime.with_timezone(&timezone).format(pattern), pattern),
+        None => write_date(datetime.format(pattern), pattern),
+    }
+}
+
+// chrono fails to display items the value doesn't have, e.g. the offset (`%z`) of
+// a date written without one, and `to_string` would panic on that
+fn write_date(formatted: impl Display, pattern: &str) -> Result<String, String> {
+    let mut text = String::new();
+    write!(text, "{}", formatted).map_err(|_| format!("date format '{}' needs a time zone offset", pattern))?;
+    Ok(text)
+}
+
+fn number(value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Number(n) => n.as_f64().ok_or_else(|| format!("{} is not a finite number", n)),
+        Value::String(s) => s.trim().parse().map_err(|_| format!("'{}' is not a number", s)),
+        other => Err(format!("{} is not a number", other)),
+    }
+}
+
+// The number as written, so that the digits of numeric strings are kept as they are
+fn number_text(value: &Value) -> Result<String, String> {
+    let n = number(value)?;
+    Ok(match value {
+        Value::String(s) => s.trim().trim_start_matches('+').to_string(),
+        _ if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", n as i64),
+        _ => n.to_string(),
+    })
+}
+
+fn digits_arg(arg: Option<&String>) -> Result<Option<usize>, String> {
+    arg.map(|arg| arg.parse().map_err(|_| format!("'{}' is not a number of digits", arg)))
+        .transpose()
+}
+
+fn locale_arg(arg: Option<&String>) -> Result<Locale, String> {
+    match arg {
+        Some(name) => Locale::from_name(name).map_err(|_| format!("unknown locale '{}'", name)),
+        None => Ok(Locale::en),
+    }
+}
+
+// Groups the integer digits of a plain decimal number ("-1234.5") and applies the
+// locale's separators, optionally padding or rounding to a number of decimals
+fn format_number(text: &str, decimals: Option<usize>, locale: &Locale) -> String {
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(digits) => (locale.minus_sign(), digits),
+        None => ("", text),
+    };
+    let (int_part, frac_part) = match decimals {
+        Some(decimals) => {
+            let rounded = format!("{:.*}", decimals, digits.parse::<f64>().unwrap_or(0.0));
+            let (int_part, frac_part) = rounded.split_once('.').unwrap_or((&rounded, ""));
+            (int_part.to_string(), frac_part.to_string())
+        }
+        None => {
+            let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+            (int_part.to_string(), frac_part.to_string())
+        }
+    };
+
+    let mut result = format!("{}{}", sign, group_digits(&int_part, locale.separator(), locale.grouping()));
+    if !frac_part.is_empty() {
+        result.push_str(locale.decimal());
+        result.push_str(&frac_part);
+    }
+    result
+}
+
+fn group_digits(digits: &str, separator: &str, grouping: Grouping) -> String {
+    // Group sizes from the right: 3,3,3... or 3,2,2... (Indian)
+    let (first, rest) = match grouping {
+        Grouping::Standard => (3, 3),
+        Grouping::Indian => (3, 2),
+        Grouping::Posix => return digits.to_string(),
+    };
+
+    let chars: Vec<char> = digits.chars().collect();
+    let mut groups = Vec::new();
+    let mut end = chars.len();
+    let mut size = first;
+    while end > size {
+        groups.push(chars[end - size..end].iter().collect::<String>());
+        end -= size;
+        size = rest;
+    }
+    groups.push(chars[..end].iter().collect::<String>());
+    groups.reverse();
+    groups.join(separator)
+}
+
+// Symbol and number of minor unit digits for common currencies
+fn currency_symbol(code: &str) -> (String, usize) {
+    match code {
+        "CNY" | "RMB" => ("¥".to_string(), 2),
+        "JPY" => ("¥".to_string(), 0),
+        "USD" => ("$".to_string(), 2),
+        "EUR" => ("€".to_string(), 2),
+        "GBP" => ("£".to_string(), 2),
+        "HKD" => ("HK$".to_string(), 2),
+        "TWD" => ("NT$".to_string(), 0),
+        "KRW" => ("₩".to_string(), 0),
+        "INR" => ("₹".to_string(), 2),
+        "RUB" => ("₽".to_string(), 2),
+        "TRY" => ("₺".to_string(), 2),
+        "KZT" => ("₸".to_string(), 2),
+        other => (format!("{} ", other), 2),
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,217 @@
+//! Image comparison helpers for golden-image tests.
+//!
+//! Rendering the same poster on different platforms gives slightly different
+//! pixels, mostly along glyph edges. These helpers compare PNGs with a
+//! perceptual color distance and ignore pixels that match a neighbor in the
+//! other image, so such differences pass while real regressions don't.
+//!
+//! ```no_run
+//! use poster_generator::testing::assert_similar_to_file;
+//! use poster_generator::PosterGenerator;
+//!
+//! let generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+//! let png = generator.generate().unwrap();
+//! assert_similar_to_file(&png, "tests/golden/blank.png", 0.001);
+//! ```
+//!
+//! On failure, a difference image is written to the directory named by the
+//! `POSTER_DIFF_DIR` environment variable (the system temp directory by
+//! default), with differing pixels in red over a faded copy of the expected
+//! image. Set `POSTER_UPDATE_GOLDEN=1` to create or rewrite golden files instead;
+//! without it, a missing golden file fails the test.
+
+use crate::{PosterError, Result};
+use skia_safe::{image::CachingHint, images, AlphaType, ColorType, Data, EncodedImageFormat, Image, ImageInfo};
+use std::path::{Path, PathBuf};
+
+// Perceptual distance above which two pixels count as different, from 0 to 1
+const PIXEL_TOLERANCE: f32 = 0.1;
+
+// Largest possible YIQ distance, between black and white
+const MAX_YIQ_DELTA: f32 = 35215.0;
+
+/// Result of comparing two images of the same size.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    /// Width of the images.
+    pub width: u32,
+    /// Height of the images.
+    pub height: u32,
+    /// Number of pixels that differ perceptibly.
+    pub different_pixels: usize,
+    /// PNG highlighting the differing pixels in red.
+    pub diff_png: Vec<u8>,
+}
+
+impl Comparison {
+    /// Fraction of pixels that differ, from 0 to 1.
+    pub fn difference(&self) -> f32 {
+        let total = self.width as usize * self.height as usize;
+        if total == 0 {
+            0.0
+        } else {
+            self.different_pixels as f32 / total as f32
+        }
+    }
+}
+
+/// Compares two PNG (or other encoded) images pixel by pixel.
+///
+/// # Errors
+///
+/// Returns an error if an image can't be decoded or the sizes differ.
+pub fn compare(actual: &[u8], expected: &[u8]) -> Result<Comparison> {
+    let actual = Pixels::decode(actual, "actual")?;
+    let expected = Pixels::decode(expected, "expected")?;
+    if (actual.width, actual.height) != (expected.width, expected.height) {
+        return Err(PosterError::RenderError(format!(
+            "image sizes differ: {}x{} (actual) vs {}x{} (expected)",
+            actual.width, actual.height, expected.width, expected.height
+        )));
+    }
+
+    let mut different_pixels = 0;
+    let mut diff = Vec::with_capacity(expected.data.len());
+    for y in 0..expected.height {
+        for x in 0..expected.width {
+            let differs = !actual.matches_near(x, y, expected.pixel(x, y))
+                && !expected.matches_near(x, y, actual.pixel(x, y));
+            if differs {
+                different_pixels += 1;
+                diff.extend_from_slice(&[255, 0, 0, 255]);
+            } else {
+                // Faded grayscale copy of the expected image for context
+                let [r, g, b, _] = blend_white(expected.pixel(x, y));
+                let gray = (0.299 * r + 0.587 * g + 0.114 * b) as u8;
+                let faded = 255 - (255 - gray) / 4;
+                diff.extend_from_slice(&[faded, faded, faded, 255]);
+            }
+        }
+    }
+
+    Ok(Comparison {
+        width: expected.width,
+        height: expected.height,
+        different_pixels,
+        diff_png: encode_rgba(expected.width, expected.height, diff)?,
+    })
+}
+
+/// Panics unless at most `threshold` (a fraction from 0 to 1) of the pixels differ.
+///
+/// The difference image is written to `POSTER_DIFF_DIR` on failure.
+#[track_caller]
+pub fn assert_similar(actual: &[u8], expected: &[u8], threshold: f32) {
+    assert_similar_named(actual, expected, threshold, "poster");
+}
+
+/// Compares against a golden file, panicking if it doesn't exist.
+///
+/// With `POSTER_UPDATE_GOLDEN=1`, the golden file is written instead.
+#[track_caller]
+pub fn assert_similar_to_file<P: AsRef<Path>>(actual: &[u8], golden: P, threshold: f32) {
+    let golden = golden.as_ref();
+    let update = std::env::var("POSTER_UPDATE_GOLDEN").is_ok_and(|value| value == "1");
+    if !update && !golden.exists() {
+        panic!("golden file {} is missing, run with POSTER_UPDATE_GOLDEN=1 to create it", golden.display());
+    }
+    if update {
+        if let Some(dir) = golden.parent() {
+            std::fs::create_dir_all(dir).expect("failed to create golden directory");
+        }
+        std::fs::write(golden, actual).expect("failed to write golden file");
+        return;
+    }
+
+    let expected = std::fs::read(golden).unwrap_or_else(|e| panic!("failed to read {}: {}", golden.display(), e));
+    let name = golden.file_stem().map_or("poster".into(), |stem| stem.to_string_lossy());
+    assert_similar_named(actual, &expected, threshold, &name);
+}
+
+#[track_caller]
+fn assert_similar_named(actual: &[u8], expected: &[u8], threshold: f32, name: &str) {
+    let comparison = compare(actual, expected).unwrap_or_else(|e| panic!("images can't be compared: {}", e));
+    if comparison.difference() <= threshold {
+        return;
+    }
+
+    let diff_path = write_diff(name, &comparison);
+    panic!(
+        "images differ in {} pixels ({:.3}%, threshold {:.3}%), difference image: {}",
+        comparison.different_pixels,
+        comparison.difference() * 100.0,
+        threshold * 100.0,
+        diff_path.map_or("not written".to_string(), |path| path.display().to_string()),
+    );
+}
+
+// Write the difference image next to the other diffs, returning its path
+fn write_diff(name: &str, comparison: &Comparison) -> Option<PathBuf> {
+    let dir = std::env::var_os("POSTER_DIFF_DIR").map_or_else(std::env::temp_dir, PathBuf::from);
+    std::fs::create_dir_all(&dir).ok()?;
+    let path = dir.join(format!("{}.diff.png", name));
+    std::fs::write(&path, &comparison.diff_png).ok()?;
+    Some(path)
+}
+
+// Decoded unpremultiplied RGBA pixels
+struct Pixels {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Pixels {
+    fn decode(bytes: &[u8], which: &str) -> Result<Self> {
+        let image = Image::from_encoded(Data::new_copy(bytes))
+            .ok_or_else(|| PosterError::ImageLoadError(format!("failed to decode {} image", which)))?;
+        let (width, height) = (image.width() as u32, image.height() as u32);
+        let info = ImageInfo::new((width as i32, height as i32), ColorType::RGBA8888, AlphaType::Unpremul, None);
+        let mut data = vec![0u8; width as usize * height as usize * 4];
+        if !image.read_pixels(&info, &mut data, width as usize * 4, (0, 0), CachingHint::Allow) {
+            return Err(PosterError::ImageLoadError(format!("failed to read {} image pixels", which)));
+        }
+        Ok(Self { width, height, data })
+    }
+
+    fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [self.data[i], self.data[i + 1], self.data[i + 2], self.data[i + 3]]
+    }
+
+    // Whether the pixel at (x, y) or one of its neighbors looks like `color`,
+    // which tolerates edges that moved by a pixel
+    fn matches_near(&self, x: u32, y: u32, color: [u8; 4]) -> bool {
+        let xs = x.saturating_sub(1)..=(x + 1).min(self.width - 1);
+        xs.flat_map(|nx| (y.saturating_sub(1)..=(y + 1).min(self.height - 1)).map(move |ny| (nx, ny)))
+            .any(|(nx, ny)| perceptual_delta(self.pixel(nx, ny), color) <= PIXEL_TOLERANCE)
+    }
+}
+
+// Color distance in YIQ space, weighted as in pixelmatch, from 0 to 1
+fn perceptual_delta(a: [u8; 4], b: [u8; 4]) -> f32 {
+    let [r1, g1, b1, _] = blend_white(a);
+    let [r2, g2, b2, _] = blend_white(b);
+    let (dr, dg, db) = (r1 - r2, g1 - g2, b1 - b2);
+    let y = dr * 0.298_895_31 + dg * 0.586_622_47 + db * 0.114_482_23;
+    let i = dr * 0.595_977_99 - dg * 0.274_176_47 - db * 0.321_801_52;
+    let q = dr * 0.211_470_17 - dg * 0.522_617_29 + db * 0.311_147_12;
+    (0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q) / MAX_YIQ_DELTA
+}
+
+// Composite a pixel over white, so transparent pixels compare by how they look
+fn blend_white([r, g, b, a]: [u8; 4]) -> [f32; 4] {
+    let alpha = a as f32 / 255.0;
+    let blend = |c: u8| 255.0 + (c as f32 - 255.0) * alpha;
+    [blend(r), blend(g), blend(b), 255.0]
+}
+
+fn encode_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Result<Vec<u8>> {
+    let info = ImageInfo::new((width as i32, height as i32), ColorType::RGBA8888, AlphaType::Unpremul, None);
+    let image = images::raster_from_data(&info, Data::new_copy(&pixels), width as usize * 4)
+        .ok_or_else(|| PosterError::Encode("failed to create difference image".to_string()))?;
+    let data = image
+        .encode_to_data(EncodedImageFormat::PNG)
+        .ok_or_else(|| PosterError::Encode("failed to encode difference image".to_string()))?;
+    Ok(data.as_bytes().to_vec())
+}
@@ -0,0 +1,58 @@
+//! Design tokens shared by the elements of a poster.
+//!
+//! A config's `theme` maps token names to values. Any string in the elements
+//! that is exactly `$name` is replaced by the token's value when the poster is
+//! rendered:
+//!
+//! ```json
+//! "theme": { "primary": "#e53935", "surface": "#fafafa", "heading-font": "Inter" },
+//! "elements": [
+//!     { "type": "text", "text": "Sale", "x": 40, "y": 80, "color": "$primary", "font_family": "$heading-font" }
+//! ]
+//! ```
+//!
+//! Tokens work in string fields such as colors, font families and image sources,
+//! and in the poster's `background_color`. Strings naming unknown tokens are left
+//! as they are.
+//!
+//! Elements keep their tokens, so the same poster can be rendered with another
+//! theme without touching its elements:
+//!
+//! ```no_run
+//! use poster_generator::theme::Theme;
+//! use poster_generator::{PosterConfig, PosterGenerator};
+//!
+//! # let config: PosterConfig = unimplemented!();
+//! let mut generator = PosterGenerator::from_config(config);
+//! let dark = Theme::from([("primary".to_string(), "#ff8a80".to_string()), ("surface".to_string(), "#212121".to_string())]);
+//! generator.set_theme(dark);
+//! let png = generator.generate().expect("Failed to render");
+//! ```
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Token values, keyed by token name (without the `$`).
+pub type Theme = HashMap<String, String>;
+
+/// The value of the token a `$name` string refers to, or the string itself.
+pub fn resolve<'a>(s: &'a str, theme: &'a Theme) -> &'a str {
+    s.strip_prefix('$').and_then(|name| theme.get(name)).map_or(s, String::as_str)
+}
+
+/// Replaces every string of the form `$name` naming a token with the token's value.
+/// Returns whether anything was replaced.
+pub fn apply(value: &mut Value, theme: &Theme) -> bool {
+    match value {
+        Value::String(s) => match s.strip_prefix('$').and_then(|name| theme.get(name)) {
+            Some(token) => {
+                *s = token.clone();
+                true
+            }
+            None => false,
+        },
+        Value::Array(items) => items.iter_mut().fold(false, |replaced, item| apply(item, theme) | replaced),
+        Value::Object(map) => map.values_mut().fold(false, |replaced, item| apply(item, theme) | replaced),
+        _ => false,
+    }
+}
@@ -0,0 +1,76 @@
+//! Quality problems found while rendering, reported without failing the render.
+//!
+//! [`PosterGenerator::generate_with_warnings`] returns them alongside the PNG,
+//! so template authors and servers can point out elements that fell off the
+//! canvas, text that didn't fit, missing fonts and blurry images:
+//!
+//! ```no_run
+//! use poster_generator::PosterGenerator;
+//!
+//! let generator = PosterGenerator::new(800, 600, "#ffffff".to_string());
+//! let (png, warnings) = generator.generate_with_warnings().expect("Failed to generate");
+//! for warning in &warnings {
+//!     eprintln!("{}", warning);
+//! }
+//! ```
+//!
+//! [`PosterGenerator::generate_with_warnings`]: crate::PosterGenerator::generate_with_warnings
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Scale above which an image is reported as upscaled, and likely blurry.
+pub const MAX_UPSCALE: f32 = 2.0;
+
+/// A quality problem with one element of a rendered poster.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RenderWarning {
+    /// Position of the element in the order it was added.
+    pub index: usize,
+    /// The element's `id`, if it has one.
+    pub id: Option<String>,
+    /// What is wrong with the element.
+    #[serde(flatten)]
+    pub kind: WarningKind,
+}
+
+/// Kind of quality problem found while rendering.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WarningKind {
+    /// The element is entirely outside the canvas, so none of it is visible.
+    OffCanvas,
+    /// The text didn't fit in its box, before the element's `overflow` setting was applied.
+    TextOverflow,
+    /// The font family isn't installed or sent with the poster; `fallback` was drawn instead.
+    FontFallback {
+        /// Requested font family.
+        family: String,
+        /// Family of the font used in its place.
+        fallback: String,
+    },
+    /// The image is drawn at more than [`MAX_UPSCALE`] times its pixel size.
+    ImageUpscaled {
+        /// Source of the image.
+        src: String,
+        /// Scale the image is drawn at.
+        scale: f32,
+    },
+}
+
+impl fmt::Display for RenderWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Element {}", self.index)?;
+        if let Some(id) = &self.id {
+            write!(f, " ({})", id)?;
+        }
+        match &self.kind {
+            WarningKind::OffCanvas => write!(f, " is outside the canvas"),
+            WarningKind::TextOverflow => write!(f, " has text that doesn't fit its box"),
+            WarningKind::FontFallback { family, fallback } => {
+                write!(f, " uses {} in place of the missing font {}", fallback, family)
+            }
+            WarningKind::ImageUpscaled { src, scale } => write!(f, " upscales {} {:.1}x", src, scale),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Golden-image tests of the built-in elements.
+//!
+//! The posters only use shapes and images on whole pixels, so they render the
+//! same on every platform. Text is left out: its pixels depend on the fonts
+//! installed. After an intended rendering change, run the tests with
+//! `POSTER_UPDATE_GOLDEN=1` to rewrite the files in `tests/golden`.
+
+use poster_generator::render_config;
+use poster_generator::testing::assert_similar_to_file;
+use serde_json::{json, Value};
+
+const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+// Render a small white poster with the given elements, loading images from the golden directory
+fn render(elements: Value) -> Vec<u8> {
+    let config = json!({
+        "width": 48,
+        "height": 40,
+        "background_color": "#ffffff",
+        "base_path": GOLDEN_DIR,
+        "elements": elements,
+    });
+    render_config(&config.to_string()).expect("Failed to render")
+}
+
+#[test]
+fn image_stretched() {
+    let png = render(json!([
+        { "type": "image", "src": "swatch.png", "x": 8, "y": 8, "width": 32, "height": 24, "object_fit": "stretch" }
+    ]));
+    assert_similar_to_file(&png, format!("{}/image.png", GOLDEN_DIR), 0.005);
+}
+
+#[test]
+fn rects_in_z_order() {
+    let png = render(json!([
+        { "type": "rect", "x": 4, "y": 24, "width": 20, "height": 12, "fill": "#457b9d", "z_index": 1 },
+        { "type": "rect", "x": 4, "y": 4, "width": 40, "height": 16, "fill": "#1d3557" }
+    ]));
+    assert_similar_to_file(&png, format!("{}/rect.png", GOLDEN_DIR), 0.005);
+}
@@ -0,0 +1,37 @@
+//! Tests of config elements that fall back to the custom element registry.
+
+use poster_generator::{render_config, PosterError};
+use serde_json::json;
+
+#[test]
+fn malformed_builtin_reports_field_error() {
+    let config = json!({
+        "width": 40,
+        "height": 40,
+        "background_color": "#ffffff",
+        "elements": [{ "type": "generative", "style": "spiral" }],
+    });
+    match render_config(&config.to_string()) {
+        Err(PosterError::ElementRender { index: 0, source }) => match *source {
+            PosterError::InvalidConfig(err) => assert!(err.to_string().contains("spiral"), "{}", err),
+            other => panic!("expected an invalid config error, got {:?}", other),
+        },
+        other => panic!("expected an element error, got {:?}", other.map(|png| png.len())),
+    }
+}
+
+#[test]
+fn unknown_type_reported_when_rendered() {
+    let config = json!({
+        "width": 40,
+        "height": 40,
+        "background_color": "#ffffff",
+        "elements": [{ "type": "sticker", "z_index": 2 }],
+    });
+    match render_config(&config.to_string()) {
+        Err(PosterError::ElementRender { index: 0, source }) => {
+            assert!(matches!(*source, PosterError::UnknownElementType(ref kind) if kind == "sticker"), "{:?}", source)
+        }
+        other => panic!("expected an element error, got {:?}", other.map(|png| png.len())),
+    }
+}
@@ -0,0 +1,18 @@
+//! Tests of template placeholders and their filters.
+
+use poster_generator::template::render_str;
+use serde_json::json;
+
+#[test]
+fn date_offset_of_naive_date_left_untouched() {
+    let vars = json!({ "date": "2024-05-01 09:30" });
+    let text = "{{date | format(\"%H:%M %z\")}}";
+    assert_eq!(render_str(text, vars.as_object().unwrap()), text);
+}
+
+#[test]
+fn date_offset_of_rfc3339_date() {
+    let vars = json!({ "date": "2024-05-01T09:30:00+08:00" });
+    let text = "{{date | format(\"%H:%M %:z\")}}";
+    assert_eq!(render_str(text, vars.as_object().unwrap()), "09:30 +08:00");
+}